@@ -1,10 +1,13 @@
 mod scenes;
 mod ray_tracer;
 mod scene;
+mod options;
+mod post_process;
 mod tests;
 
 use std::{env};
 use std::process::exit;
+use image::DynamicImage;
 use scenes::clover_scene::CloverScene;
 use crate::scene::Scene;
 use crate::scenes::basic_refraction_scene::BasicRefractionScene;
@@ -15,49 +18,76 @@ use crate::scenes::cube_scene::CubeScene;
 use crate::scenes::cylinder_scene::CylinderScene;
 use crate::scenes::dragon_scene::DragonScene;
 use crate::scenes::group_scene::GroupScene;
+use crate::scenes::instances_scene::InstancesScene;
 use crate::scenes::menger_scene::{MengerCastleScene, MengerSpongeScene};
+use crate::scenes::metaballs_scene::MetaballsScene;
 use crate::scenes::patterns_scene::PatternsScene;
+use crate::scenes::quadric_scene::QuadricScene;
 use crate::scenes::refraction_sphere_scene::RefractionSphereScene;
 use crate::scenes::smooth_teapot_scene::SmoothTeaPotScene;
 use crate::scenes::stripe_pattern_scene::StripePatternScene;
+use crate::scenes::superellipsoid_scene::SuperellipsoidScene;
 use crate::scenes::teapot_scene::TeaPotScene;
+use crate::scenes::voxel_scene::VoxelScene;
+use crate::options::RenderOptions;
 
 fn main() {
     let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("denoise") {
+        if let Err(error) = post_process::denoise(&args[2..]) {
+            println!("Error: {}", error);
+            println!("{}", RenderOptions::USAGE);
+            exit(1);
+        }
+        exit(0);
+    }
+    let options = match RenderOptions::parse(&args) {
+        Ok(options) => options,
+        Err(error) => {
+            println!("Error: {}", error);
+            println!("{}", RenderOptions::USAGE);
+            exit(1);
+        }
+    };
 
     if args.contains(&String::from("all")) {
-        render_all();
+        render_all(&options);
         exit(0);
     }
     let nb_threads = num_cpus::get() - 1;
     rayon::ThreadPoolBuilder::new().num_threads(nb_threads).build_global().unwrap();
 
     println!("Start... {} threads", nb_threads);
-    render(&MengerCastleScene{},800, 800, "./img/menger_castle_scene.png");
+    render(&MengerCastleScene{},800, 800, "./img/menger_castle_scene.png", &options);
     println!("Done.")
 }
 
-fn render_all() {
-    render(&CloverScene::new(0.0, 0.0, -9.0), 800, 600, "./img/clover.png");
-    render(&CloverTriangleScene::new(0.0, 0.0, -9.0),800, 600, "./img/clover_triangle.png");
-    render(&CylinderScene{},400, 400, "./img/cylinders_scene.png");
-    render(&ConeScene{},400, 400, "./img/cones_scene.png");
-    render(&CubeScene{},400, 400, "./img/cubes_scene.png");
-    render(&GroupScene{},400, 400, "./img/group_scene.png");
-    render(&PatternsScene{},400, 400, "./img/all_patterns_scene.png");
-    render(&StripePatternScene{},400, 400, "./img/pattern_stripe_scene.png");
-    render(&BasicRefractionScene{},640, 400, "./img/basic_refraction_sphere_scene.png");
-    render(&RefractionSphereScene{},400, 400, "./img/refraction_sphere_scene.png");
-    render(&TeaPotScene{},400, 400, "./img/teapot.png");
-    render(&DragonScene { }, 800, 600, "./img/dragon.png");
-    render(&SmoothTeaPotScene{},400, 400, "./img/teapot_smooth.png");
-    render(&CsgScene{},400, 400, "./img/csg_scene.png");
-    render(&MengerSpongeScene{},400, 400, "./img/menger_sponge_scene.png");
+fn render_all(options: &RenderOptions) {
+    render(&CloverScene::new(0.0, 0.0, -9.0), 800, 600, "./img/clover.png", options);
+    render(&CloverTriangleScene::new(0.0, 0.0, -9.0),800, 600, "./img/clover_triangle.png", options);
+    render(&CylinderScene{},400, 400, "./img/cylinders_scene.png", options);
+    render(&ConeScene{},400, 400, "./img/cones_scene.png", options);
+    render(&CubeScene{},400, 400, "./img/cubes_scene.png", options);
+    render(&GroupScene{},400, 400, "./img/group_scene.png", options);
+    render(&PatternsScene{},400, 400, "./img/all_patterns_scene.png", options);
+    render(&StripePatternScene{},400, 400, "./img/pattern_stripe_scene.png", options);
+    render(&BasicRefractionScene{},640, 400, "./img/basic_refraction_sphere_scene.png", options);
+    render(&RefractionSphereScene{},400, 400, "./img/refraction_sphere_scene.png", options);
+    render(&TeaPotScene{},400, 400, "./img/teapot.png", options);
+    render(&DragonScene { }, 800, 600, "./img/dragon.png", options);
+    render(&SmoothTeaPotScene{},400, 400, "./img/teapot_smooth.png", options);
+    render(&CsgScene{},400, 400, "./img/csg_scene.png", options);
+    render(&MengerSpongeScene{},400, 400, "./img/menger_sponge_scene.png", options);
+    render(&VoxelScene{},400, 400, "./img/voxel_scene.png", options);
+    render(&MetaballsScene{},400, 400, "./img/metaballs_scene.png", options);
+    render(&SuperellipsoidScene{},400, 400, "./img/superellipsoid_scene.png", options);
+    render(&QuadricScene{},400, 400, "./img/quadric_scene.png", options);
+    render(&InstancesScene{},400, 400, "./img/instances_scene.png", options);
 }
 
-fn render(scene : &dyn Scene, h_size : usize, v_size : usize, file_path: &str) {
-    scene.render(h_size, v_size, file_path);
-    let image = image::open(file_path).expect("Failed to open image");
+fn render(scene : &dyn Scene, h_size : usize, v_size : usize, file_path: &str, options: &RenderOptions) {
+    let canvas = scene.render(h_size, v_size, file_path, options);
+    let image = DynamicImage::ImageRgb8(canvas.to_rgb_image(&options.tone_mapper));
     let config = &artem::config::ConfigBuilder::new().border(true).build();
     let ascii_art = artem::convert(image, config);
     println!("{}", ascii_art);
@@ -0,0 +1,51 @@
+use ray_tracer_lib::aov::Aov;
+use ray_tracer_lib::background::Background;
+use ray_tracer_lib::tone_mapping::{ToneMapper, ToneMapping};
+
+// The options of the command line which change how the scenes are rendered and saved.
+pub struct RenderOptions {
+    // the exposure, the tone mapping operator and the sRGB encoding of the 8-bit images
+    pub tone_mapper: ToneMapper,
+    // replaces the background of the scenes
+    pub background: Option<Background>,
+    // the output variables saved next to the image, as <name>.<variable>.<extension>
+    pub aovs: Vec<Aov>,
+    // the image is denoised, guided by its normal, depth and albedo
+    pub denoise: bool,
+}
+
+impl RenderOptions {
+    pub const USAGE: &'static str = "Usage: ray-tracer-cli [all] [--exposure <stops>] [--tone-map clamp|reinhard|reinhard=<white>|filmic|aces] [--srgb]
+                      [--background transparent|r,g,b|r,g,b:r,g,b] [--aov all|depth,normal,object_id,albedo,shadow]
+                      [--denoise]
+       ray-tracer-cli denoise <image> <output> [--normal <image>] [--depth <image>] [--albedo <image>]";
+
+    pub fn parse(args: &[String]) -> Result<RenderOptions, String> {
+        let mut options = RenderOptions { tone_mapper: ToneMapper::new(), background: None, aovs: vec![], denoise: false };
+        let mut args = args.iter().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+            match arg.as_str() {
+                "--exposure" => {
+                    let exposure = value()?;
+                    options.tone_mapper.exposure = exposure.parse().map_err(|_| format!("invalid exposure '{}'", exposure))?;
+                }
+                "--tone-map" => options.tone_mapper.operator = value()?.parse::<ToneMapping>()?,
+                "--background" => options.background = Some(value()?.parse::<Background>()?),
+                "--aov" => {
+                    let aovs = value()?;
+                    options.aovs = if aovs == "all" {
+                        Aov::ALL.to_vec()
+                    } else {
+                        aovs.split(',').map(|aov| aov.parse::<Aov>()).collect::<Result<_, _>>()?
+                    };
+                }
+                "--srgb" => options.tone_mapper.srgb = true,
+                "--denoise" => options.denoise = true,
+                "all" => {}
+                _ => return Err(format!("unknown option '{}'", arg)),
+            }
+        }
+        Ok(options)
+    }
+}
@@ -0,0 +1,44 @@
+use ray_tracer_lib::canvas::Canvas;
+use ray_tracer_lib::denoise::{DenoiseGuides, Denoiser};
+use ray_tracer_lib::tone_mapping::ToneMapper;
+use crate::scene::save;
+
+// An image saved by the ray tracer: the .hdr and .pfm ones with their values, the others
+// with their 8-bit values in [0, 1], as they were written, and their alpha if they have one.
+pub fn load(file_path: &str) -> Result<Canvas, String> {
+    Canvas::load_image(file_path, false).map_err(|error| format!("{}: {}", file_path, error))
+}
+
+// denoise <image> <output> [--normal <image>] [--depth <image>] [--albedo <image>]
+// The guides are the raw variables of the render, saved in .hdr or .pfm images: the 8-bit ones
+// are remapped to be seen and no longer hold the normals and depths.
+pub fn denoise(args: &[String]) -> Result<(), String> {
+    let (input, output) = match args {
+        [input, output, ..] => (input, output),
+        _ => return Err("denoise needs an image and an output".to_string()),
+    };
+    let image = load(input)?;
+    let (mut normal, mut depth, mut albedo) = (None, None, None);
+    let mut options = args[2..].iter();
+    while let Some(option) = options.next() {
+        let guide = match option.as_str() {
+            "--normal" => &mut normal,
+            "--depth" => &mut depth,
+            "--albedo" => &mut albedo,
+            _ => return Err(format!("unknown option '{}'", option)),
+        };
+        let path = options.next().ok_or(format!("missing image for {}", option))?;
+        if !(path.ends_with(".hdr") || path.ends_with(".pfm")) {
+            return Err(format!("{} is not a .hdr or .pfm image with the raw values of {}", path, option));
+        }
+        let canvas = load(path)?;
+        if canvas.width != image.width || canvas.height != image.height {
+            return Err(format!("{} is not as large as {}", path, input));
+        }
+        *guide = Some(canvas);
+    }
+
+    let guides = DenoiseGuides { normal: normal.as_ref(), depth: depth.as_ref(), albedo: albedo.as_ref() };
+    let denoised = Denoiser::new().denoise(&image, &guides).map_err(|error| error.to_string())?;
+    save(&denoised, output, &ToneMapper::new()).map_err(|error| format!("{}: {}", output, error))
+}
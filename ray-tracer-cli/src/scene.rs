@@ -1,13 +1,19 @@
+use std::io::Error;
+use ray_tracer_lib::aov::Aov;
 use ray_tracer_lib::camera::Camera;
+use ray_tracer_lib::canvas::Canvas;
 use ray_tracer_lib::colors::Color;
 use ray_tracer_lib::core::math::{Float, PI};
 use ray_tracer_lib::core::transform::view_transform;
 use ray_tracer_lib::core::tuple::{point, vector};
+use ray_tracer_lib::denoise::{DenoiseGuides, Denoiser};
 use ray_tracer_lib::lights::point_light::PointLight;
 use ray_tracer_lib::material::Material;
 use ray_tracer_lib::object::build_plane;
 use ray_tracer_lib::patterns::pattern::Pattern;
+use ray_tracer_lib::tone_mapping::ToneMapper;
 use ray_tracer_lib::world::World;
+use crate::options::RenderOptions;
 
 pub trait Scene {
     fn get_world(&self) -> World;
@@ -23,15 +29,41 @@ pub trait Scene {
         camera
     }
 
-    fn render(&self, h_size: usize, v_size: usize, file_path: &str) {
+    fn render(&self, h_size: usize, v_size: usize, file_path: &str, options: &RenderOptions) -> Canvas {
         let camera = self.get_camera(h_size, v_size);
-        let world = self.get_world();
-        let canvas = camera.render(&world, file_path);
-        let result = canvas.save_png(file_path);
-        match result {
+        let mut world = self.get_world();
+        if let Some(background) = options.background {
+            world.set_background(background);
+        }
+        let mut aov_list = options.aovs.clone();
+        if options.denoise {
+            aov_list.extend([Aov::Normal, Aov::Depth, Aov::Albedo]);
+        }
+        let (mut canvas, aovs) = camera.render_with_aovs(&world, file_path, &aov_list);
+        if options.denoise {
+            match Denoiser::new().denoise(&canvas, &DenoiseGuides::from_aovs(&aovs)) {
+                Ok(denoised) => { canvas = denoised }
+                Err(error) => { println!("Error: {}", error) }
+            }
+        }
+        match save(&canvas, file_path, &options.tone_mapper) {
             Ok(_) => { println!("Ok") }
             Err(error) => { println!("Error: {}", error) }
         }
+        // the 8-bit images of the variables are made to be seen, the .hdr and .pfm ones keep their values
+        let (stem, extension) = file_path.rsplit_once('.').unwrap_or((file_path, "png"));
+        for (aov, buffer) in aovs.iter().filter(|(aov, _)| options.aovs.contains(aov)) {
+            let aov_path = format!("{}.{}.{}", stem, aov.name(), extension);
+            let result = if extension == "hdr" || extension == "pfm" {
+                save(buffer, &aov_path, &options.tone_mapper)
+            } else {
+                save(&aov.display(buffer), &aov_path, &ToneMapper::new())
+            };
+            if let Err(error) = result {
+                println!("Error: {}: {}", aov_path, error)
+            }
+        }
+        canvas
     }
 
     fn init_world(&self, add_floor : bool) -> World {
@@ -52,4 +84,15 @@ pub trait Scene {
 
         world
     }
-}
\ No newline at end of file
+}
+
+// .hdr and .pfm keep the colors above 1.0
+pub fn save(canvas: &Canvas, file_path: &str, tone_mapper: &ToneMapper) -> Result<(), Error> {
+    if file_path.ends_with(".hdr") {
+        canvas.save_hdr(file_path)
+    } else if file_path.ends_with(".pfm") {
+        canvas.save_pfm(file_path)
+    } else {
+        canvas.save_png_tone_mapped(file_path, tone_mapper)
+    }
+}
@@ -41,7 +41,7 @@ impl Scene for ConeScene {
 
                 let matrix = &translation * &scaling;
                 cone.set_transformation(matrix);
-                cone.set_material(mat);
+                cone.set_material(mat.clone());
                 world.objects.push(cone);
             }
         }
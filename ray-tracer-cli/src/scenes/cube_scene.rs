@@ -42,7 +42,7 @@ impl Scene for CubeScene {
 
                 let matrix = &translation * &scaling;
                 cube.set_transformation(matrix);
-                cube.set_material(mat);
+                cube.set_material(mat.clone());
                 world.objects.push(cube);
             }
         }
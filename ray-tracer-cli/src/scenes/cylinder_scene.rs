@@ -38,7 +38,7 @@ impl Scene for CylinderScene {
 
                 let matrix = &translation * &scaling;
                 cyl.set_transformation(matrix);
-                cyl.set_material(mat);
+                cyl.set_material(mat.clone());
                 world.objects.push(cyl);
             }
         }
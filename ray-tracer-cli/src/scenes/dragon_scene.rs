@@ -1,5 +1,4 @@
 use std::env;
-use std::fs::File;
 use ray_tracer_lib::camera::Camera;
 use ray_tracer_lib::colors::Color;
 use ray_tracer_lib::core::math::PI;
@@ -17,11 +16,10 @@ impl Scene for DragonScene {
     fn get_world(&self) -> World {
         println!("{}", env::current_dir().unwrap().display());
 
+        // the models are cached in ./obj/dragon.obj.rtmesh
         let file_path = r"./obj/dragon.obj";
-        let file = File::open(file_path).unwrap();
-
-        let mut obj_reader = ObjReader::new(file);
-        obj_reader.read();
+        let mut obj_reader = ObjReader::open(file_path).unwrap();
+        obj_reader.read().unwrap();
 
         let dragon = obj_reader.models;
         let dragon_model = dragon.get("Default").unwrap();
@@ -32,15 +32,11 @@ impl GroupScene {
         return edge;
     }
 
-    fn hexagon_side(material : Material) -> Object {
+    fn hexagon_side() -> Object {
 
         let mut side = Group::new();
-        let mut corner = Self::hexagon_corner();
-        corner.set_material(material);
-        side.add(corner);
-        let mut edge = Self::hexagon_edge();
-        edge.set_material(material);
-        side.add(edge);
+        side.add(Self::hexagon_corner());
+        side.add(Self::hexagon_edge());
         Object::new_group(side)
     }
 
@@ -48,13 +44,16 @@ impl GroupScene {
         let mut hex = Group::new();
         const N: i32 = 6;
         for i in 0..N {
-            let mut side = Self::hexagon_side(material);
+            let mut side = Self::hexagon_side();
             let alpha = i as Float * (2.0 * PI / (N as Float));
             let rot_y = rotation_y(alpha);
             side.set_transformation(rot_y);
             hex.add(side);
         }
-        Object::new_group(hex)
+        // the corners and edges use the material of the hexagon
+        let mut hex = Object::new_group(hex);
+        hex.set_material(material);
+        hex
     }
 
 }
@@ -0,0 +1,50 @@
+use std::fs::File;
+use std::sync::Arc;
+use ray_tracer_lib::camera::Camera;
+use ray_tracer_lib::colors::Color;
+use ray_tracer_lib::core::math::{Float, PI};
+use ray_tracer_lib::core::transform::{rotation_x, rotation_y, scaling, translation};
+use ray_tracer_lib::material::Material;
+use ray_tracer_lib::obj_reader::ObjReader;
+use ray_tracer_lib::object::Object;
+use ray_tracer_lib::world::World;
+use crate::scene::Scene;
+
+pub struct InstancesScene {}
+
+impl Scene for InstancesScene {
+    fn get_world(&self) -> World {
+        let file_path = r"./obj/teapot-low.obj";
+        let file = File::open(file_path).unwrap();
+
+        let mut obj_reader = ObjReader::new(file);
+        obj_reader.read().unwrap();
+
+        let teapot_model = obj_reader.smooth_models.get("Teapot001").unwrap();
+        let mut teapot = Object::new_smooth_triangle_group(teapot_model.clone());
+        teapot.set_transformation(&scaling(0.05, 0.05, 0.05) * &rotation_x(-PI / 2.0));
+        // the triangles are loaded once and shared by all the instances
+        let teapot = Arc::new(teapot);
+
+        let mut world = self.init_world(true);
+        const N: usize = 5;
+        for i in 0..N {
+            for j in 0..N {
+                let mut material = Material::new();
+                material.color = Color::new(i as Float / N as Float, 0.3, j as Float / N as Float);
+                let mut instance = Object::new_instance(teapot.clone());
+                let t_x = (i as Float - N as Float / 2.0) * 1.5;
+                let t_z = (j as Float - N as Float / 2.0) * 1.5;
+                instance.set_transformation(&translation(t_x, 0.0, t_z) * &rotation_y((i * N + j) as Float * PI / 8.0));
+                instance.set_material(material);
+                world.objects.push(instance);
+            }
+        }
+
+        world
+    }
+
+    fn get_camera(&self, h_size: usize, v_size: usize) -> Camera {
+        self.init_camera(h_size, v_size, 0.0, 5.0, -7.0, 0.0, 0.0, 0.0)
+    }
+}
@@ -0,0 +1,40 @@
+use ray_tracer_lib::camera::Camera;
+use ray_tracer_lib::colors::Color;
+use ray_tracer_lib::core::math::PI;
+use ray_tracer_lib::core::transform::{rotation_y, translation};
+use ray_tracer_lib::core::tuple::point;
+use ray_tracer_lib::material::Material;
+use ray_tracer_lib::object::Object;
+use ray_tracer_lib::shapes::metaballs::{Metaball, Metaballs};
+use ray_tracer_lib::shapes::shape::Shape;
+use ray_tracer_lib::world::World;
+use crate::scene::Scene;
+
+pub struct MetaballsScene {}
+
+impl Scene for MetaballsScene {
+    fn get_world(&self) -> World {
+        let mut world = self.init_world(true);
+
+        // a water molecule: one big oxygen, two small hydrogens
+        let molecule = Metaballs::new(vec![
+            Metaball::new(point(0.0, 0.0, 0.0), 1.6, 1.0),
+            Metaball::new(point(0.95, -0.6, 0.0), 1.0, 1.0),
+            Metaball::new(point(-0.95, -0.6, 0.0), 1.0, 1.0),
+        ], 0.3);
+        let mut material = Material::new();
+        material.color = Color::new(0.2, 0.4, 1.0);
+        material.reflective = 0.3;
+        material.shininess = 300.0;
+        let mut object = Object::new(Shape::Metaballs(molecule));
+        object.set_transformation(&translation(0.0, 1.5, 0.0) * &rotation_y(PI / 8.0));
+        object.set_material(material);
+        world.objects.push(object);
+
+        world
+    }
+
+    fn get_camera(&self, h_size: usize, v_size: usize) -> Camera {
+        self.init_camera(h_size, v_size, 0.0, 2.5, -4.0, 0.0, 1.0, 0.0)
+    }
+}
@@ -13,3 +13,8 @@ pub mod dragon_scene;
 pub mod smooth_teapot_scene;
 pub mod csg_scene;
 pub mod menger_scene;
+pub mod voxel_scene;
+pub mod metaballs_scene;
+pub mod superellipsoid_scene;
+pub mod quadric_scene;
+pub mod instances_scene;
@@ -0,0 +1,38 @@
+use ray_tracer_lib::camera::Camera;
+use ray_tracer_lib::colors::Color;
+use ray_tracer_lib::core::transform::{scaling, translation};
+use ray_tracer_lib::material::Material;
+use ray_tracer_lib::object::Object;
+use ray_tracer_lib::shapes::quadric::Quadric;
+use ray_tracer_lib::shapes::shape::Shape;
+use ray_tracer_lib::world::World;
+use crate::scene::Scene;
+
+pub struct QuadricScene {}
+
+impl Scene for QuadricScene {
+    fn get_world(&self) -> World {
+        let mut world = self.init_world(true);
+
+        let quadrics = [
+            (Quadric::from(Quadric::paraboloid(), 0.0, 1.5, true), -2.5, 0.0, Color::red()),
+            (Quadric::from(Quadric::hyperboloid_one_sheet(), -1.5, 1.5, true), 0.0, 1.5, Color::green()),
+            (Quadric::from(Quadric::elliptic_cylinder(1.0, 0.4), 0.0, 2.0, true), 2.5, 0.0, Color::blue()),
+        ];
+        for (quadric, t_x, t_y, color) in quadrics {
+            let mut material = Material::new();
+            material.color = color;
+            material.reflective = 0.1;
+            let mut object = Object::new(Shape::Quadric(quadric));
+            object.set_transformation(&translation(t_x, t_y, 0.0) * &scaling(0.8, 1.0, 0.8));
+            object.set_material(material);
+            world.objects.push(object);
+        }
+
+        world
+    }
+
+    fn get_camera(&self, h_size: usize, v_size: usize) -> Camera {
+        self.init_camera(h_size, v_size, 0.0, 4.5, -7.0, 0.0, 1.0, 0.0)
+    }
+}
@@ -28,11 +28,11 @@ impl Scene for StripePatternScene {
 
         let mut middle = build_sphere();
         middle.set_transformation(translation(-0.5, 1.0, 0.5));
-        middle.set_material(material);
+        middle.set_material(material.clone());
 
         let mut right = build_sphere();
         right.set_transformation(&translation(1.0, 0.5, -0.5) * &scaling(0.5, 0.5, 0.5));
-        right.set_material(material);
+        right.set_material(material.clone());
 
         let mut left = build_sphere();
         left.set_transformation(&translation(-2.0, 0.33, -0.75) * &scaling(0.33, 0.33, 0.33));
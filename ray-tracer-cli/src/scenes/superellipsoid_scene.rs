@@ -0,0 +1,35 @@
+use ray_tracer_lib::camera::Camera;
+use ray_tracer_lib::colors::Color;
+use ray_tracer_lib::core::math::{Float, PI};
+use ray_tracer_lib::core::transform::{rotation_y, scaling, translation};
+use ray_tracer_lib::material::Material;
+use ray_tracer_lib::object::build_superellipsoid;
+use ray_tracer_lib::world::World;
+use crate::scene::Scene;
+
+pub struct SuperellipsoidScene {}
+
+impl Scene for SuperellipsoidScene {
+    fn get_world(&self) -> World {
+        let mut world = self.init_world(true);
+
+        // rounded box, pillow, star
+        let shapes = [(0.2, 0.2, Color::red()), (0.4, 1.0, Color::green()), (2.5, 2.5, Color::blue())];
+        for (i, (e1, e2, color)) in shapes.into_iter().enumerate() {
+            let mut material = Material::new();
+            material.color = color;
+            material.reflective = 0.2;
+            let mut object = build_superellipsoid(e1, e2);
+            let t_x = (i as Float - 1.0) * 2.5;
+            object.set_transformation(&translation(t_x, 1.0, 0.0) * &(&rotation_y(PI / 6.0) * &scaling(0.9, 0.9, 0.9)));
+            object.set_material(material);
+            world.objects.push(object);
+        }
+
+        world
+    }
+
+    fn get_camera(&self, h_size: usize, v_size: usize) -> Camera {
+        self.init_camera(h_size, v_size, 0.0, 4.0, -7.0, 0.0, 1.0, 0.0)
+    }
+}
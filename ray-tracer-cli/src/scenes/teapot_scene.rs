@@ -20,7 +20,7 @@ impl Scene for TeaPotScene {
         let file = File::open(file_path).unwrap();
 
         let mut obj_reader = ObjReader::new(file);
-        obj_reader.read();
+        obj_reader.read().unwrap();
 
         let teapot = obj_reader.models;
         let teapot_model= teapot.get("Teapot001").unwrap();
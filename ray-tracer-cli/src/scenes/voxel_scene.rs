@@ -0,0 +1,53 @@
+use ray_tracer_lib::camera::Camera;
+use ray_tracer_lib::colors::Color;
+use ray_tracer_lib::core::math::{Float, PI};
+use ray_tracer_lib::core::transform::{scaling, translation};
+use ray_tracer_lib::core::tuple::point;
+use ray_tracer_lib::lights::point_light::PointLight;
+use ray_tracer_lib::material::Material;
+use ray_tracer_lib::object::Object;
+use ray_tracer_lib::shapes::shape::Shape;
+use ray_tracer_lib::shapes::voxels::Voxels;
+use ray_tracer_lib::world::World;
+use crate::scene::Scene;
+
+pub struct VoxelScene {}
+
+impl Scene for VoxelScene {
+    fn get_world(&self) -> World {
+        let mut world = self.init_world(true);
+        world.set_lights(vec!(PointLight::new(point(-20.0, 40.0, -30.0), Color::white())));
+
+        const N: usize = 32;
+        let mut voxels = Voxels::dense(N, N / 2, N);
+        let colors = [Color::new(0.2, 0.6, 0.2), Color::new(0.5, 0.35, 0.2), Color::new(0.6, 0.6, 0.6), Color::white()];
+        for color in colors {
+            let mut material = Material::new();
+            material.color = color;
+            voxels.add_material(material);
+        }
+
+        for x in 0..N {
+            for z in 0..N {
+                let u = x as Float / N as Float * 2.0 * PI;
+                let v = z as Float / N as Float * 2.0 * PI;
+                let height = ((u.sin() + v.cos() + 2.0) / 4.0 * (N / 2) as Float) as usize;
+                for y in 0..=height {
+                    let material_index = usize::min(3, y * 4 / (N / 2));
+                    voxels.set(x, y, z, Some(material_index));
+                }
+            }
+        }
+
+        let mut terrain = Object::new(Shape::Voxels(voxels));
+        let half = N as Float / 2.0;
+        terrain.set_transformation(&scaling(0.1, 0.1, 0.1) * &translation(-half, 0.0, -half));
+        world.objects.push(terrain);
+
+        world
+    }
+
+    fn get_camera(&self, h_size: usize, v_size: usize) -> Camera {
+        self.init_camera(h_size, v_size, -2.0, 3.0, -3.5, 0.0, 0.5, 0.0)
+    }
+}
@@ -0,0 +1,157 @@
+use std::str::FromStr;
+use crate::canvas::Canvas;
+use crate::colors::Color;
+use crate::core::math::Float;
+use crate::core::tuple::Tuple;
+
+// The arbitrary output variables, buffers rendered alongside the colors with what the camera
+// sees first through each pixel. The pixels which see no object are black, with a zero alpha.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Aov {
+    // the distance from the camera to the hit
+    Depth,
+    // the world normal used for shading, facing the camera, in [-1, 1]
+    Normal,
+    // the id of the object hit, in each channel
+    ObjectId,
+    // the color of the material at the hit, before lighting
+    Albedo,
+    // the fraction of the lights which do not reach the hit
+    Shadow,
+}
+
+// What the camera sees first through a pixel.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SurfaceSample {
+    pub depth: Float,
+    pub normal: Tuple,
+    pub object_id: usize,
+    pub albedo: Color,
+    pub shadow: Float,
+}
+
+impl Aov {
+    pub const ALL: [Aov; 5] = [Aov::Depth, Aov::Normal, Aov::ObjectId, Aov::Albedo, Aov::Shadow];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aov::Depth => "depth",
+            Aov::Normal => "normal",
+            Aov::ObjectId => "object_id",
+            Aov::Albedo => "albedo",
+            Aov::Shadow => "shadow",
+        }
+    }
+
+    pub fn value(&self, sample: &SurfaceSample) -> Color {
+        match self {
+            Aov::Depth => Color::new(sample.depth, sample.depth, sample.depth),
+            Aov::Normal => Color::new(sample.normal.x, sample.normal.y, sample.normal.z),
+            Aov::ObjectId => {
+                let id = sample.object_id as Float;
+                Color::new(id, id, id)
+            }
+            Aov::Albedo => sample.albedo,
+            Aov::Shadow => Color::new(sample.shadow, sample.shadow, sample.shadow),
+        }
+    }
+
+    // the buffer of this variable brought into [0, 1] to be seen: the nearest hits are white and
+    // the farthest black, the normals are shifted from [-1, 1] and each object id gets a color
+    pub fn display(&self, buffer: &Canvas) -> Canvas {
+        let mut display = buffer.clone();
+        let covered = |x: usize, y: usize| buffer.alpha_at(x, y) > 0.0;
+        match self {
+            Aov::Depth => {
+                let (near, far) = buffer.iter().filter(|(x, y, _)| covered(*x, *y))
+                    .fold((Float::MAX, Float::MIN), |(near, far), (_, _, c)| (near.min(c.r), far.max(c.r)));
+                let range = if far > near { far - near } else { 1.0 };
+                for (x, y, color) in display.iter_mut() {
+                    if covered(x, y) {
+                        let value = 1.0 - (color.r - near) / range;
+                        *color = Color::new(value, value, value);
+                    }
+                }
+            }
+            Aov::Normal => {
+                for (x, y, color) in display.iter_mut() {
+                    if covered(x, y) {
+                        *color = Color::new(color.r * 0.5 + 0.5, color.g * 0.5 + 0.5, color.b * 0.5 + 0.5);
+                    }
+                }
+            }
+            Aov::ObjectId => {
+                for (x, y, color) in display.iter_mut() {
+                    if covered(x, y) {
+                        *color = id_color(color.r as usize);
+                    }
+                }
+            }
+            Aov::Albedo | Aov::Shadow => {}
+        }
+        display
+    }
+}
+
+// a bright color spread over the hues by the golden ratio
+fn id_color(id: usize) -> Color {
+    let hue = (id as Float * 0.618_033_988_75).fract() * 6.0;
+    let f = hue.fract();
+    let (r, g, b) = match hue as usize {
+        0 => (1.0, f, 0.0),
+        1 => (1.0 - f, 1.0, 0.0),
+        2 => (0.0, 1.0, f),
+        3 => (0.0, 1.0 - f, 1.0),
+        4 => (f, 0.0, 1.0),
+        _ => (1.0, 0.0, 1.0 - f),
+    };
+    Color::new(0.2 + 0.8 * r, 0.2 + 0.8 * g, 0.2 + 0.8 * b)
+}
+
+impl FromStr for Aov {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Aov::ALL.iter().find(|aov| aov.name() == value).copied().ok_or_else(|| format!("unknown output variable '{}'", value))
+    }
+}
+
+// The buffers of the requested variables, as large as the image.
+#[derive(Debug, Clone)]
+pub struct AovBuffers {
+    buffers: Vec<(Aov, Canvas)>,
+}
+
+impl AovBuffers {
+    pub fn new(aovs: &[Aov], width: usize, height: usize) -> Self {
+        let mut buffers: Vec<(Aov, Canvas)> = vec![];
+        for aov in aovs {
+            if !buffers.iter().any(|(other, _)| other == aov) {
+                buffers.push((*aov, Canvas::new(width, height)));
+            }
+        }
+        AovBuffers { buffers }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+
+    pub fn get(&self, aov: Aov) -> Option<&Canvas> {
+        self.buffers.iter().find(|(other, _)| *other == aov).map(|(_, buffer)| buffer)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Aov, &Canvas)> + '_ {
+        self.buffers.iter().map(|(aov, buffer)| (*aov, buffer))
+    }
+
+    // the pixels without a sample are left uncovered
+    pub fn write(&mut self, x: usize, y: usize, sample: Option<&SurfaceSample>) {
+        for (aov, buffer) in self.buffers.iter_mut() {
+            match sample {
+                Some(sample) => buffer.write_pixel(x, y, aov.value(sample)),
+                None => buffer.write_alpha(x, y, 0.0),
+            }
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use std::str::FromStr;
+use crate::colors::Color;
+use crate::core::math::Float;
+use crate::core::ray::Ray;
+
+// What the rays which hit nothing see.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Background {
+    Solid(Color),
+    // from the bottom color, looking down, to the top color, looking up
+    Gradient { bottom: Color, top: Color },
+    // black, and the camera leaves the pixels uncovered
+    Transparent,
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Solid(Color::black())
+    }
+}
+
+impl Background {
+    pub fn color(&self, ray: &Ray) -> Color {
+        match self {
+            Background::Solid(color) => *color,
+            Background::Gradient { bottom, top } => {
+                let t = (ray.direction.normalize().y + 1.0) / 2.0;
+                *bottom * (1.0 - t) + *top * t
+            }
+            Background::Transparent => Color::black(),
+        }
+    }
+
+    // the coverage of the pixels of the background
+    pub fn alpha(&self) -> Float {
+        match self {
+            Background::Transparent => 0.0,
+            _ => 1.0,
+        }
+    }
+}
+
+fn parse_color(value: &str) -> Result<Color, String> {
+    let components: Vec<Float> = value.split(',').map(|c| c.trim().parse::<Float>())
+        .collect::<Result<_, _>>().map_err(|_| format!("invalid color '{}'", value))?;
+    match components[..] {
+        [r, g, b] => Ok(Color::new(r, g, b)),
+        _ => Err(format!("invalid color '{}', expected r,g,b", value)),
+    }
+}
+
+impl FromStr for Background {
+    type Err = String;
+
+    // transparent, r,g,b or bottom:top as r,g,b:r,g,b
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value == "transparent" {
+            return Ok(Background::Transparent);
+        }
+        match value.split_once(':') {
+            Some((bottom, top)) => Ok(Background::Gradient { bottom: parse_color(bottom)?, top: parse_color(top)? }),
+            None => Ok(Background::Solid(parse_color(value)?)),
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::io;
 use std::io::Write;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use rayon::prelude::*;
+use crate::aov::{Aov, AovBuffers, SurfaceSample};
 use crate::canvas::Canvas;
 use crate::core::math::Float;
 use crate::core::matrix::Matrix;
@@ -31,6 +32,8 @@ pub struct Pixel {
     x : usize,
     y : usize,
     color : Color,
+    alpha : Float,
+    surface : Option<SurfaceSample>,
 }
 
 pub struct Block {
@@ -86,17 +89,23 @@ impl Camera {
     }
 
     pub fn render(&self, world: &World, file_path: &str) -> Canvas {
+        self.render_with_aovs(world, file_path, &[]).0
+    }
+
+    // the image and the buffers of the output variables
+    pub fn render_with_aovs(&self, world: &World, file_path: &str, aovs: &[Aov]) -> (Canvas, AovBuffers) {
         let mut blocks: Vec<Block> = vec![];
-        let nx = usize::max(1,self.v_size / self.block_size);
-        let ny = usize::max(1,self.h_size / self.block_size);
+        // the blocks cover every pixel, the last ones of a row or a column may be smaller
+        let nx = self.h_size.div_ceil(self.block_size);
+        let ny = self.v_size.div_ceil(self.block_size);
 
         for i in 0..nx {
             for j in 0..ny {
                 let block = Block {
-                    x_min: usize::min(i * self.block_size, self.v_size-1),
-                    x_max: usize::min((i+1) * self.block_size, self.v_size-1),
-                    y_min: usize::min(j * self.block_size, self.h_size-1),
-                    y_max: usize::min((j+1) * self.block_size, self.h_size-1),
+                    x_min: i * self.block_size,
+                    x_max: usize::min((i+1) * self.block_size, self.h_size),
+                    y_min: j * self.block_size,
+                    y_max: usize::min((j+1) * self.block_size, self.v_size),
                     pixels: vec![],
                 };
                 blocks.push(block);
@@ -109,14 +118,15 @@ impl Camera {
         let start = Arc::new(Mutex::new(Instant::now()));
 
         let mut image = Canvas::new(self.h_size, self.v_size);
+        let mut buffers = AovBuffers::new(aovs, self.h_size, self.v_size);
 
         blocks.par_iter_mut().for_each(|block| {
             num_block.fetch_add(1, Ordering::SeqCst);
             for x in block.x_min..block.x_max {
                 for y in block.y_min..block.y_max {
                     let ray = self.ray_for_pixel(x, y);
-                    let color = world.color_at(&ray, 5);
-                    let pixel = Pixel { x, y, color };
+                    let (color, alpha, surface) = world.sample_at(&ray, 5, !aovs.is_empty());
+                    let pixel = Pixel { x, y, color, alpha, surface };
                     block.pixels.push(pixel);
                     let nb_pixels = num_pixel.fetch_add(1, Ordering::SeqCst);
                     let mut guard = start.lock().unwrap();
@@ -131,14 +141,22 @@ impl Camera {
             }
         });
 
+        // the canvas has an alpha channel only when the background leaves pixels uncovered
+        let with_alpha = world.background.alpha() < 1.0;
         for block in blocks.iter() {
             for pixel in block.pixels.iter() {
                 image.write_pixel(pixel.x, pixel.y, pixel.color);
+                if with_alpha {
+                    image.write_alpha(pixel.x, pixel.y, pixel.alpha);
+                }
+                if !buffers.is_empty() {
+                    buffers.write(pixel.x, pixel.y, pixel.surface.as_ref());
+                }
             }
         }
 
         println!();
-        image
+        (image, buffers)
     }
 
     pub fn _render(&self, world: &World, file_path: &str) -> Canvas {
@@ -1,20 +1,75 @@
 use std::fs::File;
-use std::io::{Write, Error, BufWriter};
+use std::io::{Write, Error, BufReader, BufWriter};
 use std::path::Path;
 use crate::colors::Color;
 use crate::core::math::Float;
+use crate::hdr::{read_image, write_hdr, write_pfm};
+use crate::tone_mapping::{srgb_decode, ToneMapper};
+use image::{Rgb32FImage, RgbaImage, RgbImage};
+use rayon::prelude::*;
 
-#[derive(Debug)]
+// The pixels are stored row after row, from the top left corner.
+// The alpha channel, the coverage of the pixels, is only there once one has been written.
+#[derive(Debug, Clone)]
 pub struct Canvas {
     pub width : usize,
     pub height : usize,
-    pixels: Vec<Vec<Color>>
+    pixels: Vec<Color>,
+    alpha: Option<Vec<Float>>,
+}
+
+// A rectangle of a canvas, its coordinates are relative to its top left corner.
+#[derive(Debug, Copy, Clone)]
+pub struct CanvasRegion<'a> {
+    canvas: &'a Canvas,
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl CanvasRegion<'_> {
+    pub fn pixel_at(&self, x : usize, y : usize) -> Color {
+        self.row(y)[x]
+    }
+
+    pub fn row(&self, y: usize) -> &[Color] {
+        let start = self.canvas.index(self.x, self.y + y);
+        &self.canvas.pixels[start..start + self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[Color]> + ExactSizeIterator + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn to_canvas(&self) -> Canvas {
+        let pixels = self.rows().flat_map(|row| row.iter().copied()).collect();
+        let mut canvas = Canvas::from_pixels(self.width, self.height, pixels);
+        if let Some(alpha) = &self.canvas.alpha {
+            let alpha = (0..self.height).flat_map(|y| {
+                let start = self.canvas.index(self.x, self.y + y);
+                alpha[start..start + self.width].iter().copied()
+            }).collect();
+            canvas.alpha = Some(alpha);
+        }
+        canvas
+    }
 }
 
 impl Canvas {
     pub fn new(width : usize, height : usize) -> Self {
-        let pixels : Vec<Vec<Color>> = vec![vec![Color::new(0.0, 0.0, 0.0); width]; height];
-        Self {width, height, pixels}
+        let pixels = vec![Color::new(0.0, 0.0, 0.0); width * height];
+        Self {width, height, pixels, alpha: None}
+    }
+
+    // the pixels row after row, there must be width * height of them
+    pub fn from_pixels(width : usize, height : usize, pixels: Vec<Color>) -> Self {
+        assert_eq!(pixels.len(), width * height, "a {}x{} canvas needs {} pixels", width, height, width * height);
+        Self {width, height, pixels, alpha: None}
+    }
+
+    fn index(&self, x : usize, y : usize) -> usize {
+        y * self.width + x
     }
 
     pub fn write_pixel(&mut self, x : usize, y : usize, color : Color) {
@@ -25,11 +80,122 @@ impl Canvas {
             return;
         }
 
-        self.pixels[y][x] = color;
+        let index = self.index(x, y);
+        self.pixels[index] = color;
     }
 
     pub fn pixel_at(&self, x : usize, y : usize) -> Color {
-        self.pixels[y][x]
+        self.pixels[self.index(x, y)]
+    }
+
+    pub fn has_alpha(&self) -> bool {
+        self.alpha.is_some()
+    }
+
+    // the pixels are opaque without an alpha channel
+    pub fn alpha_at(&self, x : usize, y : usize) -> Float {
+        self.alpha.as_ref().map_or(1.0, |alpha| alpha[self.index(x, y)])
+    }
+
+    pub fn write_alpha(&mut self, x : usize, y : usize, alpha : Float) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let index = self.index(x, y);
+        let size = self.width * self.height;
+        self.alpha.get_or_insert_with(|| vec![1.0; size])[index] = alpha;
+    }
+
+    pub fn alphas(&self) -> Option<&[Float]> {
+        self.alpha.as_deref()
+    }
+
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    pub fn pixels_mut(&mut self) -> &mut [Color] {
+        &mut self.pixels
+    }
+
+    pub fn into_pixels(self) -> Vec<Color> {
+        self.pixels
+    }
+
+    pub fn row(&self, y: usize) -> &[Color] {
+        let start = self.index(0, y);
+        &self.pixels[start..start + self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [Color] {
+        let start = self.index(0, y);
+        let width = self.width;
+        &mut self.pixels[start..start + width]
+    }
+
+    // the rows from the top
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[Color]> + ExactSizeIterator + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    // a canvas without width has no rows to change
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [Color]> + '_ {
+        let height = if self.width == 0 { 0 } else { self.height };
+        self.pixels.chunks_mut(self.width.max(1)).take(height)
+    }
+
+    pub fn par_rows_mut(&mut self) -> impl IndexedParallelIterator<Item = &mut [Color]> + '_ {
+        let height = if self.width == 0 { 0 } else { self.height };
+        self.pixels.par_chunks_mut(self.width.max(1)).take(height)
+    }
+
+    // the pixels with their coordinates, row after row
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, &Color)> + '_ {
+        let width = self.width;
+        self.pixels.iter().enumerate().map(move |(i, color)| (i % width, i / width, color))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (usize, usize, &mut Color)> + '_ {
+        let width = self.width;
+        self.pixels.iter_mut().enumerate().map(move |(i, color)| (i % width, i / width, color))
+    }
+
+    pub fn par_iter(&self) -> impl IndexedParallelIterator<Item = (usize, usize, &Color)> + '_ {
+        let width = self.width;
+        self.pixels.par_iter().enumerate().map(move |(i, color)| (i % width, i / width, color))
+    }
+
+    pub fn par_iter_mut(&mut self) -> impl IndexedParallelIterator<Item = (usize, usize, &mut Color)> + '_ {
+        let width = self.width;
+        self.pixels.par_iter_mut().enumerate().map(move |(i, color)| (i % width, i / width, color))
+    }
+
+    // the region must be inside the canvas
+    pub fn region(&self, x: usize, y: usize, width: usize, height: usize) -> CanvasRegion<'_> {
+        assert!(x + width <= self.width && y + height <= self.height, "the region is outside the canvas");
+        CanvasRegion { canvas: self, x, y, width, height }
+    }
+
+    // the colors through the tone mapper, 8 bits per channel
+    pub fn to_rgb_image(&self, tone_mapper: &ToneMapper) -> RgbImage {
+        let data: Vec<u8> = self.pixels.par_iter().flat_map_iter(|color| tone_mapper.to_rgb8(*color)).collect();
+        RgbImage::from_raw(self.width as u32, self.height as u32, data).unwrap()
+    }
+
+    // the colors through the tone mapper and the alpha channel, 8 bits per channel
+    pub fn to_rgba_image(&self, tone_mapper: &ToneMapper) -> RgbaImage {
+        let data: Vec<u8> = self.pixels.par_iter().enumerate().flat_map_iter(|(i, color)| {
+            let [r, g, b] = tone_mapper.to_rgb8(*color);
+            let alpha = self.alpha.as_ref().map_or(1.0, |alpha| alpha[i]);
+            [r, g, b, (255.0 * alpha).round().clamp(0.0, 255.0) as u8]
+        }).collect();
+        RgbaImage::from_raw(self.width as u32, self.height as u32, data).unwrap()
+    }
+
+    // the colors as they are, in 32-bit floats
+    pub fn to_rgb32f_image(&self) -> Rgb32FImage {
+        let data: Vec<f32> = self.pixels.iter().flat_map(|c| [c.r as f32, c.g as f32, c.b as f32]).collect();
+        Rgb32FImage::from_raw(self.width as u32, self.height as u32, data).unwrap()
     }
 
     pub fn ppm_header(&self) -> String {
@@ -76,30 +242,81 @@ impl Canvas {
     }
 
     pub fn save_png(&self, file_path: &str) -> Result<(), Error> {
-        let path = Path::new(file_path);
-        let file = File::create(path).unwrap();
-        let ref mut w = BufWriter::new(file);
-        let mut encoder = png::Encoder::new(w, self.width as u32, self.height as u32); // Width is 2 pixels and height is 1.
-        encoder.set_color(png::ColorType::Rgb);
+        self.save_png_tone_mapped(file_path, &ToneMapper::new())
+    }
+
+    // the colors are tone mapped to 8 bits, with an alpha channel when the canvas has one
+    pub fn save_png_tone_mapped(&self, file_path: &str, tone_mapper: &ToneMapper) -> Result<(), Error> {
+        let file = File::create(Path::new(file_path))?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), self.width as u32, self.height as u32);
+        encoder.set_color(if self.has_alpha() { png::ColorType::Rgba } else { png::ColorType::Rgb });
         encoder.set_depth(png::BitDepth::Eight);
-        let mut writer = encoder.write_header().unwrap();
-        let mut data = vec![];
+        if tone_mapper.srgb {
+            encoder.set_srgb(png::SrgbRenderingIntent::Perceptual);
+        }
+        let mut writer = encoder.write_header()?;
+        if self.has_alpha() {
+            writer.write_image_data(self.to_rgba_image(tone_mapper).as_raw())?;
+        } else {
+            writer.write_image_data(self.to_rgb_image(tone_mapper).as_raw())?;
+        }
 
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let c = self.pixel_at(x, y);
-                let r = clamp(c.r);
-                let g = clamp(c.g);
-                let b = clamp(c.b);
-                data.push(r);
-                data.push(g);
-                data.push(b);
-            }
+        Ok(())
+    }
+
+    // the colors are saved without clamping, as a Radiance .hdr image
+    pub fn save_hdr(&self, file_path: &str) -> Result<(), Error> {
+        let mut w = BufWriter::new(File::create(file_path)?);
+        write_hdr(self, &mut w)?;
+        w.flush()
+    }
+
+    // the colors are saved without clamping, as 32-bit floats in a PFM image
+    pub fn save_pfm(&self, file_path: &str) -> Result<(), Error> {
+        let mut w = BufWriter::new(File::create(file_path)?);
+        write_pfm(self, &mut w)?;
+        w.flush()
+    }
+
+    // a .hdr or PFM image
+    pub fn load_hdr(file_path: &str) -> Result<Canvas, Error> {
+        read_image(&mut BufReader::new(File::open(file_path)?))
+    }
+
+    // A .hdr or PFM image with its values, or an 8-bit image, with its alpha if it has one.
+    // The 8-bit colors are decoded as they were saved: from sRGB when srgb is set, otherwise as they are.
+    pub fn load_image(file_path: &str, srgb: bool) -> Result<Canvas, Error> {
+        if file_path.ends_with(".hdr") || file_path.ends_with(".pfm") {
+            return Self::load_hdr(file_path);
         }
+        let image = image::open(file_path).map_err(Error::other)?;
+        if image.color().has_alpha() {
+            Ok(Self::from_rgba_image(&image.into_rgba8(), srgb))
+        } else {
+            Ok(Self::from_rgb_image(&image.into_rgb8(), srgb))
+        }
+    }
 
-        writer.write_image_data(&data).unwrap();
+    // the reverse of to_rgb_image without tone mapping
+    pub fn from_rgb_image(image: &RgbImage, srgb: bool) -> Canvas {
+        let pixels = image.pixels().map(|p| from_rgb8([p[0], p[1], p[2]], srgb)).collect();
+        Canvas::from_pixels(image.width() as usize, image.height() as usize, pixels)
+    }
 
-        Ok(())
+    pub fn from_rgba_image(image: &RgbaImage, srgb: bool) -> Canvas {
+        let pixels = image.pixels().map(|p| from_rgb8([p[0], p[1], p[2]], srgb)).collect();
+        let mut canvas = Canvas::from_pixels(image.width() as usize, image.height() as usize, pixels);
+        canvas.alpha = Some(image.pixels().map(|p| p[3] as Float / 255.0).collect());
+        canvas
+    }
+}
+
+fn from_rgb8(rgb: [u8; 3], srgb: bool) -> Color {
+    let [r, g, b] = rgb.map(|value| value as Float / 255.0);
+    if srgb {
+        Color::new(srgb_decode(r), srgb_decode(g), srgb_decode(b))
+    } else {
+        Color::new(r, g, b)
     }
 }
 
@@ -116,3 +333,16 @@ fn clamp(value : Float) -> u8 {
     c as u8
 }
 
+
+impl From<&Rgb32FImage> for Canvas {
+    fn from(image: &Rgb32FImage) -> Self {
+        let pixels = image.pixels().map(|p| Color::new(p[0] as Float, p[1] as Float, p[2] as Float)).collect();
+        Canvas::from_pixels(image.width() as usize, image.height() as usize, pixels)
+    }
+}
+
+impl From<&Canvas> for Rgb32FImage {
+    fn from(canvas: &Canvas) -> Self {
+        canvas.to_rgb32f_image()
+    }
+}
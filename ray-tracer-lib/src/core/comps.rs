@@ -1,7 +1,9 @@
+use crate::colors::Color;
 use crate::core::intersection::Intersection;
 use crate::core::intersections::Intersections;
 use crate::core::math::{EPSILON, Float};
 use crate::object::Object;
+use crate::patterns::normal_map::NormalMap;
 use crate::core::ray::Ray;
 use crate::core::tuple::{Tuple};
 
@@ -17,6 +19,8 @@ pub struct Comps {
     pub reflectv: Tuple,
     pub n1: Float,
     pub n2: Float,
+    pub texture_uv: Option<(Float, Float)>,
+    pub vertex_color: Option<Color>,
 }
 
 impl Comps {
@@ -55,12 +59,21 @@ pub fn prepare_computations(hit: &Intersection, ray: &Ray, xs: &Intersections) -
         normalv = -normalv;
     }
 
-    // after negating the normal, if necessary
-    let reflectv = ray.direction.reflect(&normalv);
-
+    // the points for the secondary rays are offset along the geometric normal
     let over_point = point + normalv * EPSILON;
     let under_point = point - normalv * EPSILON;
 
+    // the normal map changes the outward normal, before shading
+    let normal_map = &hit.object.material().normal_map;
+    if !matches!(normal_map, NormalMap::None) {
+        let outward = if inside { -normalv } else { normalv };
+        let mapped = normal_map.normal_at(&hit.object, &point, outward, hit);
+        normalv = if inside { -mapped } else { mapped };
+    }
+
+    // after negating the normal, if necessary
+    let reflectv = ray.direction.reflect(&normalv);
+
     let mut containers: Vec<Object> = vec!();
     let mut n1 = 0.0;
     let mut n2 = 0.0;
@@ -83,7 +96,7 @@ pub fn prepare_computations(hit: &Intersection, ray: &Ray, xs: &Intersections) -
         }
     }
 
-    Comps { t: hit.t, object: hit.object.clone(), point, eyev, normalv, inside, over_point, under_point, reflectv, n1, n2 }
+    Comps { t: hit.t, object: hit.object.clone(), point, eyev, normalv, inside, over_point, under_point, reflectv, n1, n2, texture_uv: hit.texture_uv, vertex_color: hit.vertex_color }
 }
 
 fn get_refractive_index(containers: &Vec<Object>) -> Float {
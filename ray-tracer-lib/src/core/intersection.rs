@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use crate::colors::Color;
 use crate::core::math::Float;
 use crate::object::Object;
 
@@ -7,17 +8,19 @@ pub struct Intersection {
     pub t : Float,
     pub u : Float,
     pub v : Float,
-    pub object: Object
+    pub object: Object,
+    pub texture_uv: Option<(Float, Float)>,   // the texture coordinates of meshes read with their UVs
+    pub vertex_color: Option<Color>,    // the interpolated vertex colors of meshes read with colors
 }
 
 impl Intersection {
     pub fn new(t : Float, object : Object) -> Self {
-        let inter = Intersection {t, u: 0.0, v: 0.0, object};
+        let inter = Intersection {t, u: 0.0, v: 0.0, object, texture_uv: None, vertex_color: None};
         inter
     }
 
     pub fn new_uv(t : Float, object : Object, u: Float, v : Float) -> Self {
-        let inter = Intersection {t, u, v, object};
+        let inter = Intersection {t, u, v, object, texture_uv: None, vertex_color: None};
         inter
     }
 }
@@ -0,0 +1,135 @@
+use std::io::{Error, ErrorKind};
+use rayon::prelude::*;
+use crate::aov::{Aov, AovBuffers};
+use crate::canvas::Canvas;
+use crate::colors::Color;
+use crate::core::math::{EPSILON, Float};
+
+// the B3 spline, the weights of the 5 taps of each pass
+const KERNEL: [Float; 5] = [1.0 / 16.0, 1.0 / 4.0, 3.0 / 8.0, 1.0 / 4.0, 1.0 / 16.0];
+
+// The buffers which keep the edges of the image, all the same size as the image.
+// The pixels which they leave uncovered (alpha 0) are only mixed with each other.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct DenoiseGuides<'a> {
+    pub normal: Option<&'a Canvas>,
+    pub depth: Option<&'a Canvas>,
+    pub albedo: Option<&'a Canvas>,
+}
+
+impl<'a> DenoiseGuides<'a> {
+    pub fn from_aovs(aovs: &'a AovBuffers) -> Self {
+        DenoiseGuides { normal: aovs.get(Aov::Normal), depth: aovs.get(Aov::Depth), albedo: aovs.get(Aov::Albedo) }
+    }
+}
+
+// The edge-avoiding À-trous wavelet filter (Dammertz et al. 2010): each pass blurs with a
+// 5x5 kernel whose taps are twice as far apart as in the previous pass, and each tap is
+// weighted down by how much its color and its guides differ from the pixel's.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Denoiser {
+    pub iterations: usize,
+    // the color difference which lowers a weight by e, halved at each pass
+    pub color_sigma: Float,
+    pub normal_sigma: Float,
+    // relative to the range of the depths of the image
+    pub depth_sigma: Float,
+    pub albedo_sigma: Float,
+}
+
+impl Default for Denoiser {
+    fn default() -> Self {
+        Denoiser::new()
+    }
+}
+
+fn distance2(c1: &Color, c2: &Color) -> Float {
+    let d = *c1 - *c2;
+    d.r * d.r + d.g * d.g + d.b * d.b
+}
+
+impl Denoiser {
+    pub fn new() -> Self {
+        Denoiser { iterations: 5, color_sigma: 0.5, normal_sigma: 0.3, depth_sigma: 0.05, albedo_sigma: 0.1 }
+    }
+
+    // the guides must be as large as the image
+    pub fn denoise(&self, image: &Canvas, guides: &DenoiseGuides) -> Result<Canvas, Error> {
+        let (width, height) = (image.width, image.height);
+        for guide in [guides.normal, guides.depth, guides.albedo].into_iter().flatten() {
+            if guide.width != width || guide.height != height {
+                return Err(Error::new(ErrorKind::InvalidInput, format!("a {}x{} guide for a {}x{} image", guide.width, guide.height, width, height)));
+            }
+        }
+        let covered = |i: usize| [guides.normal, guides.depth, guides.albedo].iter().flatten()
+            .all(|guide| guide.alphas().is_none_or(|alpha| alpha[i] > 0.0));
+        let coverage: Vec<bool> = (0..width * height).map(covered).collect();
+        let depth_range = guides.depth.map_or(1.0, |depth| {
+            let (near, far) = depth.pixels().iter().enumerate().filter(|(i, _)| coverage[*i])
+                .fold((Float::MAX, Float::MIN), |(near, far), (_, c)| (near.min(c.r), far.max(c.r)));
+            if far > near { far - near } else { 1.0 }
+        });
+
+        // a sigma of 0 keeps the guides apart without dividing by 0
+        let normal_sigma2 = (self.normal_sigma * self.normal_sigma).max(EPSILON * EPSILON);
+        let depth_scale = (depth_range * self.depth_sigma).max(EPSILON);
+        let albedo_sigma2 = (self.albedo_sigma * self.albedo_sigma).max(EPSILON * EPSILON);
+
+        let mut current = image.clone();
+        for iteration in 0..self.iterations {
+            let step = 1_isize << iteration;
+            let color_sigma2 = (self.color_sigma / (1 << iteration) as Float).powi(2).max(EPSILON * EPSILON);
+            let source = current.pixels();
+            let mut filtered = Canvas::from_pixels(width, height, source.to_vec());
+            filtered.par_rows_mut().enumerate().for_each(|(y, row)| {
+                for (x, pixel) in row.iter_mut().enumerate() {
+                    let p = y * width + x;
+                    let mut sum = Color::black();
+                    let mut total = 0.0;
+                    for (j, ky) in KERNEL.iter().enumerate() {
+                        let qy = y as isize + (j as isize - 2) * step;
+                        if qy < 0 || qy >= height as isize {
+                            continue;
+                        }
+                        for (i, kx) in KERNEL.iter().enumerate() {
+                            let qx = x as isize + (i as isize - 2) * step;
+                            if qx < 0 || qx >= width as isize {
+                                continue;
+                            }
+                            let q = qy as usize * width + qx as usize;
+                            if coverage[p] != coverage[q] {
+                                continue;
+                            }
+                            let mut exponent = distance2(&source[p], &source[q]) / color_sigma2;
+                            if coverage[p] {
+                                if let Some(normal) = guides.normal {
+                                    exponent += distance2(&normal.pixels()[p], &normal.pixels()[q]) / normal_sigma2;
+                                }
+                                if let Some(depth) = guides.depth {
+                                    let d = (depth.pixels()[p].r - depth.pixels()[q].r) / depth_scale;
+                                    exponent += d * d;
+                                }
+                                if let Some(albedo) = guides.albedo {
+                                    exponent += distance2(&albedo.pixels()[p], &albedo.pixels()[q]) / albedo_sigma2;
+                                }
+                            }
+                            let weight = kx * ky * (-exponent).exp();
+                            sum = sum + source[q] * weight;
+                            total += weight;
+                        }
+                    }
+                    // the pixel itself always has a weight
+                    *pixel = sum * (1.0 / total);
+                }
+            });
+            current = filtered;
+        }
+
+        if let Some(alpha) = image.alphas() {
+            for (i, a) in alpha.iter().enumerate() {
+                current.write_alpha(i % width, i / width, *a);
+            }
+        }
+        Ok(current)
+    }
+}
@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use gltf::camera::Projection;
+use gltf::image::Format;
+use gltf::khr_lights_punctual::Kind;
+use gltf::material::AlphaMode;
+use gltf::mesh::Mode;
+use crate::camera::Camera;
+use crate::colors::Color;
+use crate::core::math::Float;
+use crate::core::matrix::Matrix;
+use crate::core::transform::scaling;
+use crate::core::tuple::{point, Tuple, vector};
+use crate::lights::light::Light;
+use crate::lights::point_light::PointLight;
+use crate::material::Material;
+use crate::mesh::error::{MeshError, MeshErrorReason};
+use crate::object::Object;
+use crate::mesh::tangents::generate_tangents;
+use crate::patterns::image::Texture;
+use crate::patterns::normal_map::NormalMap;
+use crate::patterns::pattern::Pattern;
+use crate::shapes::group::Group;
+use crate::shapes::smooth_triangle::SmoothTriangle;
+use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
+use crate::shapes::triangle::Triangle;
+use crate::shapes::triangle_model::TriangleModel;
+
+// directional lights become point lights this far away, in the direction they come from
+const DIRECTIONAL_LIGHT_DISTANCE: Float = 1.0e6;
+
+// A perspective camera of the file, its image size is chosen when rendering
+#[derive(Debug, Clone)]
+pub struct GltfCamera {
+    pub y_field_of_view: Float,
+    pub aspect_ratio: Option<Float>,
+    pub transform: Matrix<4>,   // from the world to the camera, as given to Camera::set_transform
+}
+
+impl GltfCamera {
+    // the vertical field of view is kept, whatever the image size
+    pub fn camera(&self, h_size: usize, v_size: usize) -> Camera {
+        let aspect = h_size as Float / v_size as Float;
+        let field_of_view = if aspect >= 1.0 {
+            2.0 * ((self.y_field_of_view / 2.0).tan() * aspect).atan()
+        } else {
+            self.y_field_of_view
+        };
+        let mut camera = Camera::new(h_size, v_size, field_of_view);
+        camera.set_transform(self.transform);
+        camera
+    }
+}
+
+// Reads a glTF 2.0 scene (.gltf or .glb): each node of the default scene gives a group with its local
+// transformation, each mesh primitive a triangle model with its material.
+// The base color, metallic and roughness factors give the color, the highlights and the reflections,
+// the base color texture an image pattern, alpha blending and KHR_materials_transmission the transparency.
+// Perspective cameras and KHR_lights_punctual lights are placed with the world transformation of their node,
+// spot lights are read as point lights and directional lights as far away point lights.
+pub struct GltfReader<T> {
+    pub source : T,
+    pub directory : Option<PathBuf>,    // where the external buffers and images are, the current directory if None
+    pub light_scale : Float,    // lights have no falloff here, their intensities are scaled by this factor
+    pub objects : Vec<Object>,
+    pub cameras : Vec<GltfCamera>,
+    pub lights : Vec<Light>,
+}
+
+impl GltfReader<File> {
+    // the buffers and images are looked for next to the file
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, MeshError> {
+        let file = File::open(&path).map_err(|error| MeshError { line: 0, reason: MeshErrorReason::Io(error) })?;
+        let mut gltf_reader = GltfReader::new(file);
+        gltf_reader.directory = path.as_ref().parent().map(PathBuf::from);
+        Ok(gltf_reader)
+    }
+}
+
+impl<T> GltfReader<T> where T: Read {
+    pub fn new(source : T) -> Self {
+        Self { source, directory: None, light_scale: 1.0, objects: vec![], cameras: vec![], lights: vec![] }
+    }
+
+    pub fn read(&mut self) -> Result<(), MeshError> {
+        self.read_document().map_err(|reason| MeshError { line: 0, reason })
+    }
+
+    fn read_document(&mut self) -> Result<(), MeshErrorReason> {
+        let mut data = vec![];
+        self.source.read_to_end(&mut data).map_err(MeshErrorReason::Io)?;
+        let gltf_error = |error: gltf::Error| MeshErrorReason::Gltf(error.to_string());
+        let gltf::Gltf { document, blob } = gltf::Gltf::from_slice(&data).map_err(gltf_error)?;
+        let directory = self.directory.as_deref();
+        let buffers = gltf::import_buffers(&document, directory, blob).map_err(gltf_error)?;
+        let images = gltf::import_images(&document, directory, &buffers).map_err(gltf_error)?;
+
+        let materials: Vec<Material> = document.materials().map(|material| Self::material(&material, &images)).collect();
+        let mut mesh_users = HashMap::new();
+        for node in document.nodes() {
+            if let Some(mesh) = node.mesh() {
+                *mesh_users.entry(mesh.index()).or_insert(0) += 1;
+            }
+        }
+        let mut meshes = MeshCache { buffers: &buffers, materials: &materials, users: mesh_users, shared: HashMap::new() };
+
+        let scene = match document.default_scene().or_else(|| document.scenes().next()) {
+            Some(scene) => scene,
+            None => return Ok(()),
+        };
+        for node in scene.nodes() {
+            if let Some(object) = self.read_node(&node, &Matrix::<4>::identity(), &mut meshes)? {
+                self.objects.push(object);
+            }
+        }
+        Ok(())
+    }
+
+    // the cameras and lights of the node and its descendants are gathered on the way,
+    // the nodes without a mesh below them give no object
+    fn read_node(&mut self, node: &gltf::Node, parent_transformation: &Matrix<4>, meshes: &mut MeshCache) -> Result<Option<Object>, MeshErrorReason> {
+        let local_transformation = Self::matrix(node.transform().matrix());
+        let transformation = parent_transformation * &local_transformation;
+
+        if let Some(camera) = node.camera() {
+            if let Projection::Perspective(perspective) = camera.projection() {
+                // our cameras look toward -z like glTF ones, but with +x to the left
+                self.cameras.push(GltfCamera {
+                    y_field_of_view: perspective.yfov() as Float,
+                    aspect_ratio: perspective.aspect_ratio().map(|aspect_ratio| aspect_ratio as Float),
+                    transform: &scaling(-1.0, 1.0, 1.0) * &transformation.inverse(),
+                });
+            }
+        }
+        if let Some(light) = node.light() {
+            let [r, g, b] = light.color();
+            let intensity = Color::new(r as Float, g as Float, b as Float) * (light.intensity() as Float * self.light_scale);
+            let position = match light.kind() {
+                Kind::Directional => {
+                    let direction = (&transformation * &vector(0.0, 0.0, -1.0)).normalize();
+                    point(0.0, 0.0, 0.0) - direction * DIRECTIONAL_LIGHT_DISTANCE
+                }
+                Kind::Point | Kind::Spot { .. } => &transformation * &point(0.0, 0.0, 0.0),
+            };
+            self.lights.push(PointLight::new(position, intensity));
+        }
+
+        let mut children = vec![];
+        if let Some(mesh) = node.mesh() {
+            if let Some(object) = meshes.object(&mesh)? {
+                children.push(object);
+            }
+        }
+        for child in node.children() {
+            if let Some(object) = self.read_node(&child, &transformation, meshes)? {
+                children.push(object);
+            }
+        }
+        if children.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Group::from(children, local_transformation)))
+    }
+
+    // glTF matrices are given column by column
+    fn matrix(columns: [[f32; 4]; 4]) -> Matrix<4> {
+        Matrix::new(columns.map(|column| column.map(|value| value as Float))).transpose()
+    }
+
+    fn material(material: &gltf::Material, images: &[gltf::image::Data]) -> Material {
+        let pbr = material.pbr_metallic_roughness();
+        let [r, g, b, alpha] = pbr.base_color_factor().map(|value| value as Float);
+        let metallic = pbr.metallic_factor() as Float;
+        let roughness = pbr.roughness_factor() as Float;
+
+        let mut result = Material::new();
+        result.color = Color::new(r, g, b);
+        result.diffuse = 0.9 * (1.0 - 0.5 * metallic);
+        result.specular = 0.9 * (1.0 - roughness);
+        // the Phong exponent of a microfacet distribution with alpha = roughness²
+        result.shininess = (2.0 / roughness.powi(4).max(0.0001) - 2.0).clamp(1.0, 1000.0);
+        result.reflective = metallic * (1.0 - roughness);
+        if material.alpha_mode() == AlphaMode::Blend {
+            result.transparency = 1.0 - alpha;
+        }
+        if let Some(transmission) = material.transmission() {
+            result.transparency = result.transparency.max(transmission.transmission_factor() as Float);
+        }
+        if let Some(ior) = material.ior() {
+            result.refractive_index = ior as Float;
+        }
+        if let Some(info) = pbr.base_color_texture() {
+            let image = &images[info.texture().source().index()];
+            if let Some(texture) = Self::texture(image, &result.color) {
+                result.pattern = Pattern::image(texture);
+            }
+        }
+        if let Some(info) = material.normal_texture() {
+            let image = &images[info.texture().source().index()];
+            if let Some(texture) = Self::texture(image, &Color::new(1.0, 1.0, 1.0)) {
+                result.normal_map = NormalMap::image(texture, info.scale() as Float);
+            }
+        }
+        result
+    }
+
+    // the base color factor multiplies the texture, None for the formats without colors
+    fn texture(image: &gltf::image::Data, factor: &Color) -> Option<Texture> {
+        let (channels, size) = match image.format {
+            Format::R8 => (1, 1),
+            Format::R8G8 => (2, 1),
+            Format::R8G8B8 => (3, 1),
+            Format::R8G8B8A8 => (4, 1),
+            Format::R16 => (1, 2),
+            Format::R16G16 => (2, 2),
+            Format::R16G16B16 => (3, 2),
+            Format::R16G16B16A16 => (4, 2),
+            Format::R32G32B32FLOAT => (3, 4),
+            Format::R32G32B32A32FLOAT => (4, 4),
+        };
+        let value = |bytes: &[u8]| match size {
+            1 => bytes[0] as Float / 255.0,
+            2 => u16::from_ne_bytes([bytes[0], bytes[1]]) as Float / 65535.0,
+            _ => f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as Float,
+        };
+        let pixels: Vec<Color> = image.pixels.chunks_exact(channels * size).map(|pixel| {
+            let color = if channels < 3 {
+                let grey = value(pixel);
+                Color::new(grey, grey, grey)
+            } else {
+                Color::new(value(pixel), value(&pixel[size..]), value(&pixel[2 * size..]))
+            };
+            color * *factor
+        }).collect();
+        let (width, height) = (image.width as usize, image.height as usize);
+        Texture::new(width, height, pixels).ok()
+    }
+}
+
+// The meshes used by several nodes are read once and shared by instances
+struct MeshCache<'a> {
+    buffers: &'a [gltf::buffer::Data],
+    materials: &'a [Material],
+    users: HashMap<usize, usize>,
+    shared: HashMap<usize, Arc<Object>>,
+}
+
+impl MeshCache<'_> {
+    fn object(&mut self, mesh: &gltf::Mesh) -> Result<Option<Object>, MeshErrorReason> {
+        if let Some(shared) = self.shared.get(&mesh.index()) {
+            return Ok(Some(Object::new_instance(shared.clone())));
+        }
+        let mut objects = vec![];
+        for primitive in mesh.primitives() {
+            if let Some(object) = self.primitive(&primitive)? {
+                objects.push(object);
+            }
+        }
+        let object = match objects.len() {
+            0 => return Ok(None),
+            1 => objects.pop().unwrap(),
+            _ => Group::from(objects, Matrix::<4>::identity()),
+        };
+        if self.users.get(&mesh.index()).copied().unwrap_or(0) > 1 {
+            let shared = Arc::new(object);
+            self.shared.insert(mesh.index(), shared.clone());
+            return Ok(Some(Object::new_instance(shared)));
+        }
+        Ok(Some(object))
+    }
+
+    // points and lines give nothing, the mesh is smooth when the primitive has normals
+    fn primitive(&self, primitive: &gltf::Primitive) -> Result<Option<Object>, MeshErrorReason> {
+        let reader = primitive.reader(|buffer| self.buffers.get(buffer.index()).map(|data| &data.0[..]));
+        let positions: Vec<Tuple> = match reader.read_positions() {
+            Some(positions) => positions.map(|[x, y, z]| point(x as Float, y as Float, z as Float)).collect(),
+            None => return Ok(None),
+        };
+        let normals: Option<Vec<Tuple>> = reader.read_normals()
+            .map(|normals| normals.map(|[x, y, z]| vector(x as Float, y as Float, z as Float)).collect());
+        // glTF texture coordinates start at the top left corner
+        let uvs: Option<Vec<(Float, Float)>> = reader.read_tex_coords(0)
+            .map(|uvs| uvs.into_f32().map(|[u, v]| (u as Float, 1.0 - v as Float)).collect());
+        // the bitangents of glTF go down the images, they go up here
+        let tangents: Option<Vec<Tuple>> = reader.read_tangents()
+            .map(|tangents| tangents.map(|[x, y, z, w]| Tuple::new(x as Float, y as Float, z as Float, -w as Float)).collect());
+        let colors: Option<Vec<Color>> = reader.read_colors(0)
+            .map(|colors| colors.into_rgb_f32().map(|[r, g, b]| Color::new(r as Float, g as Float, b as Float)).collect());
+        // every attribute gives a value to each vertex
+        let counts = [
+            ("NORMAL", normals.as_ref().map(Vec::len)),
+            ("TEXCOORD_0", uvs.as_ref().map(Vec::len)),
+            ("TANGENT", tangents.as_ref().map(Vec::len)),
+            ("COLOR_0", colors.as_ref().map(Vec::len)),
+        ];
+        for (attribute, count) in counts {
+            if let Some(count) = count.filter(|count| *count != positions.len()) {
+                return Err(MeshErrorReason::Gltf(format!("{} has {} values for {} positions", attribute, count, positions.len())));
+            }
+        }
+        let indices: Vec<usize> = match reader.read_indices() {
+            Some(indices) => indices.into_u32().map(|index| index as usize).collect(),
+            None => (0..positions.len()).collect(),
+        };
+        if let Some(index) = indices.iter().find(|index| **index >= positions.len()) {
+            return Err(MeshErrorReason::VertexOutOfRange(*index as isize));
+        }
+
+        let faces: Vec<[usize; 3]> = match primitive.mode() {
+            Mode::Triangles => indices.chunks_exact(3).map(|face| [face[0], face[1], face[2]]).collect(),
+            // every other triangle of a strip is turned over to keep the winding
+            Mode::TriangleStrip => indices.windows(3).enumerate()
+                .map(|(i, face)| if i % 2 == 0 { [face[0], face[1], face[2]] } else { [face[1], face[0], face[2]] })
+                .collect(),
+            Mode::TriangleFan => (1..indices.len().saturating_sub(1)).map(|i| [indices[0], indices[i], indices[i + 1]]).collect(),
+            _ => return Ok(None),
+        };
+
+        let mut triangles = vec![];
+        let mut smooth_triangles = vec![];
+        for corners in faces.iter() {
+            let mut triangle = Triangle::new(positions[corners[0]], positions[corners[1]], positions[corners[2]]);
+            if let Some(uvs) = &uvs {
+                triangle.uvs = Some(corners.map(|corner| uvs[corner]));
+            }
+            if let Some(colors) = &colors {
+                triangle.colors = Some(corners.map(|corner| colors[corner]));
+            }
+            if let Some(tangents) = &tangents {
+                triangle.tangents = Some(corners.map(|corner| tangents[corner]));
+            }
+            match &normals {
+                Some(normals) => {
+                    let [n1, n2, n3] = corners.map(|corner| normals[corner]);
+                    smooth_triangles.push(SmoothTriangle { triangle, n1, n2, n3 });
+                }
+                None => triangles.push(triangle),
+            }
+        }
+        // the normal maps need tangents
+        if tangents.is_none() && primitive.material().normal_texture().is_some() {
+            generate_tangents(&mut smooth_triangles);
+        }
+        let mut object = match normals {
+            Some(_) => Object::new_smooth_triangle_group(SmoothTriangleModel::new(smooth_triangles)),
+            None => Object::new_triangle_group(TriangleModel::new(triangles)),
+        };
+        if let Some(material) = primitive.material().index().and_then(|index| self.materials.get(index)) {
+            object.set_material(material.clone());
+        }
+        Ok(Some(object))
+    }
+}
@@ -0,0 +1,230 @@
+use std::io::{BufRead, Error, ErrorKind, Read, Write};
+use crate::canvas::Canvas;
+use crate::colors::Color;
+use crate::core::math::Float;
+
+// High dynamic range images, the colors are kept above 1.0:
+// - Radiance .hdr files, a shared exponent byte for the 3 mantissa bytes of a pixel (RGBE).
+//   The writer writes flat scanlines, the reader also reads run length encoded ones.
+// - PFM files, 32-bit floats stored from the bottom row to the top one.
+
+// the smallest component written as a non black RGBE pixel
+const RGBE_MIN: Float = 1e-32;
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+pub fn to_rgbe(color: Color) -> [u8; 4] {
+    let (r, g, b) = (color.r.max(0.0), color.g.max(0.0), color.b.max(0.0));
+    let max = r.max(g).max(b);
+    if max < RGBE_MIN {
+        return [0, 0, 0, 0];
+    }
+    // max = mantissa * 2^exponent with the mantissa in [0.5, 1)
+    let mut exponent = max.log2().floor() as i32 + 1;
+    let mantissa = max / (2.0 as Float).powi(exponent);
+    if mantissa >= 1.0 {
+        exponent += 1;
+    } else if mantissa < 0.5 {
+        exponent -= 1;
+    }
+    let scale = 256.0 / (2.0 as Float).powi(exponent);
+    [(r * scale) as u8, (g * scale) as u8, (b * scale) as u8, (exponent + 128) as u8]
+}
+
+pub fn from_rgbe(rgbe: [u8; 4]) -> Color {
+    if rgbe[3] == 0 {
+        return Color::black();
+    }
+    let f = (2.0 as Float).powi(rgbe[3] as i32 - (128 + 8));
+    Color::new((rgbe[0] as Float + 0.5) * f, (rgbe[1] as Float + 0.5) * f, (rgbe[2] as Float + 0.5) * f)
+}
+
+pub fn write_hdr<W: Write>(canvas: &Canvas, target: &mut W) -> Result<(), Error> {
+    write!(target, "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n", canvas.height, canvas.width)?;
+    let data: Vec<u8> = canvas.pixels().iter().flat_map(|color| to_rgbe(*color)).collect();
+    target.write_all(&data)
+}
+
+pub fn write_pfm<W: Write>(canvas: &Canvas, target: &mut W) -> Result<(), Error> {
+    // a negative scale is for little endian values
+    write!(target, "PF\n{} {}\n-1.0\n", canvas.width, canvas.height)?;
+    let mut data = Vec::with_capacity(canvas.width * canvas.height * 12);
+    for c in canvas.rows().rev().flatten() {
+        for value in [c.r, c.g, c.b] {
+            data.extend_from_slice(&(value as f32).to_le_bytes());
+        }
+    }
+    target.write_all(&data)
+}
+
+// Reads a .hdr or a PFM image, told apart by their first bytes.
+pub fn read_image<R: BufRead>(source: &mut R) -> Result<Canvas, Error> {
+    let start = source.fill_buf()?;
+    if start.starts_with(b"#?") {
+        read_hdr(source)
+    } else if start.starts_with(b"PF") || start.starts_with(b"Pf") {
+        read_pfm(source)
+    } else {
+        Err(invalid("not a .hdr or a PFM image"))
+    }
+}
+
+fn read_line<R: BufRead>(source: &mut R) -> Result<String, Error> {
+    let mut line = String::new();
+    if source.read_line(&mut line)? == 0 {
+        return Err(Error::new(ErrorKind::UnexpectedEof, "unexpected end of the header"));
+    }
+    Ok(line.trim_end_matches(['\n', '\r']).to_string())
+}
+
+fn parse_size(value: Option<&str>) -> Result<usize, Error> {
+    value.and_then(|value| value.parse::<usize>().ok()).ok_or_else(|| invalid("invalid image size"))
+}
+
+// The pixels are read from the rest of the source, after checking it can hold them:
+// the sizes of the header are not trusted before allocating the image.
+fn read_body<R: Read>(source: &mut R, width: usize, height: usize, min_row_bytes: Option<usize>) -> Result<Vec<u8>, Error> {
+    let mut body = vec![];
+    source.read_to_end(&mut body)?;
+    let min_bytes = width.checked_mul(height).and(min_row_bytes).and_then(|row_bytes| row_bytes.checked_mul(height));
+    match min_bytes {
+        Some(min_bytes) if min_bytes <= body.len() => Ok(body),
+        Some(_) => Err(Error::new(ErrorKind::UnexpectedEof, "the image is larger than its data")),
+        None => Err(invalid("invalid image size")),
+    }
+}
+
+// the shortest encoding of a scanline: runs of 127 pixels for each channel when run length encoded
+fn min_scanline_bytes(width: usize) -> Option<usize> {
+    if (8..0x8000).contains(&width) {
+        Some(4 + 4 * 2 * width.div_ceil(127))
+    } else {
+        width.checked_mul(4)
+    }
+}
+
+pub fn read_hdr<R: BufRead>(source: &mut R) -> Result<Canvas, Error> {
+    if !read_line(source)?.starts_with("#?") {
+        return Err(invalid("not a .hdr image"));
+    }
+    loop {
+        let line = read_line(source)?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some(format) = line.strip_prefix("FORMAT=") {
+            if format != "32-bit_rle_rgbe" {
+                return Err(invalid(&format!("unsupported format {}", format)));
+            }
+        }
+    }
+
+    // only the usual orientations, the rows from the top (-Y) or the bottom (+Y), left to right
+    let resolution = read_line(source)?;
+    let items: Vec<&str> = resolution.split_whitespace().collect();
+    if items.len() != 4 || !(items[0] == "-Y" || items[0] == "+Y") || items[2] != "+X" {
+        return Err(invalid(&format!("unsupported resolution '{}'", resolution)));
+    }
+    let height = parse_size(items.get(1).copied())?;
+    let width = parse_size(items.get(3).copied())?;
+    let bottom_up = items[0] == "+Y";
+    let body = read_body(source, width, height, min_scanline_bytes(width))?;
+    let mut body = body.as_slice();
+
+    let mut canvas = Canvas::new(width, height);
+    let mut scanline = vec![[0u8; 4]; width];
+    for row in 0..height {
+        read_scanline(&mut body, &mut scanline)?;
+        let y = if bottom_up { height - 1 - row } else { row };
+        for (x, rgbe) in scanline.iter().enumerate() {
+            canvas.write_pixel(x, y, from_rgbe(*rgbe));
+        }
+    }
+    Ok(canvas)
+}
+
+fn read_scanline<R: Read>(source: &mut R, scanline: &mut [[u8; 4]]) -> Result<(), Error> {
+    let width = scanline.len();
+    if width == 0 {
+        return Ok(());
+    }
+    let mut first = [0u8; 4];
+    source.read_exact(&mut first)?;
+    let encoded_width = ((first[2] as usize) << 8) | first[3] as usize;
+    let run_length = (8..0x8000).contains(&width) && first[0] == 2 && first[1] == 2 && first[2] & 0x80 == 0;
+    if !run_length {
+        scanline[0] = first;
+        for pixel in scanline.iter_mut().skip(1) {
+            source.read_exact(pixel)?;
+        }
+        return Ok(());
+    }
+    if encoded_width != width {
+        return Err(invalid("the width of a scanline is not the width of the image"));
+    }
+
+    // each channel is encoded on its own: a count above 128 repeats the next byte count - 128 times,
+    // otherwise count bytes follow
+    let mut byte = [0u8; 1];
+    for channel in 0..4 {
+        let mut x = 0;
+        while x < width {
+            source.read_exact(&mut byte)?;
+            let count = byte[0] as usize;
+            if count > 128 {
+                let count = count - 128;
+                if x + count > width {
+                    return Err(invalid("a run goes past the end of a scanline"));
+                }
+                source.read_exact(&mut byte)?;
+                for pixel in scanline[x..x + count].iter_mut() {
+                    pixel[channel] = byte[0];
+                }
+                x += count;
+            } else {
+                if count == 0 || x + count > width {
+                    return Err(invalid("invalid run in a scanline"));
+                }
+                for pixel in scanline[x..x + count].iter_mut() {
+                    source.read_exact(&mut byte)?;
+                    pixel[channel] = byte[0];
+                }
+                x += count;
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn read_pfm<R: BufRead>(source: &mut R) -> Result<Canvas, Error> {
+    let channels = match read_line(source)?.trim() {
+        "PF" => 3,
+        "Pf" => 1,
+        _ => return Err(invalid("not a PFM image")),
+    };
+    let size = read_line(source)?;
+    let mut items = size.split_whitespace();
+    let width = parse_size(items.next())?;
+    let height = parse_size(items.next())?;
+    let scale = read_line(source)?.trim().parse::<f32>().map_err(|_| invalid("invalid PFM scale"))?;
+    let little_endian = scale < 0.0;
+    let body = read_body(source, width, height, width.checked_mul(channels * 4))?;
+    let mut source = body.as_slice();
+
+    let mut canvas = Canvas::new(width, height);
+    let mut bytes = [0u8; 4];
+    let mut values = [0.0 as Float; 3];
+    for y in (0..height).rev() {
+        for x in 0..width {
+            for value in values.iter_mut().take(channels) {
+                source.read_exact(&mut bytes)?;
+                *value = if little_endian { f32::from_le_bytes(bytes) } else { f32::from_be_bytes(bytes) } as Float;
+            }
+            let color = if channels == 1 { Color::new(values[0], values[0], values[0]) } else { Color::new(values[0], values[1], values[2]) };
+            canvas.write_pixel(x, y, color);
+        }
+    }
+    Ok(canvas)
+}
@@ -7,9 +7,21 @@ pub mod patterns;
 pub mod shapes;
 pub mod tests;
 pub mod world;
+pub mod background;
+pub mod aov;
+pub mod denoise;
 pub mod canvas;
+pub mod hdr;
+pub mod tone_mapping;
 pub mod camera;
 pub mod obj_reader;
+pub mod mtl_reader;
+pub mod stl_reader;
+pub mod ply_reader;
+pub mod gltf_reader;
+pub mod obj_writer;
+pub mod ply_writer;
+pub mod mesh;
 
 
 
@@ -2,10 +2,11 @@ use crate::colors::Color;
 use crate::lights::light::Light;
 use crate::core::math::{equals, Float};
 use crate::object::Object;
+use crate::patterns::normal_map::NormalMap;
 use crate::patterns::pattern::{Pattern};
 use crate::core::tuple::Tuple;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct Material {
     pub color: Color,
     pub ambient: Float,
@@ -16,15 +17,21 @@ pub struct Material {
     pub transparency: Float,
     pub refractive_index: Float,
     pub pattern: Pattern,
+    pub normal_map: NormalMap,
 }
 
 impl Material {
     pub fn new() -> Material {
-        Material { color: Color { r: 1.0, g: 1.0, b: 1.0 }, ambient: 0.1, diffuse: 0.9, specular: 0.9, shininess: 200.0, reflective: 0.0, transparency: 0.0, refractive_index: 1.0, pattern: Pattern::new() }
+        Material { color: Color { r: 1.0, g: 1.0, b: 1.0 }, ambient: 0.1, diffuse: 0.9, specular: 0.9, shininess: 200.0, reflective: 0.0, transparency: 0.0, refractive_index: 1.0, pattern: Pattern::new(), normal_map: NormalMap::None }
     }
 
     pub fn lighting(&self, object: &Object, light: &Light, point: Tuple, eyev: Tuple, normalv: Tuple, in_shadow : bool) -> Color {
-        let color =self.pattern.pattern_at_object(object, point);
+        self.lighting_uv(object, light, point, eyev, normalv, in_shadow, None, None)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn lighting_uv(&self, object: &Object, light: &Light, point: Tuple, eyev: Tuple, normalv: Tuple, in_shadow : bool, texture_uv: Option<(Float, Float)>, vertex_color: Option<Color>) -> Color {
+        let color =self.pattern.pattern_at_object_uv(object, point, texture_uv, vertex_color);
 
         // combine the surface color with the light's color/intensity
         let effective_color = color * light.intensity();
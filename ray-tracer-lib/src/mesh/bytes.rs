@@ -0,0 +1,60 @@
+use crate::mesh::error::MeshErrorReason;
+
+// A cursor on the binary part of a mesh file
+pub(crate) struct Bytes<'a> {
+    data: &'a [u8],
+    position: usize,
+    big_endian: bool,
+}
+
+impl<'a> Bytes<'a> {
+    pub(crate) fn new(data: &'a [u8], big_endian: bool) -> Self {
+        Bytes { data, position: 0, big_endian }
+    }
+
+    pub(crate) fn take<const N: usize>(&mut self) -> Result<[u8; N], MeshErrorReason> {
+        let bytes = self.data.get(self.position..self.position + N).ok_or(MeshErrorReason::UnexpectedEnd)?;
+        self.position += N;
+        let mut array: [u8; N] = bytes.try_into().unwrap();
+        if self.big_endian {
+            array.reverse();
+        }
+        Ok(array)
+    }
+
+    pub(crate) fn skip(&mut self, n: usize) -> Result<(), MeshErrorReason> {
+        if self.position + n > self.data.len() {
+            return Err(MeshErrorReason::UnexpectedEnd);
+        }
+        self.position += n;
+        Ok(())
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, MeshErrorReason> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, MeshErrorReason> {
+        Ok(u16::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, MeshErrorReason> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, MeshErrorReason> {
+        Ok(u64::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn f32(&mut self) -> Result<f32, MeshErrorReason> {
+        Ok(f32::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn f64(&mut self) -> Result<f64, MeshErrorReason> {
+        Ok(f64::from_le_bytes(self.take()?))
+    }
+
+    pub(crate) fn is_at_end(&self) -> bool {
+        self.position == self.data.len()
+    }
+}
@@ -0,0 +1,387 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use crate::colors::Color;
+use crate::core::math::Float;
+use crate::core::tuple::{point, Tuple, vector};
+use crate::mesh::bytes::Bytes;
+use crate::mesh::error::MeshErrorReason;
+use crate::shapes::smooth_triangle::SmoothTriangle;
+use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
+use crate::shapes::triangle::Triangle;
+use crate::shapes::triangle_model::TriangleModel;
+
+const MAGIC: &[u8; 4] = b"RTMC";
+// to be increased whenever the layout changes, the caches of other versions are ignored
+pub const CACHE_VERSION: u32 = 2;
+
+// the optional parts of a triangle
+const HAS_UVS: u8 = 1;
+const HAS_MATERIAL: u8 = 2;
+const HAS_COLORS: u8 = 4;
+const HAS_TANGENTS: u8 = 8;
+
+// What identifies the source of a cache: its size and modification time, and a hash of its content
+// so a copied or touched source still matches.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SourceStamp {
+    pub size: u64,
+    pub modified: (u64, u32),   // seconds and nanoseconds since the epoch
+    pub hash: u64,
+}
+
+impl SourceStamp {
+    pub fn of(path: &Path) -> std::io::Result<SourceStamp> {
+        let (size, modified) = Self::metadata(path)?;
+        Ok(SourceStamp { size, modified, hash: Self::hash(path)? })
+    }
+
+    fn metadata(path: &Path) -> std::io::Result<(u64, (u64, u32))> {
+        let metadata = fs::metadata(path)?;
+        let modified = metadata.modified()?.duration_since(UNIX_EPOCH).unwrap_or_default();
+        Ok((metadata.len(), (modified.as_secs(), modified.subsec_nanos())))
+    }
+
+    // FNV-1a
+    fn hash(path: &Path) -> std::io::Result<u64> {
+        let mut file = fs::File::open(path)?;
+        let mut buffer = vec![0u8; 1 << 16];
+        let mut hash: u64 = 0xcbf29ce484222325;
+        loop {
+            let count = file.read(&mut buffer)?;
+            if count == 0 {
+                return Ok(hash);
+            }
+            for byte in buffer[..count].iter() {
+                hash = (hash ^ *byte as u64).wrapping_mul(0x100000001b3);
+            }
+        }
+    }
+
+    // the hash is only computed when the size matches but not the modification time
+    fn matches(&self, path: &Path) -> bool {
+        match Self::metadata(path) {
+            Ok((size, _)) if size != self.size => false,
+            Ok((_, modified)) if modified == self.modified => true,
+            Ok(_) => Self::hash(path).is_ok_and(|hash| hash == self.hash),
+            Err(_) => false,
+        }
+    }
+}
+
+// The models read from a mesh source, saved in a compact binary file next to it, with the
+// elements of the source they were made of so a reader loading the cache has all of them.
+// The materials are not saved (they may hold image textures): the names of the material libraries
+// are kept so they are read again, the faces keep their material index.
+// The models have no acceleration structure beyond their bounds, which are computed again on load.
+#[derive(Debug, Clone, Default)]
+pub struct ModelCache {
+    pub smooth_angle: Option<Float>,    // the normals generated when reading, part of the key of the cache
+    pub material_libraries: Vec<String>,
+    pub material_names: Vec<String>,
+    pub models: HashMap<String, TriangleModel>,
+    pub smooth_models: HashMap<String, SmoothTriangleModel>,
+    pub vertices: Vec<Tuple>,
+    pub vertex_colors: Vec<Option<Color>>,
+    pub normals: Vec<Tuple>,
+    pub texture_coordinates: Vec<(Float, Float)>,
+    pub faces: Vec<Vec<usize>>,
+}
+
+impl ModelCache {
+    // dragon.obj is cached in dragon.obj.rtmesh
+    pub fn path_for(source: &Path) -> PathBuf {
+        let mut name = source.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+        name.push(".rtmesh");
+        source.with_file_name(name)
+    }
+
+    pub fn save(&self, path: &Path, stamp: &SourceStamp) -> std::io::Result<()> {
+        fs::write(path, self.to_bytes(stamp))
+    }
+
+    // None when there is no cache, or when it is from another version, another source or other settings
+    pub fn load(path: &Path, source: &Path, smooth_angle: Option<Float>) -> Option<ModelCache> {
+        let data = fs::read(path).ok()?;
+        let (stamp, cache) = Self::from_bytes(&data).ok()?;
+        if !stamp.matches(source) || cache.smooth_angle.map(Float::to_bits) != smooth_angle.map(Float::to_bits) {
+            return None;
+        }
+        Some(cache)
+    }
+
+    pub fn to_bytes(&self, stamp: &SourceStamp) -> Vec<u8> {
+        let mut output = Output { data: vec![] };
+        output.data.extend_from_slice(MAGIC);
+        output.u32(CACHE_VERSION);
+        output.u64(stamp.size);
+        output.u64(stamp.modified.0);
+        output.u32(stamp.modified.1);
+        output.u64(stamp.hash);
+        output.u8(self.smooth_angle.is_some() as u8);
+        output.f64(self.smooth_angle.unwrap_or(0.0));
+        output.strings(&self.material_libraries);
+        output.strings(&self.material_names);
+
+        // sorted by name so the same models give the same file
+        let mut names: Vec<&String> = self.models.keys().collect();
+        names.sort();
+        output.u32(names.len() as u32);
+        for name in names {
+            output.string(name);
+            let triangles = &self.models[name].triangles;
+            output.u32(triangles.len() as u32);
+            for triangle in triangles.iter() {
+                output.triangle(triangle);
+            }
+        }
+        let mut names: Vec<&String> = self.smooth_models.keys().collect();
+        names.sort();
+        output.u32(names.len() as u32);
+        for name in names {
+            output.string(name);
+            let smooth_triangles = &self.smooth_models[name].smooth_triangles;
+            output.u32(smooth_triangles.len() as u32);
+            for smooth_triangle in smooth_triangles.iter() {
+                output.triangle(&smooth_triangle.triangle);
+                for n in [smooth_triangle.n1, smooth_triangle.n2, smooth_triangle.n3] {
+                    output.tuple(&n);
+                }
+            }
+        }
+
+        output.u32(self.vertices.len() as u32);
+        for vertex in self.vertices.iter() {
+            output.tuple(vertex);
+        }
+        output.u32(self.vertex_colors.len() as u32);
+        for color in self.vertex_colors.iter() {
+            output.u8(color.is_some() as u8);
+            if let Some(color) = color {
+                output.color(color);
+            }
+        }
+        output.u32(self.normals.len() as u32);
+        for normal in self.normals.iter() {
+            output.tuple(normal);
+        }
+        output.u32(self.texture_coordinates.len() as u32);
+        for (u, v) in self.texture_coordinates.iter() {
+            output.f64(*u);
+            output.f64(*v);
+        }
+        output.u32(self.faces.len() as u32);
+        for face in self.faces.iter() {
+            output.u32(face.len() as u32);
+            for index in face.iter() {
+                output.u32(*index as u32);
+            }
+        }
+        output.data
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<(SourceStamp, ModelCache), MeshErrorReason> {
+        let mut input = Bytes::new(data, false);
+        if &input.take::<4>()? != MAGIC {
+            return Err(MeshErrorReason::InvalidHeader(String::from("not a mesh cache")));
+        }
+        let version = input.u32()?;
+        if version != CACHE_VERSION {
+            return Err(MeshErrorReason::InvalidHeader(format!("cache version {}", version)));
+        }
+        let stamp = SourceStamp { size: input.u64()?, modified: (input.u64()?, input.u32()?), hash: input.u64()? };
+        let mut cache = ModelCache::default();
+        let has_smooth_angle = input.u8()? != 0;
+        let smooth_angle = input.f64()?;
+        cache.smooth_angle = Some(smooth_angle).filter(|_| has_smooth_angle);
+        cache.material_libraries = read_strings(&mut input)?;
+        cache.material_names = read_strings(&mut input)?;
+
+        for _ in 0..input.u32()? {
+            let name = read_string(&mut input)?;
+            let count = input.u32()? as usize;
+            let mut triangles = vec![];
+            for _ in 0..count {
+                triangles.push(read_triangle(&mut input)?);
+            }
+            cache.models.insert(name, TriangleModel::new(triangles));
+        }
+        for _ in 0..input.u32()? {
+            let name = read_string(&mut input)?;
+            let count = input.u32()? as usize;
+            let mut smooth_triangles = vec![];
+            for _ in 0..count {
+                let triangle = read_triangle(&mut input)?;
+                let (n1, n2, n3) = (read_vector(&mut input)?, read_vector(&mut input)?, read_vector(&mut input)?);
+                smooth_triangles.push(SmoothTriangle { triangle, n1, n2, n3 });
+            }
+            cache.smooth_models.insert(name, SmoothTriangleModel::new(smooth_triangles));
+        }
+
+        for _ in 0..input.u32()? {
+            cache.vertices.push(read_point(&mut input)?);
+        }
+        for _ in 0..input.u32()? {
+            let color = if input.u8()? != 0 { Some(read_color(&mut input)?) } else { None };
+            cache.vertex_colors.push(color);
+        }
+        for _ in 0..input.u32()? {
+            cache.normals.push(read_vector(&mut input)?);
+        }
+        for _ in 0..input.u32()? {
+            cache.texture_coordinates.push((input.f64()?, input.f64()?));
+        }
+        for _ in 0..input.u32()? {
+            let face = (0..input.u32()?).map(|_| input.u32().map(|index| index as usize)).collect::<Result<_, _>>()?;
+            cache.faces.push(face);
+        }
+        if !input.is_at_end() {
+            return Err(MeshErrorReason::InvalidHeader(String::from("trailing data")));
+        }
+        Ok((stamp, cache))
+    }
+}
+
+// little endian values
+struct Output {
+    data: Vec<u8>,
+}
+
+impl Output {
+    fn u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f64(&mut self, value: f64) {
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn string(&mut self, value: &str) {
+        self.u32(value.len() as u32);
+        self.data.extend_from_slice(value.as_bytes());
+    }
+
+    fn strings(&mut self, values: &[String]) {
+        self.u32(values.len() as u32);
+        for value in values.iter() {
+            self.string(value);
+        }
+    }
+
+    fn tuple(&mut self, tuple: &Tuple) {
+        for value in [tuple.x, tuple.y, tuple.z] {
+            self.f64(value);
+        }
+    }
+
+    fn color(&mut self, color: &Color) {
+        for value in [color.r, color.g, color.b] {
+            self.f64(value);
+        }
+    }
+
+    // the edges and the normal are computed again on load
+    fn triangle(&mut self, triangle: &Triangle) {
+        let flags = triangle.uvs.map_or(0, |_| HAS_UVS)
+            | triangle.material_index.map_or(0, |_| HAS_MATERIAL)
+            | triangle.colors.map_or(0, |_| HAS_COLORS)
+            | triangle.tangents.map_or(0, |_| HAS_TANGENTS);
+        self.u8(flags);
+        for p in [triangle.p1, triangle.p2, triangle.p3] {
+            self.tuple(&p);
+        }
+        if let Some(uvs) = triangle.uvs {
+            for (u, v) in uvs {
+                self.f64(u);
+                self.f64(v);
+            }
+        }
+        if let Some(index) = triangle.material_index {
+            self.u32(index as u32);
+        }
+        if let Some(colors) = triangle.colors {
+            for color in colors.iter() {
+                self.color(color);
+            }
+        }
+        if let Some(tangents) = triangle.tangents {
+            for tangent in tangents.iter() {
+                self.tuple(tangent);
+                self.f64(tangent.w);
+            }
+        }
+    }
+}
+
+fn read_string(input: &mut Bytes) -> Result<String, MeshErrorReason> {
+    let length = input.u32()? as usize;
+    let mut bytes = vec![];
+    for _ in 0..length {
+        bytes.push(input.u8()?);
+    }
+    String::from_utf8(bytes).map_err(|_| MeshErrorReason::InvalidHeader(String::from("invalid name")))
+}
+
+fn read_strings(input: &mut Bytes) -> Result<Vec<String>, MeshErrorReason> {
+    (0..input.u32()?).map(|_| read_string(input)).collect()
+}
+
+fn read_xyz(input: &mut Bytes) -> Result<(Float, Float, Float), MeshErrorReason> {
+    Ok((input.f64()?, input.f64()?, input.f64()?))
+}
+
+fn read_point(input: &mut Bytes) -> Result<Tuple, MeshErrorReason> {
+    let (x, y, z) = read_xyz(input)?;
+    Ok(point(x, y, z))
+}
+
+fn read_vector(input: &mut Bytes) -> Result<Tuple, MeshErrorReason> {
+    let (x, y, z) = read_xyz(input)?;
+    Ok(vector(x, y, z))
+}
+
+fn read_color(input: &mut Bytes) -> Result<Color, MeshErrorReason> {
+    let (r, g, b) = read_xyz(input)?;
+    Ok(Color::new(r, g, b))
+}
+
+fn read_triangle(input: &mut Bytes) -> Result<Triangle, MeshErrorReason> {
+    let flags = input.u8()?;
+    let mut triangle = Triangle::new(read_point(input)?, read_point(input)?, read_point(input)?);
+    if flags & HAS_UVS != 0 {
+        let mut uvs = [(0.0, 0.0); 3];
+        for uv in uvs.iter_mut() {
+            *uv = (input.f64()?, input.f64()?);
+        }
+        triangle.uvs = Some(uvs);
+    }
+    if flags & HAS_MATERIAL != 0 {
+        triangle.material_index = Some(input.u32()? as usize);
+    }
+    if flags & HAS_COLORS != 0 {
+        let mut colors = [Color::new(0.0, 0.0, 0.0); 3];
+        for color in colors.iter_mut() {
+            *color = read_color(input)?;
+        }
+        triangle.colors = Some(colors);
+    }
+    if flags & HAS_TANGENTS != 0 {
+        let mut tangents = [vector(0.0, 0.0, 0.0); 3];
+        for tangent in tangents.iter_mut() {
+            let (x, y, z) = read_xyz(input)?;
+            *tangent = Tuple::new(x, y, z, input.f64()?);
+        }
+        triangle.tangents = Some(tangents);
+    }
+    Ok(triangle)
+}
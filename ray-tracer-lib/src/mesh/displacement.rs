@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use crate::colors::Color;
+use crate::core::math::{Float, PI};
+use crate::core::tuple::{Tuple, vector};
+use crate::mesh::normals::{smooth_normals, vertex_key};
+use crate::patterns::pattern::Pattern;
+use crate::shapes::smooth_triangle::SmoothTriangle;
+use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
+use crate::shapes::triangle::Triangle;
+use crate::shapes::triangle_model::TriangleModel;
+
+// the edges are split at most this many times
+const MAX_PASSES: usize = 16;
+
+// a vertex shared by the triangles with the same position, the texture coordinates and color
+// of the first triangle are kept on the seams
+#[derive(Debug, Copy, Clone)]
+struct Vertex {
+    position: Tuple,
+    normal: Tuple,
+    uv: Option<(Float, Float)>,
+    color: Option<Color>,
+}
+
+impl Vertex {
+    fn middle(&self, other: &Vertex) -> Vertex {
+        Vertex {
+            position: (self.position + other.position) * 0.5,
+            normal: (self.normal + other.normal).normalize(),
+            uv: self.uv.zip(other.uv).map(|((u1, v1), (u2, v2))| ((u1 + u2) * 0.5, (v1 + v2) * 0.5)),
+            color: self.color.zip(other.color).map(|(c1, c2)| (c1 + c2) * 0.5),
+        }
+    }
+}
+
+// The triangles of a mesh as indices of shared vertices, so the edges are split and the
+// vertices moved the same way by the triangles on both sides, without cracks.
+struct Tessellation {
+    vertices: Vec<Vertex>,
+    triangles: Vec<([usize; 3], Option<usize>)>,   // with their material index
+    middles: HashMap<(usize, usize), usize>,
+}
+
+impl Tessellation {
+    // the displacement direction of a vertex is the average of its corner normals
+    fn new(smooth_triangles: &[SmoothTriangle]) -> Tessellation {
+        let mut tessellation = Tessellation { vertices: vec![], triangles: vec![], middles: HashMap::new() };
+        let mut indices: HashMap<(u64, u64, u64), usize> = HashMap::new();
+        for smooth_triangle in smooth_triangles.iter() {
+            let triangle = &smooth_triangle.triangle;
+            let corners = [(triangle.p1, smooth_triangle.n1), (triangle.p2, smooth_triangle.n2), (triangle.p3, smooth_triangle.n3)];
+            let mut face = [0; 3];
+            for (i, (position, normal)) in corners.iter().enumerate() {
+                let index = *indices.entry(vertex_key(position)).or_insert_with(|| {
+                    tessellation.vertices.push(Vertex { position: *position, normal: vector(0.0, 0.0, 0.0),
+                        uv: triangle.uvs.map(|uvs| uvs[i]), color: triangle.colors.map(|colors| colors[i]) });
+                    tessellation.vertices.len() - 1
+                });
+                if !normal.x.is_nan() {
+                    tessellation.vertices[index].normal = tessellation.vertices[index].normal + *normal;
+                }
+                face[i] = index;
+            }
+            tessellation.triangles.push((face, triangle.material_index));
+        }
+        for vertex in tessellation.vertices.iter_mut() {
+            vertex.normal = vertex.normal.normalize();
+        }
+        tessellation
+    }
+
+    fn middle(&mut self, a: usize, b: usize) -> usize {
+        let key = (a.min(b), a.max(b));
+        if let Some(index) = self.middles.get(&key) {
+            return *index;
+        }
+        self.vertices.push(self.vertices[a].middle(&self.vertices[b]));
+        self.middles.insert(key, self.vertices.len() - 1);
+        self.vertices.len() - 1
+    }
+
+    // Each pass splits the edges longer than the target in their middle, the triangles with
+    // one, two or three split edges become two, three or four triangles.
+    fn split(&mut self, edge_length: Float) {
+        for _ in 0..MAX_PASSES {
+            let mut split = false;
+            let mut triangles = vec![];
+            for (face, material_index) in std::mem::take(&mut self.triangles) {
+                let long = |i: usize| {
+                    let (a, b) = (face[i], face[(i + 1) % 3]);
+                    (self.vertices[a].position - self.vertices[b].position).magnitude() > edge_length
+                };
+                let edges = [long(0), long(1), long(2)];
+                // the corners turned so the split edges come first
+                let turn = match edges {
+                    [false, false, false] => {
+                        triangles.push((face, material_index));
+                        continue;
+                    }
+                    [true, true, true] | [true, false, false] | [true, true, false] => 0,
+                    [false, true, false] | [false, true, true] => 1,
+                    [false, false, true] | [true, false, true] => 2,
+                };
+                split = true;
+                let [a, b, c] = [face[turn], face[(turn + 1) % 3], face[(turn + 2) % 3]];
+                let faces = match edges.iter().filter(|long| **long).count() {
+                    1 => {
+                        let ab = self.middle(a, b);
+                        vec![[a, ab, c], [ab, b, c]]
+                    }
+                    2 => {
+                        let (ab, bc) = (self.middle(a, b), self.middle(b, c));
+                        vec![[ab, b, bc], [a, ab, bc], [a, bc, c]]
+                    }
+                    _ => {
+                        let (ab, bc, ca) = (self.middle(a, b), self.middle(b, c), self.middle(c, a));
+                        vec![[a, ab, ca], [ab, b, bc], [ca, bc, c], [ab, bc, ca]]
+                    }
+                };
+                triangles.extend(faces.into_iter().map(|face| (face, material_index)));
+            }
+            self.triangles = triangles;
+            if !split {
+                return;
+            }
+        }
+    }
+}
+
+// Displaces the vertices of a mesh along their normals by a height given for each vertex
+// position (in object space) and texture coordinates. The triangles are first split until
+// their edges are no longer than the edge length, so the details of the height are kept.
+// The displaced mesh gets new normals, smoothed between the faces below the smooth angle.
+pub struct Displacement<F> where F: Fn(&Tuple, Option<(Float, Float)>) -> Float {
+    pub height: F,
+    pub scale: Float,       // the heights are multiplied by the scale
+    pub edge_length: Float,
+    pub smooth_angle: Float,
+}
+
+// the brightness of a pattern, for the patterns giving a color in object space
+pub fn pattern_height(pattern: Pattern) -> impl Fn(&Tuple, Option<(Float, Float)>) -> Float {
+    move |point, texture_uv| pattern.height_at(point, texture_uv)
+}
+
+impl<F> Displacement<F> where F: Fn(&Tuple, Option<(Float, Float)>) -> Float {
+    // no split, and all smooth
+    pub fn new(height: F) -> Self {
+        Displacement { height, scale: 1.0, edge_length: Float::INFINITY, smooth_angle: PI }
+    }
+
+    // the faces of the flat models are smoothed first, to move their shared vertices together
+    pub fn displace(&self, model: &TriangleModel) -> SmoothTriangleModel {
+        let mut displaced = self.displace_triangles(&smooth_normals(&model.triangles, PI));
+        displaced.materials = model.materials.clone();
+        displaced
+    }
+
+    pub fn displace_smooth(&self, model: &SmoothTriangleModel) -> SmoothTriangleModel {
+        let mut displaced = self.displace_triangles(&model.smooth_triangles);
+        displaced.materials = model.materials.clone();
+        displaced
+    }
+
+    fn displace_triangles(&self, smooth_triangles: &[SmoothTriangle]) -> SmoothTriangleModel {
+        let mut tessellation = Tessellation::new(smooth_triangles);
+        tessellation.split(self.edge_length);
+        for vertex in tessellation.vertices.iter_mut() {
+            let height = (self.height)(&vertex.position, vertex.uv) * self.scale;
+            if !vertex.normal.x.is_nan() {
+                vertex.position = vertex.position + vertex.normal * height;
+            }
+        }
+
+        let triangles: Vec<Triangle> = tessellation.triangles.iter().map(|(face, material_index)| {
+            let [v1, v2, v3] = face.map(|index| tessellation.vertices[index]);
+            let mut triangle = Triangle::new(v1.position, v2.position, v3.position);
+            triangle.uvs = match (v1.uv, v2.uv, v3.uv) {
+                (Some(uv1), Some(uv2), Some(uv3)) => Some([uv1, uv2, uv3]),
+                _ => None,
+            };
+            triangle.colors = match (v1.color, v2.color, v3.color) {
+                (Some(c1), Some(c2), Some(c3)) => Some([c1, c2, c3]),
+                _ => None,
+            };
+            triangle.material_index = *material_index;
+            triangle
+        }).collect();
+        SmoothTriangleModel::new(smooth_normals(&triangles, self.smooth_angle))
+    }
+}
@@ -0,0 +1,52 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug)]
+pub enum MeshErrorReason {
+    Io(std::io::Error),
+    MissingValue,
+    InvalidNumber(String),
+    InvalidIndex(String),
+    VertexOutOfRange(isize),
+    TextureOutOfRange(isize),
+    NormalOutOfRange(isize),
+    NotEnoughVertices,
+    Texture(String),
+    MaterialLibrary(String, Box<MeshError>),
+    InvalidHeader(String),
+    UnexpectedEnd,
+    Gltf(String),
+    NonManifoldEdge(usize, usize),
+}
+
+// an error in a mesh source, lines are numbered from 1 (0 when there is no line, as in binary sources, and then it is not shown)
+#[derive(Debug)]
+pub struct MeshError {
+    pub line: usize,
+    pub reason: MeshErrorReason,
+}
+
+impl Display for MeshError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.line > 0 {
+            write!(f, "line {}: ", self.line)?;
+        }
+        match &self.reason {
+            MeshErrorReason::Io(error) => write!(f, "{}", error),
+            MeshErrorReason::MissingValue => write!(f, "missing value"),
+            MeshErrorReason::InvalidNumber(value) => write!(f, "invalid number '{}'", value),
+            MeshErrorReason::InvalidIndex(value) => write!(f, "invalid index '{}'", value),
+            MeshErrorReason::VertexOutOfRange(index) => write!(f, "no vertex {}", index),
+            MeshErrorReason::TextureOutOfRange(index) => write!(f, "no texture coordinates {}", index),
+            MeshErrorReason::NormalOutOfRange(index) => write!(f, "no normal {}", index),
+            MeshErrorReason::NotEnoughVertices => write!(f, "a face needs at least 3 vertices"),
+            MeshErrorReason::Texture(error) => write!(f, "{}", error),
+            MeshErrorReason::MaterialLibrary(name, error) => write!(f, "{}: {}", name, error),
+            MeshErrorReason::InvalidHeader(error) => write!(f, "invalid header, {}", error),
+            MeshErrorReason::UnexpectedEnd => write!(f, "unexpected end of data"),
+            MeshErrorReason::Gltf(error) => write!(f, "{}", error),
+            MeshErrorReason::NonManifoldEdge(v1, v2) => write!(f, "the edge from vertex {} to vertex {} is in more than one face", v1 + 1, v2 + 1),
+        }
+    }
+}
+
+impl std::error::Error for MeshError {}
@@ -0,0 +1,85 @@
+use crate::core::matrix::Matrix;
+use crate::core::tuple::Tuple;
+use crate::object::{Object, ObjectType};
+use crate::shapes::shape::Shape;
+use crate::shapes::smooth_triangle::SmoothTriangle;
+use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
+use crate::shapes::triangle::Triangle;
+use crate::shapes::triangle_model::TriangleModel;
+
+// A triangle to export, with its vertex normals when it is smooth
+#[derive(Debug, Clone)]
+pub struct MeshFace {
+    pub triangle: Triangle,
+    pub normals: Option<[Tuple; 3]>,
+}
+
+impl MeshFace {
+    fn transform(&self, transformation: &Matrix<4>, normal_transformation: &Matrix<4>) -> MeshFace {
+        let mut triangle = Triangle::new(transformation * &self.triangle.p1, transformation * &self.triangle.p2, transformation * &self.triangle.p3);
+        triangle.uvs = self.triangle.uvs;
+        triangle.colors = self.triangle.colors;
+        triangle.material_index = self.triangle.material_index;
+        let normals = self.normals.map(|normals| normals.map(|normal| {
+            let mut n = normal_transformation * &normal;
+            n.w = 0.0;
+            n.normalize()
+        }));
+        MeshFace { triangle, normals }
+    }
+}
+
+impl From<&Triangle> for MeshFace {
+    fn from(triangle: &Triangle) -> Self {
+        MeshFace { triangle: *triangle, normals: None }
+    }
+}
+
+impl From<&SmoothTriangle> for MeshFace {
+    fn from(smooth_triangle: &SmoothTriangle) -> Self {
+        MeshFace { triangle: smooth_triangle.triangle, normals: Some([smooth_triangle.n1, smooth_triangle.n2, smooth_triangle.n3]) }
+    }
+}
+
+pub fn model_faces(model: &TriangleModel) -> Vec<MeshFace> {
+    model.triangles.iter().map(MeshFace::from).collect()
+}
+
+pub fn smooth_model_faces(model: &SmoothTriangleModel) -> Vec<MeshFace> {
+    model.smooth_triangles.iter().map(MeshFace::from).collect()
+}
+
+// The triangles of an object and its descendants in world space: triangle shapes, triangle models,
+// groups and instances. The other shapes, and CSG, are left out.
+pub fn world_faces(object: &Object) -> Vec<MeshFace> {
+    let mut faces = vec![];
+    let (transformation, _) = object.transformation_to_root();
+    collect_faces(object, &transformation, &mut faces);
+    faces
+}
+
+// the transformation includes the one of the object
+fn collect_faces(object: &Object, transformation: &Matrix<4>, faces: &mut Vec<MeshFace>) {
+    let local_faces = match &object.object_type {
+        ObjectType::ObjectShape(Shape::Triangle(triangle)) => vec![MeshFace::from(triangle)],
+        ObjectType::ObjectShape(Shape::SmoothTriangle(smooth_triangle)) => vec![MeshFace::from(smooth_triangle)],
+        ObjectType::TriangleGroup(model) => model_faces(model),
+        ObjectType::SmoothTriangleGroup(model) => smooth_model_faces(model),
+        ObjectType::ObjectGroup(group) => {
+            for child in group.children().iter() {
+                collect_faces(child, &(transformation * child.transformation()), faces);
+            }
+            vec![]
+        }
+        ObjectType::ObjectInstance(instance) => {
+            collect_faces(&instance.object, &(transformation * instance.object.transformation()), faces);
+            vec![]
+        }
+        _ => vec![],
+    };
+    if local_faces.is_empty() {
+        return;
+    }
+    let normal_transformation = transformation.inverse().transpose();
+    faces.extend(local_faces.iter().map(|face| face.transform(transformation, &normal_transformation)));
+}
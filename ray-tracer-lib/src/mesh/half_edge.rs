@@ -0,0 +1,222 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use crate::core::math::Float;
+use crate::core::tuple::{point, Tuple, vector};
+use crate::mesh::error::MeshErrorReason;
+use crate::mesh::normals::{corner_angle, vertex_key};
+use crate::obj_reader::ObjReader;
+use crate::shapes::smooth_triangle::SmoothTriangle;
+use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
+use crate::shapes::triangle::Triangle;
+
+// the half of an edge going around a face, from its origin to the origin of the next one
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HalfEdge {
+    pub origin: usize,
+    pub face: usize,
+    pub next: usize,
+    pub prev: usize,
+    pub twin: Option<usize>,    // the half edge of the neighbour face, None on the boundary
+}
+
+// An indexed polygon mesh with its adjacency: every face is a loop of half edges, and the
+// half edges of an edge shared by two faces are twins. An edge is shared by at most two faces
+// going around it in opposite directions.
+#[derive(Debug, Clone)]
+pub struct HalfEdgeMesh {
+    pub vertices: Vec<Tuple>,
+    pub half_edges: Vec<HalfEdge>,
+    pub faces: Vec<usize>,     // the first half edge of each face
+    pub creases: HashMap<(usize, usize), Float>,   // the sharpness of the edges, infinite for a hard crease
+}
+
+impl HalfEdgeMesh {
+    // the faces are the indices of their vertices, starting at 0
+    pub fn new(vertices: Vec<Tuple>, faces: &[Vec<usize>]) -> Result<Self, MeshErrorReason> {
+        let mut half_edges = vec![];
+        let mut first_half_edges = vec![];
+        let mut directed_edges: HashMap<(usize, usize), usize> = HashMap::new();
+        for (face, indices) in faces.iter().enumerate() {
+            if indices.len() < 3 {
+                return Err(MeshErrorReason::NotEnoughVertices);
+            }
+            if let Some(index) = indices.iter().find(|index| **index >= vertices.len()) {
+                return Err(MeshErrorReason::VertexOutOfRange(*index as isize));
+            }
+            let first = half_edges.len();
+            let count = indices.len();
+            for (i, origin) in indices.iter().enumerate() {
+                let destination = indices[(i + 1) % count];
+                if directed_edges.insert((*origin, destination), first + i).is_some() {
+                    return Err(MeshErrorReason::NonManifoldEdge(*origin, destination));
+                }
+                half_edges.push(HalfEdge { origin: *origin, face, next: first + (i + 1) % count, prev: first + (i + count - 1) % count, twin: None });
+            }
+            first_half_edges.push(first);
+        }
+        for h in 0..half_edges.len() {
+            let destination = half_edges[half_edges[h].next].origin;
+            half_edges[h].twin = directed_edges.get(&(destination, half_edges[h].origin)).copied();
+        }
+        Ok(HalfEdgeMesh { vertices, half_edges, faces: first_half_edges, creases: HashMap::new() })
+    }
+
+    // the polygons as read, before their triangulation, the reader must have read its source or a cache of it
+    pub fn from_obj<T: Read>(obj_reader: &ObjReader<T>) -> Result<Self, MeshErrorReason> {
+        if obj_reader.faces.is_empty() {
+            return Err(MeshErrorReason::MissingValue);
+        }
+        Self::new(obj_reader.vertices.clone(), &obj_reader.faces)
+    }
+
+    // the triangles sharing a vertex position share the vertex
+    pub fn from_triangles(triangles: &[Triangle]) -> Result<Self, MeshErrorReason> {
+        let mut vertices = vec![];
+        let mut indices: HashMap<(u64, u64, u64), usize> = HashMap::new();
+        let faces: Vec<Vec<usize>> = triangles.iter().map(|triangle| {
+            [triangle.p1, triangle.p2, triangle.p3].iter().map(|p| {
+                *indices.entry(vertex_key(p)).or_insert_with(|| {
+                    vertices.push(*p);
+                    vertices.len() - 1
+                })
+            }).collect()
+        }).collect();
+        Self::new(vertices, &faces)
+    }
+
+    // the edges are the same in both directions
+    pub fn set_crease(&mut self, v1: usize, v2: usize, sharpness: Float) {
+        self.creases.insert(edge_key(v1, v2), sharpness);
+    }
+
+    pub fn destination(&self, half_edge: usize) -> usize {
+        self.half_edges[self.half_edges[half_edge].next].origin
+    }
+
+    // the boundary edges are hard creases
+    pub fn sharpness(&self, half_edge: usize) -> Float {
+        match self.half_edges[half_edge].twin {
+            None => Float::INFINITY,
+            Some(_) => {
+                let key = edge_key(self.half_edges[half_edge].origin, self.destination(half_edge));
+                self.creases.get(&key).copied().unwrap_or(0.0)
+            }
+        }
+    }
+
+    // a half edge of every edge
+    pub fn edges(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.half_edges.len()).filter(|h| self.half_edges[*h].twin.is_none_or(|twin| *h < twin))
+    }
+
+    pub fn face_half_edges(&self, face: usize) -> Vec<usize> {
+        let first = self.faces[face];
+        let mut half_edges = vec![first];
+        let mut half_edge = self.half_edges[first].next;
+        while half_edge != first {
+            half_edges.push(half_edge);
+            half_edge = self.half_edges[half_edge].next;
+        }
+        half_edges
+    }
+
+    pub fn face_vertices(&self, face: usize) -> Vec<usize> {
+        self.face_half_edges(face).iter().map(|h| self.half_edges[*h].origin).collect()
+    }
+
+    pub fn is_triangle_mesh(&self) -> bool {
+        (0..self.faces.len()).all(|face| self.face_half_edges(face).len() == 3)
+    }
+
+    // The polygons are split in fans of triangles, keeping the creases. The fan of a polygon starts
+    // at the first corner whose diagonals are not edges yet, an error when there is none.
+    pub fn triangulated(&self) -> Result<HalfEdgeMesh, MeshErrorReason> {
+        let mut edges: HashSet<(usize, usize)> = (0..self.half_edges.len())
+            .map(|h| edge_key(self.half_edges[h].origin, self.destination(h))).collect();
+        let mut faces = vec![];
+        for face in 0..self.faces.len() {
+            let vertices = self.face_vertices(face);
+            let count = vertices.len();
+            let start = (0..count).find(|start| {
+                (2..count - 1).all(|i| !edges.contains(&edge_key(vertices[*start], vertices[(start + i) % count])))
+            }).unwrap_or(0);
+            let corner = |i: usize| vertices[(start + i) % count];
+            for i in 1..count - 1 {
+                faces.push(vec![corner(0), corner(i), corner(i + 1)]);
+            }
+            edges.extend((2..count - 1).map(|i| edge_key(corner(0), corner(i))));
+        }
+        let mut mesh = Self::new(self.vertices.clone(), &faces)?;
+        mesh.creases = self.creases.clone();
+        Ok(mesh)
+    }
+
+    // with the same orientation as the triangles of the faces
+    fn face_normal(&self, face: usize) -> Tuple {
+        let vertices = self.face_vertices(face);
+        let p0 = self.vertices[vertices[0]];
+        let mut normal = vector(0.0, 0.0, 0.0);
+        for i in 1..vertices.len() - 1 {
+            let e1 = self.vertices[vertices[i]] - p0;
+            let e2 = self.vertices[vertices[i + 1]] - p0;
+            normal = normal + e2 * &e1;
+        }
+        normal.normalize()
+    }
+
+    fn weighted_normal(&self, half_edge: usize, face_normals: &[Tuple]) -> Tuple {
+        let h = &self.half_edges[half_edge];
+        let angle = corner_angle(&self.vertices[h.origin], &self.vertices[self.destination(half_edge)], &self.vertices[self.half_edges[h.prev].origin]);
+        face_normals[h.face] * angle
+    }
+
+    // the normals of the faces around the vertex of a corner, weighted by their angle,
+    // without going over the creases
+    fn corner_normal(&self, half_edge: usize, face_normals: &[Tuple]) -> Tuple {
+        let mut normal = self.weighted_normal(half_edge, face_normals);
+        // one way around the vertex, then the other one unless the faces all around were found
+        let mut current = half_edge;
+        loop {
+            let incoming = self.half_edges[current].prev;
+            if self.sharpness(incoming) > 0.0 {
+                break;
+            }
+            current = self.half_edges[incoming].twin.unwrap();
+            if current == half_edge {
+                return normal.normalize();
+            }
+            normal = normal + self.weighted_normal(current, face_normals);
+        }
+        let mut current = half_edge;
+        while self.sharpness(current) <= 0.0 {
+            current = self.half_edges[self.half_edges[current].twin.unwrap()].next;
+            normal = normal + self.weighted_normal(current, face_normals);
+        }
+        normal.normalize()
+    }
+
+    // the polygons split in fans of smooth triangles, with hard normals along the creases
+    pub fn smooth_model(&self) -> SmoothTriangleModel {
+        let face_normals: Vec<Tuple> = (0..self.faces.len()).map(|face| self.face_normal(face)).collect();
+        let mut smooth_triangles = vec![];
+        for face in 0..self.faces.len() {
+            let corners: Vec<(Tuple, Tuple)> = self.face_half_edges(face).iter()
+                .map(|h| (self.vertices[self.half_edges[*h].origin], self.corner_normal(*h, &face_normals)))
+                .collect();
+            for i in 1..corners.len() - 1 {
+                let [(p1, n1), (p2, n2), (p3, n3)] = [corners[0], corners[i], corners[i + 1]];
+                smooth_triangles.push(SmoothTriangle::new(p1, p2, p3, n1, n2, n3));
+            }
+        }
+        SmoothTriangleModel::new(smooth_triangles)
+    }
+}
+
+pub(crate) fn edge_key(v1: usize, v2: usize) -> (usize, usize) {
+    (v1.min(v2), v1.max(v2))
+}
+
+// the weighted sums of points are points again
+pub(crate) fn as_point(p: Tuple) -> Tuple {
+    point(p.x, p.y, p.z)
+}
@@ -0,0 +1,9 @@
+pub(crate) mod bytes;
+pub mod cache;
+pub mod displacement;
+pub mod error;
+pub mod faces;
+pub mod half_edge;
+pub mod normals;
+pub mod subdivision;
+pub mod tangents;
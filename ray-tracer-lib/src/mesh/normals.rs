@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use crate::core::math::Float;
+use crate::core::tuple::{Tuple, vector};
+use crate::shapes::smooth_triangle::SmoothTriangle;
+use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
+use crate::shapes::triangle::Triangle;
+use crate::shapes::triangle_model::TriangleModel;
+
+// the key of a vertex shared by several triangles (-0.0 and 0.0 are the same vertex)
+pub(crate) fn vertex_key(p: &Tuple) -> (u64, u64, u64) {
+    ((p.x + 0.0).to_bits(), (p.y + 0.0).to_bits(), (p.z + 0.0).to_bits())
+}
+
+// the angle between the edges leaving p to a and b
+pub(crate) fn corner_angle(p: &Tuple, a: &Tuple, b: &Tuple) -> Float {
+    let u = (*a - *p).normalize();
+    let v = (*b - *p).normalize();
+    u.dot(&v).clamp(-1.0, 1.0).acos()
+}
+
+// Per-vertex normals: at each corner, the normals of the faces sharing the vertex
+// are averaged (weighted by their corner angle), unless the angle between the faces is
+// above the threshold (in radians), which keeps the hard edges.
+pub fn smooth_normals(triangles: &[Triangle], angle_threshold: Float) -> Vec<SmoothTriangle> {
+    let cos_threshold = angle_threshold.cos();
+    let corners = |triangle: &Triangle| [
+        (triangle.p1, corner_angle(&triangle.p1, &triangle.p2, &triangle.p3)),
+        (triangle.p2, corner_angle(&triangle.p2, &triangle.p3, &triangle.p1)),
+        (triangle.p3, corner_angle(&triangle.p3, &triangle.p1, &triangle.p2)),
+    ];
+
+    // the faces around each vertex, with their corner angle
+    let mut faces: HashMap<(u64, u64, u64), Vec<(Tuple, Float)>> = HashMap::new();
+    for triangle in triangles.iter().filter(|triangle| !triangle.normal.x.is_nan()) {
+        for (p, angle) in corners(triangle) {
+            faces.entry(vertex_key(&p)).or_default().push((triangle.normal, angle));
+        }
+    }
+
+    triangles.iter().map(|triangle| {
+        let normals: Vec<Tuple> = corners(triangle).iter().map(|(p, _)| {
+            if triangle.normal.x.is_nan() {
+                return triangle.normal;
+            }
+            let mut n = vector(0.0, 0.0, 0.0);
+            for (face_normal, angle) in faces[&vertex_key(p)].iter() {
+                if face_normal.dot(&triangle.normal) >= cos_threshold {
+                    n = n + *face_normal * *angle;
+                }
+            }
+            n.normalize()
+        }).collect();
+        let mut smooth_triangle = SmoothTriangle::new(triangle.p1, triangle.p2, triangle.p3, normals[0], normals[1], normals[2]);
+        smooth_triangle.triangle.uvs = triangle.uvs;
+        smooth_triangle.triangle.material_index = triangle.material_index;
+        smooth_triangle.triangle.colors = triangle.colors;
+        smooth_triangle
+    }).collect()
+}
+
+// the model with smooth normals, its material palette is kept
+pub fn smooth_model(model: &TriangleModel, angle_threshold: Float) -> SmoothTriangleModel {
+    let mut smooth_model = SmoothTriangleModel::new(smooth_normals(&model.triangles, angle_threshold));
+    smooth_model.materials = model.materials.clone();
+    smooth_model
+}
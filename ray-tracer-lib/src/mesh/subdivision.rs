@@ -0,0 +1,178 @@
+use crate::core::math::{Float, PI};
+use crate::core::tuple::Tuple;
+use crate::mesh::error::MeshErrorReason;
+use crate::mesh::half_edge::{as_point, edge_key, HalfEdgeMesh};
+
+// Loop subdivision splits every triangle in 4 (the other polygons are triangulated first),
+// Catmull-Clark splits every polygon in quads, one per corner.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Subdivision { Loop, CatmullClark }
+
+// The creases are semi-sharp: an edge of sharpness s is subdivided with the sharp rules for
+// s levels, then with the smooth ones, a fractional sharpness blends the rules.
+// The boundary edges are hard creases. A vertex on two crease edges moves along them,
+// a vertex on more is a corner and does not move.
+impl HalfEdgeMesh {
+    // Loop subdivision fails on the polygons that cannot be triangulated without a non manifold edge
+    pub fn subdivide(&self, scheme: Subdivision, levels: usize) -> Result<HalfEdgeMesh, MeshErrorReason> {
+        let mut mesh = match scheme {
+            Subdivision::Loop if !self.is_triangle_mesh() => self.triangulated()?,
+            _ => self.clone(),
+        };
+        for _ in 0..levels {
+            mesh = match scheme {
+                Subdivision::Loop => mesh.loop_level(),
+                Subdivision::CatmullClark => mesh.catmull_clark_level(),
+            };
+        }
+        Ok(mesh)
+    }
+
+    fn loop_level(&self) -> HalfEdgeMesh {
+        let (neighbours, creases) = self.vertex_edges();
+        let mut vertices: Vec<Tuple> = (0..self.vertices.len()).map(|v| {
+            let p = self.vertices[v];
+            let n = neighbours[v].len();
+            if n == 0 {
+                return p;
+            }
+            let beta = (5.0 / 8.0 - (3.0 / 8.0 + (2.0 * PI / n as Float).cos() / 4.0).powi(2)) / n as Float;
+            let sum = neighbours[v].iter().fold(p * 0.0, |sum, neighbour| sum + self.vertices[*neighbour]);
+            let smooth = p * (1.0 - n as Float * beta) + sum * beta;
+            self.vertex_point(v, smooth, &creases[v])
+        }).collect();
+
+        let edge_points = self.edge_points(vertices.len());
+        for h in self.edges() {
+            let half_edge = &self.half_edges[h];
+            let (a, b) = (self.vertices[half_edge.origin], self.vertices[self.destination(h)]);
+            // the vertices facing the edge
+            let c = self.vertices[self.half_edges[half_edge.prev].origin];
+            let d = half_edge.twin.map_or(c, |twin| self.vertices[self.half_edges[self.half_edges[twin].prev].origin]);
+            let smooth = (a + b) * (3.0 / 8.0) + (c + d) * (1.0 / 8.0);
+            vertices.push(as_point(blend(smooth, (a + b) * 0.5, self.sharpness(h))));
+        }
+
+        let mut faces = vec![];
+        for face in 0..self.faces.len() {
+            let half_edges = self.face_half_edges(face);
+            let [h1, h2, h3] = [half_edges[0], half_edges[1], half_edges[2]];
+            let [a, b, c] = [h1, h2, h3].map(|h| self.half_edges[h].origin);
+            let [ab, bc, ca] = [h1, h2, h3].map(|h| edge_points[h]);
+            faces.extend([vec![a, ab, ca], vec![ab, b, bc], vec![ca, bc, c], vec![ab, bc, ca]]);
+        }
+        self.child(vertices, &faces, &edge_points)
+    }
+
+    fn catmull_clark_level(&self) -> HalfEdgeMesh {
+        let (neighbours, creases) = self.vertex_edges();
+        let face_points: Vec<Tuple> = (0..self.faces.len()).map(|face| {
+            let vertices = self.face_vertices(face);
+            let sum = vertices.iter().fold(self.vertices[vertices[0]] * 0.0, |sum, v| sum + self.vertices[*v]);
+            as_point(sum / vertices.len() as Float)
+        }).collect();
+        let mut faces_around: Vec<Vec<usize>> = vec![vec![]; self.vertices.len()];
+        for half_edge in self.half_edges.iter() {
+            faces_around[half_edge.origin].push(half_edge.face);
+        }
+
+        let mut vertices: Vec<Tuple> = (0..self.vertices.len()).map(|v| {
+            let p = self.vertices[v];
+            let n = neighbours[v].len();
+            if n == 0 || faces_around[v].is_empty() {
+                return p;
+            }
+            let f = faces_around[v].iter().fold(p * 0.0, |sum, face| sum + face_points[*face]) / faces_around[v].len() as Float;
+            let r = neighbours[v].iter().fold(p * 0.0, |sum, neighbour| sum + (p + self.vertices[*neighbour]) * 0.5) / n as Float;
+            let smooth = (f + r * 2.0 + p * (n as Float - 3.0)) / n as Float;
+            self.vertex_point(v, smooth, &creases[v])
+        }).collect();
+
+        let edge_points = self.edge_points(vertices.len());
+        for h in self.edges() {
+            let half_edge = &self.half_edges[h];
+            let (a, b) = (self.vertices[half_edge.origin], self.vertices[self.destination(h)]);
+            let smooth = match half_edge.twin {
+                Some(twin) => (a + b + face_points[half_edge.face] + face_points[self.half_edges[twin].face]) * 0.25,
+                None => (a + b) * 0.5,
+            };
+            vertices.push(as_point(blend(smooth, (a + b) * 0.5, self.sharpness(h))));
+        }
+
+        let first_face_point = vertices.len();
+        vertices.extend(face_points);
+        let mut faces = vec![];
+        for face in 0..self.faces.len() {
+            for h in self.face_half_edges(face) {
+                let half_edge = &self.half_edges[h];
+                faces.push(vec![half_edge.origin, edge_points[h], first_face_point + face, edge_points[half_edge.prev]]);
+            }
+        }
+        self.child(vertices, &faces, &edge_points)
+    }
+
+    // the neighbours of every vertex, and the crease edges around it with their sharpness
+    #[allow(clippy::type_complexity)]
+    fn vertex_edges(&self) -> (Vec<Vec<usize>>, Vec<Vec<(usize, Float)>>) {
+        let mut neighbours = vec![vec![]; self.vertices.len()];
+        let mut creases = vec![vec![]; self.vertices.len()];
+        for h in self.edges() {
+            let (a, b) = (self.half_edges[h].origin, self.destination(h));
+            neighbours[a].push(b);
+            neighbours[b].push(a);
+            let sharpness = self.sharpness(h);
+            if sharpness > 0.0 {
+                creases[a].push((b, sharpness));
+                creases[b].push((a, sharpness));
+            }
+        }
+        (neighbours, creases)
+    }
+
+    fn vertex_point(&self, v: usize, smooth: Tuple, creases: &[(usize, Float)]) -> Tuple {
+        let p = self.vertices[v];
+        let sharp = match creases {
+            [] | [_] => return as_point(smooth),
+            [(a, _), (b, _)] => (p * 6.0 + self.vertices[*a] + self.vertices[*b]) / 8.0,
+            _ => p,
+        };
+        let sharpness = creases.iter().map(|(_, sharpness)| sharpness).sum::<Float>() / creases.len() as Float;
+        as_point(blend(smooth, sharp, sharpness))
+    }
+
+    // the index of the new vertex of each half edge, the new vertices follow the ones of the edges
+    fn edge_points(&self, first: usize) -> Vec<usize> {
+        let mut edge_points = vec![0; self.half_edges.len()];
+        for (i, h) in self.edges().enumerate() {
+            edge_points[h] = first + i;
+            if let Some(twin) = self.half_edges[h].twin {
+                edge_points[twin] = first + i;
+            }
+        }
+        edge_points
+    }
+
+    // the halves of the crease edges are one level less sharp
+    fn child(&self, vertices: Vec<Tuple>, faces: &[Vec<usize>], edge_points: &[usize]) -> HalfEdgeMesh {
+        let mut mesh = HalfEdgeMesh::new(vertices, faces).expect("the subdivision of a valid mesh");
+        for h in self.edges().filter(|h| self.half_edges[*h].twin.is_some()) {
+            let (a, b) = (self.half_edges[h].origin, self.destination(h));
+            let sharpness = self.creases.get(&edge_key(a, b)).copied().unwrap_or(0.0) - 1.0;
+            if sharpness > 0.0 {
+                mesh.set_crease(a, edge_points[h], sharpness);
+                mesh.set_crease(edge_points[h], b, sharpness);
+            }
+        }
+        mesh
+    }
+}
+
+fn blend(smooth: Tuple, sharp: Tuple, sharpness: Float) -> Tuple {
+    if sharpness >= 1.0 {
+        sharp
+    } else if sharpness <= 0.0 {
+        smooth
+    } else {
+        smooth * (1.0 - sharpness) + sharp * sharpness
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use crate::core::math::{EPSILON, Float};
+use crate::core::tuple::{Tuple, vector};
+use crate::mesh::normals::vertex_key;
+use crate::shapes::smooth_triangle::SmoothTriangle;
+
+type CornerKey = ((u64, u64, u64), (u64, u64), (u64, u64, u64));
+
+// the corners with the same position, texture coordinates and normal share their tangent
+fn corner_key(p: &Tuple, uv: (Float, Float), n: &Tuple) -> CornerKey {
+    (vertex_key(p), ((uv.0 + 0.0).to_bits(), (uv.1 + 0.0).to_bits()), vertex_key(n))
+}
+
+// Per-vertex tangents for the normal maps of the triangles with texture coordinates: the texture
+// frames of the faces around a vertex are summed, the tangent is made perpendicular to the normal
+// and its w is the handedness of the bitangent. The triangles without texture coordinates, or
+// with a degenerate frame, keep the tangents of their faces.
+pub fn generate_tangents(smooth_triangles: &mut [SmoothTriangle]) {
+    let corners = |smooth_triangle: &SmoothTriangle| -> Option<[CornerKey; 3]> {
+        let triangle = &smooth_triangle.triangle;
+        let [uv1, uv2, uv3] = triangle.uvs?;
+        Some([corner_key(&triangle.p1, uv1, &smooth_triangle.n1),
+              corner_key(&triangle.p2, uv2, &smooth_triangle.n2),
+              corner_key(&triangle.p3, uv3, &smooth_triangle.n3)])
+    };
+
+    let mut frames: HashMap<CornerKey, (Tuple, Tuple)> = HashMap::new();
+    for smooth_triangle in smooth_triangles.iter() {
+        if let (Some(keys), Some((tangent, bitangent))) = (corners(smooth_triangle), smooth_triangle.triangle.face_uv_frame()) {
+            for key in keys {
+                let frame = frames.entry(key).or_insert((vector(0.0, 0.0, 0.0), vector(0.0, 0.0, 0.0)));
+                *frame = (frame.0 + tangent, frame.1 + bitangent);
+            }
+        }
+    }
+
+    for smooth_triangle in smooth_triangles.iter_mut() {
+        let keys = match corners(smooth_triangle) {
+            Some(keys) => keys,
+            None => continue,
+        };
+        let normals = [smooth_triangle.n1, smooth_triangle.n2, smooth_triangle.n3];
+        let mut tangents = [vector(0.0, 0.0, 0.0); 3];
+        for (i, key) in keys.iter().enumerate() {
+            let (tangent, bitangent) = match frames.get(key) {
+                Some(frame) => *frame,
+                None => break,
+            };
+            let n = normals[i];
+            let tangent = tangent - n * n.dot(&tangent);
+            if tangent.magnitude() < EPSILON {
+                break;
+            }
+            let tangent = tangent.normalize();
+            let handedness = if (n * &tangent).dot(&bitangent) < 0.0 { -1.0 } else { 1.0 };
+            tangents[i] = Tuple::new(tangent.x, tangent.y, tangent.z, handedness);
+        }
+        if tangents.iter().all(|tangent| tangent.w != 0.0) {
+            smooth_triangle.triangle.tangents = Some(tangents);
+        }
+    }
+}
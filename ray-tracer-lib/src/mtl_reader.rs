@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use crate::colors::Color;
+use crate::core::math::Float;
+use crate::material::Material;
+use crate::mesh::error::{MeshError, MeshErrorReason};
+use crate::patterns::image::Texture;
+use crate::patterns::normal_map::NormalMap;
+use crate::patterns::pattern::Pattern;
+
+// Reads a MTL material library:
+// Kd gives the color, Ka and Ks the ambient and specular factors, Ns the shininess,
+// d (or Tr) the transparency, Ni the refractive index, map_Kd an image pattern
+// and illum switches the highlights and the reflections.
+pub struct MtlReader<T> {
+    pub source : T,
+    pub directory : Option<PathBuf>,    // where the texture maps are, the current directory if None
+    pub materials : HashMap<String, Material>,
+}
+
+// the material being read, illum is applied once all the statements are known
+struct PendingMaterial {
+    name: String,
+    material: Material,
+    illum: Option<usize>,
+    specular_color: Float,
+}
+
+impl<T> MtlReader<T> where T: std::io::Read {
+    pub fn new(source : T) -> Self {
+        Self { source, directory: None, materials: HashMap::new() }
+    }
+
+    pub fn read(&mut self) -> Result<(), MeshError> {
+        let mut lines = vec![];
+        for (i, line_file) in BufReader::new(&mut self.source).lines().enumerate() {
+            match line_file {
+                Ok(line) => lines.push(line),
+                Err(error) => return Err(MeshError { line: i + 1, reason: MeshErrorReason::Io(error) }),
+            }
+        }
+
+        let mut pending: Option<PendingMaterial> = None;
+        for (i, line) in lines.iter().enumerate() {
+            self.read_line(line, &mut pending).map_err(|reason| MeshError { line: i + 1, reason })?;
+        }
+        if let Some(pending) = pending {
+            self.add_material(pending);
+        }
+        Ok(())
+    }
+
+    fn read_line(&mut self, line: &str, pending: &mut Option<PendingMaterial>) -> Result<(), MeshErrorReason> {
+        let items: Vec<&str> = line.split_whitespace().collect();
+        let keyword = match items.first() {
+            None => return Ok(()),
+            Some(keyword) if keyword.starts_with('#') => return Ok(()),
+            Some(keyword) => *keyword,
+        };
+        if keyword == "newmtl" {
+            let name = items.get(1).ok_or(MeshErrorReason::MissingValue)?;
+            if let Some(previous) = pending.take() {
+                self.add_material(previous);
+            }
+            *pending = Some(PendingMaterial { name: String::from(*name), material: Material::new(), illum: None, specular_color: 0.0 });
+            return Ok(());
+        }
+
+        let current = match pending.as_mut() {
+            Some(current) => current,
+            None => return Ok(()),
+        };
+        let material = &mut current.material;
+        match keyword {
+            "Kd" => {
+                material.color = Self::parse_color(&items)?;
+                material.diffuse = 1.0;
+            }
+            // MTL ambient colors are relative to a scene ambient light, they scale the default ambient
+            "Ka" => material.ambient = Self::average(&Self::parse_color(&items)?) * Material::new().ambient,
+            "Ks" => {
+                current.specular_color = Self::average(&Self::parse_color(&items)?);
+                material.specular = current.specular_color;
+            }
+            "Ns" => material.shininess = Self::parse_float(&items)?,
+            "d" => material.transparency = 1.0 - Self::parse_float(&items)?,
+            "Tr" => material.transparency = Self::parse_float(&items)?,
+            "Ni" => material.refractive_index = Self::parse_float(&items)?,
+            "illum" => {
+                let item = items.get(1).ok_or(MeshErrorReason::MissingValue)?;
+                current.illum = Some(item.parse::<usize>().map_err(|_| MeshErrorReason::InvalidNumber(String::from(*item)))?);
+            }
+            "map_Kd" => material.pattern = Pattern::image(self.texture(&items)?),
+            // a tangent-space normal map
+            "norm" => material.normal_map = NormalMap::image(self.texture(&items)?, 1.0),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn texture(&self, items: &[&str]) -> Result<Texture, MeshErrorReason> {
+        // the options (-s, -o, ...) come before the file name
+        let file_name = items.last().filter(|_| items.len() > 1).ok_or(MeshErrorReason::MissingValue)?;
+        let path = match &self.directory {
+            Some(directory) => directory.join(file_name),
+            None => PathBuf::from(file_name),
+        };
+        Texture::load(&path).map_err(|error| MeshErrorReason::Texture(format!("{}: {}", path.display(), error)))
+    }
+
+    fn add_material(&mut self, pending: PendingMaterial) {
+        let mut material = pending.material;
+        match pending.illum {
+            // color on, ambient off: a constant color
+            Some(0) => {
+                material.ambient = 1.0;
+                material.diffuse = 0.0;
+                material.specular = 0.0;
+            }
+            // no highlights
+            Some(1) => material.specular = 0.0,
+            // reflections on
+            Some(3..=8) => material.reflective = pending.specular_color,
+            _ => {}
+        }
+        self.materials.insert(pending.name, material);
+    }
+
+    fn parse_float(items: &[&str]) -> Result<Float, MeshErrorReason> {
+        let item = items.get(1).ok_or(MeshErrorReason::MissingValue)?;
+        item.parse::<Float>().map_err(|_| MeshErrorReason::InvalidNumber(String::from(*item)))
+    }
+
+    // a single value is a grey
+    fn parse_color(items: &[&str]) -> Result<Color, MeshErrorReason> {
+        let r = Self::parse_float(items)?;
+        if items.len() < 4 {
+            return Ok(Color::new(r, r, r));
+        }
+        let parse = |item: &str| item.parse::<Float>().map_err(|_| MeshErrorReason::InvalidNumber(String::from(item)));
+        Ok(Color::new(r, parse(items[2])?, parse(items[3])?))
+    }
+
+    fn average(color: &Color) -> Float {
+        (color.r + color.g + color.b) / 3.0
+    }
+}
@@ -1,7 +1,15 @@
 use std::collections::HashMap;
+use std::fs::File;
 use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use crate::colors::Color;
 use crate::core::math::Float;
 use crate::core::tuple::{point, Tuple, vector};
+use crate::material::Material;
+use crate::mesh::cache::{ModelCache, SourceStamp};
+use crate::mesh::error::{MeshError, MeshErrorReason};
+use crate::mesh::normals::smooth_normals;
+use crate::mtl_reader::MtlReader;
 use crate::shapes::smooth_triangle::SmoothTriangle;
 use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
 use crate::shapes::triangle::Triangle;
@@ -10,94 +18,315 @@ use crate::shapes::triangle_model::TriangleModel;
 pub struct ObjReader<T> {
     pub source : T,
     pub vertices : Vec<Tuple>,
+    pub vertex_colors : Vec<Option<Color>>,    // from the "v x y z r g b" vertices, parallel to the vertices
     pub normals : Vec<Tuple>,
+    pub texture_coordinates : Vec<(Float, Float)>,
+    pub material_names : Vec<String>,   // the usemtl names, a face material index refers to this list
+    pub material_libraries : Vec<String>,   // the mtllib names
+    pub faces : Vec<Vec<usize>>,   // the vertex indices of the polygons, starting at 0, before their triangulation
     pub triangles : Vec<Triangle>,
     pub smooth_triangles : Vec<SmoothTriangle>,
     pub models: HashMap<String, TriangleModel>,
     pub smooth_models: HashMap<String, SmoothTriangleModel>,
+    pub materials: HashMap<String, Material>,  // read from the mtllib statements, or given before reading
+    pub directory: Option<PathBuf>,  // where the material libraries are, they are not read if None
+    pub smooth_angle: Option<Float>,  // when set, the faces without normals get smooth normals, keeping the edges above this angle
+    pub lenient: bool,          // skip the malformed lines instead of failing
+    pub errors: Vec<MeshError>,  // the lines skipped in lenient mode
+    pub path: Option<PathBuf>,  // the source file, when known the models are cached next to it
+    pub use_cache: bool,
+}
+
+impl ObjReader<File> {
+    // the material libraries are looked for next to the file, and the cache is kept there
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, MeshError> {
+        let file = File::open(&path).map_err(|error| MeshError { line: 0, reason: MeshErrorReason::Io(error) })?;
+        let mut obj_reader = ObjReader::new(file);
+        obj_reader.directory = path.as_ref().parent().map(PathBuf::from);
+        obj_reader.path = Some(PathBuf::from(path.as_ref()));
+        Ok(obj_reader)
+    }
 }
 
 impl<T> ObjReader<T> where T: std::io::Read {
     pub fn new(source : T) -> Self  {
-        Self{source, vertices: vec![], normals: vec![],
-            triangles: vec![], smooth_triangles: vec![],
+        Self{source, vertices: vec![], vertex_colors: vec![], normals: vec![], texture_coordinates: vec![], material_names: vec![],
+            material_libraries: vec![], faces: vec![], triangles: vec![], smooth_triangles: vec![],
             models: HashMap::new(), smooth_models: HashMap::new(),
+            materials: HashMap::new(), directory: None,
+            smooth_angle: None, lenient: false, errors: vec![],
+            path: None, use_cache: true,
         } }
 
-    pub fn read(&mut self) {
-        let reader = BufReader::new(&mut self.source);
-        let mut current_name = String::from("Default");
+    // a cache matching the source is read instead of the source, otherwise it is written after reading the source
+    pub fn read(&mut self) -> Result<(), MeshError> {
+        let cache_path = self.path.as_deref().filter(|_| self.use_cache).map(ModelCache::path_for);
+        if let (Some(path), Some(cache_path)) = (&self.path, &cache_path) {
+            if let Some(cache) = ModelCache::load(cache_path, path, self.smooth_angle) {
+                return self.read_cache(cache);
+            }
+        }
 
-        for line_file in reader.lines() {
+        let mut lines = vec![];
+        for (i, line_file) in BufReader::new(&mut self.source).lines().enumerate() {
             match line_file {
-                Err(_) => {continue;}
-                Ok(line) => {
-                    if line.starts_with("v ") {
-                        let items : Vec<&str> = line.split(' ').collect();
-                        let x = items[1].parse::<Float>().unwrap();
-                        let y = items[2].parse::<Float>().unwrap();
-                        let z = items[3].parse::<Float>().unwrap();
-                        self.vertices.push(point(x, y, z));
-                    }
-                    if line.starts_with("vn ") {
-                        let items : Vec<&str> = line.split(' ').collect();
-                        let x = items[1].parse::<Float>().unwrap();
-                        let y = items[2].parse::<Float>().unwrap();
-                        let z = items[3].parse::<Float>().unwrap();
-                        self.normals.push(vector(x, y, z));
-                    }
-                    if line.starts_with("f ") {
-                        let items: Vec<&str> = line.split(' ').filter(|item| ! item.is_empty()).collect();
-                        for i in 1..items.len()-2 {
-                            let s1 : Vec<&str> = items[1].split('/').collect();
-                            let s2 : Vec<&str> = items[i+1].split('/').collect();
-                            let s3 : Vec<&str> = items[i+2].split('/').collect();
-                            let i1 = s1[0].parse::<usize>().unwrap() - 1;
-                            let i2 = s2[0].parse::<usize>().unwrap() - 1;
-                            let i3 = s3[0].parse::<usize>().unwrap() - 1;
-                            let v1 = &self.vertices[i1];
-                            let v2 = &self.vertices[i2];
-                            let v3 = &self.vertices[i3];
-                            if s1.len() == 3 {
-                                let i_n1 = s1[2].parse::<usize>().unwrap() - 1;
-                                let i_n2 = s2[2].parse::<usize>().unwrap() - 1;
-                                let i_n3 = s3[2].parse::<usize>().unwrap() - 1;
-                                let n1 = self.normals[i_n1];
-                                let n2 = self.normals[i_n2];
-                                let n3 = self.normals[i_n3];
-                                let smooth_triangle = SmoothTriangle::new(*v1, *v2, *v3, n1, n2, n3);
-                                self.smooth_triangles.push(smooth_triangle);
-                            } else {
-                                let triangle = Triangle::new(*v1, *v2, *v3);
-                                self.triangles.push(triangle);
-                            }
-                        }
-                    }
-                    if line.starts_with("g ") {
-                        let items: Vec<&str> = line.split(' ').collect();
-                        let name = String::from(items[1]);
-                        if self.triangles.len() > 0 {
-                            let triangle_model = TriangleModel::new(self.triangles.clone());
-                            self.models.insert(current_name.to_string(), triangle_model);
-                            self.triangles = vec![];
-                        } else {
-                            let smooth_triangle_model = SmoothTriangleModel::new(self.smooth_triangles.clone());
-                            self.smooth_models.insert(current_name.to_string(), smooth_triangle_model);
-                            self.smooth_triangles = vec![];
-                        }
-                        current_name = name;
+                Ok(line) => lines.push(line),
+                Err(error) => return Err(MeshError { line: i + 1, reason: MeshErrorReason::Io(error) }),
+            }
+        }
+
+        let mut current_name = String::from("Default");
+        let mut current_material = None;
+        for (i, line) in lines.iter().enumerate() {
+            if let Err(reason) = self.read_line(line, &mut current_name, &mut current_material) {
+                let error = MeshError { line: i + 1, reason };
+                if !self.lenient {
+                    return Err(error);
+                }
+                self.errors.push(error);
+            }
+        }
+        self.add_model(&current_name);
+        if let Some(angle) = self.smooth_angle {
+            self.generate_normals(angle);
+        }
+        // the sources read in lenient mode with errors are read again, to report them
+        if let Some(cache_path) = cache_path.filter(|_| self.errors.is_empty()) {
+            self.write_cache(&cache_path);
+        }
+        self.set_face_materials();
+        Ok(())
+    }
+
+    fn read_cache(&mut self, cache: ModelCache) -> Result<(), MeshError> {
+        self.models = cache.models;
+        self.smooth_models = cache.smooth_models;
+        self.material_names = cache.material_names;
+        self.vertices = cache.vertices;
+        self.vertex_colors = cache.vertex_colors;
+        self.normals = cache.normals;
+        self.texture_coordinates = cache.texture_coordinates;
+        self.faces = cache.faces;
+        for name in cache.material_libraries.iter() {
+            self.read_material_library(name).map_err(|reason| MeshError { line: 0, reason })?;
+        }
+        self.material_libraries = cache.material_libraries;
+        self.set_face_materials();
+        Ok(())
+    }
+
+    // a cache that cannot be written is not an error, the source is read again next time
+    fn write_cache(&mut self, cache_path: &Path) {
+        let stamp = match self.path.as_deref().map(SourceStamp::of) {
+            Some(Ok(stamp)) => stamp,
+            _ => return,
+        };
+        let cache = ModelCache {
+            smooth_angle: self.smooth_angle,
+            material_libraries: self.material_libraries.clone(),
+            material_names: self.material_names.clone(),
+            models: std::mem::take(&mut self.models),
+            smooth_models: std::mem::take(&mut self.smooth_models),
+            vertices: std::mem::take(&mut self.vertices),
+            vertex_colors: std::mem::take(&mut self.vertex_colors),
+            normals: std::mem::take(&mut self.normals),
+            texture_coordinates: std::mem::take(&mut self.texture_coordinates),
+            faces: std::mem::take(&mut self.faces),
+        };
+        let _ = cache.save(cache_path, &stamp);
+        self.models = cache.models;
+        self.smooth_models = cache.smooth_models;
+        self.vertices = cache.vertices;
+        self.vertex_colors = cache.vertex_colors;
+        self.normals = cache.normals;
+        self.texture_coordinates = cache.texture_coordinates;
+        self.faces = cache.faces;
+    }
+
+    // the flat models become smooth models
+    fn generate_normals(&mut self, angle: Float) {
+        for (name, model) in self.models.drain() {
+            let mut smooth_triangles = smooth_normals(&model.triangles, angle);
+            if let Some(smooth_model) = self.smooth_models.remove(&name) {
+                smooth_triangles.splice(0..0, smooth_model.smooth_triangles);
+            }
+            self.smooth_models.insert(name, SmoothTriangleModel::new(smooth_triangles));
+        }
+    }
+
+    // the faces with an unknown material get the default one
+    fn set_face_materials(&mut self) {
+        if self.materials.is_empty() {
+            return;
+        }
+        let palette: Vec<Material> = self.material_names.iter()
+            .map(|name| self.materials.get(name).cloned().unwrap_or_else(Material::new))
+            .collect();
+        for model in self.models.values_mut() {
+            model.materials = palette.clone();
+        }
+        for model in self.smooth_models.values_mut() {
+            model.materials = palette.clone();
+        }
+    }
+
+    fn read_material_library(&mut self, name: &str) -> Result<(), MeshErrorReason> {
+        let directory = match &self.directory {
+            Some(directory) => directory.clone(),
+            None => return Ok(()),
+        };
+        let path = directory.join(name);
+        let file = File::open(&path).map_err(MeshErrorReason::Io)?;
+        let mut mtl_reader = MtlReader::new(file);
+        mtl_reader.directory = Some(directory);
+        mtl_reader.read().map_err(|error| MeshErrorReason::MaterialLibrary(String::from(name), Box::new(error)))?;
+        self.materials.extend(mtl_reader.materials);
+        Ok(())
+    }
+
+    // a malformed line leaves the reader unchanged
+    fn read_line(&mut self, line: &str, current_name: &mut String, current_material: &mut Option<usize>) -> Result<(), MeshErrorReason> {
+        let items: Vec<&str> = line.split_whitespace().collect();
+        match items.first() {
+            Some(&"v") => {
+                let (x, y, z) = Self::parse_xyz(&items)?;
+                let color = match items.get(4..7) {
+                    Some([r, g, b]) => Some(Color::new(Self::parse_float(r)?, Self::parse_float(g)?, Self::parse_float(b)?)),
+                    _ => None,
+                };
+                self.vertices.push(point(x, y, z));
+                self.vertex_colors.push(color);
+            }
+            Some(&"vn") => {
+                let (x, y, z) = Self::parse_xyz(&items)?;
+                self.normals.push(vector(x, y, z));
+            }
+            Some(&"vt") => {
+                let u = Self::parse_float(items.get(1).ok_or(MeshErrorReason::MissingValue)?)?;
+                let v = match items.get(2) {
+                    Some(item) => Self::parse_float(item)?,
+                    None => 0.0,
+                };
+                self.texture_coordinates.push((u, v));
+            }
+            Some(&"f") => self.read_face(&items, *current_material)?,
+            // objects and groups both give a named model
+            Some(&"g") | Some(&"o") => {
+                let name = items.get(1).ok_or(MeshErrorReason::MissingValue)?;
+                self.add_model(current_name);
+                *current_name = String::from(*name);
+            }
+            Some(&"usemtl") => {
+                let name = items.get(1).ok_or(MeshErrorReason::MissingValue)?;
+                let index = match self.material_names.iter().position(|material_name| material_name == name) {
+                    Some(index) => index,
+                    None => {
+                        self.material_names.push(String::from(*name));
+                        self.material_names.len() - 1
                     }
+                };
+                *current_material = Some(index);
+            }
+            Some(&"mtllib") => {
+                for name in items[1..].iter() {
+                    self.read_material_library(name)?;
+                    self.material_libraries.push(String::from(*name));
                 }
             }
+            // smoothing groups are given by the vertex normals
+            _ => {}
         }
-        if self.triangles.len() > 0 {
-            let triangle_model = TriangleModel::new(self.triangles.clone());
-            self.models.insert(current_name.to_string(), triangle_model);
-            self.triangles = vec![];
-        } else {
-            let smooth_triangle_model = SmoothTriangleModel::new(self.smooth_triangles.clone());
-            self.smooth_models.insert(current_name.to_string(), smooth_triangle_model);
-            self.smooth_triangles = vec![];
+        Ok(())
+    }
+
+    // a vertex is v, v/vt, v//vn or v/vt/vn
+    fn read_face(&mut self, items: &[&str], material_index: Option<usize>) -> Result<(), MeshErrorReason> {
+        if items.len() < 4 {
+            return Err(MeshErrorReason::NotEnoughVertices);
+        }
+        let mut indices = vec![];
+        let mut vertices = vec![];
+        let mut colors = vec![];
+        let mut uvs = vec![];
+        let mut normals = vec![];
+        for item in items[1..].iter() {
+            let s: Vec<&str> = item.split('/').collect();
+            let i_v = Self::parse_index(s[0], self.vertices.len(), MeshErrorReason::VertexOutOfRange)?;
+            indices.push(i_v);
+            vertices.push(self.vertices[i_v]);
+            if let Some(color) = self.vertex_colors[i_v] {
+                colors.push(color);
+            }
+            // texture indices are ignored when the source has no texture coordinates
+            if let Some(item) = s.get(1).filter(|item| !item.is_empty() && !self.texture_coordinates.is_empty()) {
+                let i_vt = Self::parse_index(item, self.texture_coordinates.len(), MeshErrorReason::TextureOutOfRange)?;
+                uvs.push(self.texture_coordinates[i_vt]);
+            }
+            if let Some(item) = s.get(2).filter(|item| !item.is_empty()) {
+                let i_n = Self::parse_index(item, self.normals.len(), MeshErrorReason::NormalOutOfRange)?;
+                normals.push(self.normals[i_n]);
+            }
         }
+
+        self.faces.push(indices);
+
+        // fan triangulation
+        for i in 1..vertices.len() - 1 {
+            let mut triangle = Triangle::new(vertices[0], vertices[i], vertices[i + 1]);
+            if uvs.len() == vertices.len() {
+                triangle.uvs = Some([uvs[0], uvs[i], uvs[i + 1]]);
+            }
+            if colors.len() == vertices.len() {
+                triangle.colors = Some([colors[0], colors[i], colors[i + 1]]);
+            }
+            triangle.material_index = material_index;
+            if normals.len() == vertices.len() {
+                let smooth_triangle = SmoothTriangle { triangle, n1: normals[0], n2: normals[i], n3: normals[i + 1] };
+                self.smooth_triangles.push(smooth_triangle);
+            } else {
+                self.triangles.push(triangle);
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_float(item: &str) -> Result<Float, MeshErrorReason> {
+        item.parse::<Float>().map_err(|_| MeshErrorReason::InvalidNumber(String::from(item)))
+    }
+
+    fn parse_xyz(items: &[&str]) -> Result<(Float, Float, Float), MeshErrorReason> {
+        if items.len() < 4 {
+            return Err(MeshErrorReason::MissingValue);
+        }
+        Ok((Self::parse_float(items[1])?, Self::parse_float(items[2])?, Self::parse_float(items[3])?))
     }
-}
\ No newline at end of file
+
+    // OBJ indices start at 1, negative indices count back from the last element read.
+    // The returned index starts at 0.
+    fn parse_index(item: &str, count: usize, out_of_range: fn(isize) -> MeshErrorReason) -> Result<usize, MeshErrorReason> {
+        let index = item.parse::<isize>().map_err(|_| MeshErrorReason::InvalidIndex(String::from(item)))?;
+        let resolved = match index {
+            0 => return Err(MeshErrorReason::InvalidIndex(String::from(item))),
+            index if index > 0 => index - 1,
+            index => count as isize + index,
+        };
+        if resolved < 0 || resolved >= count as isize {
+            return Err(out_of_range(index));
+        }
+        Ok(resolved as usize)
+    }
+
+    // the faces read since the previous model are added to the model with this name
+    fn add_model(&mut self, name: &str) {
+        if !self.triangles.is_empty() {
+            let mut triangles = self.models.remove(name).map(|model| model.triangles).unwrap_or_default();
+            triangles.append(&mut self.triangles);
+            self.models.insert(name.to_string(), TriangleModel::new(triangles));
+        }
+        if !self.smooth_triangles.is_empty() {
+            let mut smooth_triangles = self.smooth_models.remove(name).map(|model| model.smooth_triangles).unwrap_or_default();
+            smooth_triangles.append(&mut self.smooth_triangles);
+            self.smooth_models.insert(name.to_string(), SmoothTriangleModel::new(smooth_triangles));
+        }
+    }
+}
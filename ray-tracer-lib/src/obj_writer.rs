@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use crate::mesh::faces::{MeshFace, model_faces, smooth_model_faces, world_faces};
+use crate::mesh::normals::vertex_key;
+use crate::object::Object;
+use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
+use crate::shapes::triangle_model::TriangleModel;
+
+// Writes triangles to an OBJ file, the shared vertices, normals and texture coordinates are written once.
+// The values are written with all their digits so ObjReader reads back the same triangles.
+pub struct ObjWriter<W> {
+    pub target : W,
+}
+
+impl ObjWriter<BufWriter<File>> {
+    pub fn create<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Ok(ObjWriter::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+// the index (from 1) of each distinct value, in the order they are met
+struct Indices<K> {
+    indices: HashMap<K, usize>,
+}
+
+impl<K: Hash + Eq> Indices<K> {
+    fn new() -> Self {
+        Indices { indices: HashMap::new() }
+    }
+
+    // the index, and whether the value is new
+    fn index(&mut self, key: K) -> (usize, bool) {
+        let count = self.indices.len();
+        let index = *self.indices.entry(key).or_insert(count + 1);
+        (index, index == count + 1)
+    }
+}
+
+impl<W> ObjWriter<W> where W: Write {
+    pub fn new(target : W) -> Self {
+        Self { target }
+    }
+
+    pub fn write_model(&mut self, model: &TriangleModel) -> std::io::Result<()> {
+        self.write_faces(&model_faces(model))
+    }
+
+    pub fn write_smooth_model(&mut self, model: &SmoothTriangleModel) -> std::io::Result<()> {
+        self.write_faces(&smooth_model_faces(model))
+    }
+
+    // the triangles of the object and its descendants, with their world transformations
+    pub fn write_object(&mut self, object: &Object) -> std::io::Result<()> {
+        self.write_faces(&world_faces(object))
+    }
+
+    // the vertices, texture coordinates and normals come first, then the faces
+    pub fn write_faces(&mut self, faces: &[MeshFace]) -> std::io::Result<()> {
+        let mut vertices = Indices::new();
+        let mut uvs = Indices::new();
+        let mut normals = Indices::new();
+        let mut vertex_lines = vec![];
+        let mut uv_lines = vec![];
+        let mut normal_lines = vec![];
+        let mut face_lines = vec![];
+        for face in faces.iter() {
+            let triangle = &face.triangle;
+            let mut corners = vec![];
+            for (i, p) in [triangle.p1, triangle.p2, triangle.p3].iter().enumerate() {
+                let (v, new) = vertices.index(vertex_key(p));
+                if new {
+                    vertex_lines.push(format!("v {} {} {}", p.x, p.y, p.z));
+                }
+                let vt = triangle.uvs.map(|triangle_uvs| {
+                    let (u, v) = triangle_uvs[i];
+                    let (vt, new) = uvs.index(((u + 0.0).to_bits(), (v + 0.0).to_bits()));
+                    if new {
+                        uv_lines.push(format!("vt {} {}", u, v));
+                    }
+                    vt
+                });
+                let vn = face.normals.map(|face_normals| {
+                    let n = face_normals[i];
+                    let (vn, new) = normals.index(vertex_key(&n));
+                    if new {
+                        normal_lines.push(format!("vn {} {} {}", n.x, n.y, n.z));
+                    }
+                    vn
+                });
+                corners.push(match (vt, vn) {
+                    (None, None) => v.to_string(),
+                    (Some(vt), None) => format!("{}/{}", v, vt),
+                    (None, Some(vn)) => format!("{}//{}", v, vn),
+                    (Some(vt), Some(vn)) => format!("{}/{}/{}", v, vt, vn),
+                });
+            }
+            face_lines.push(format!("f {}", corners.join(" ")));
+        }
+        for line in vertex_lines.iter().chain(uv_lines.iter()).chain(normal_lines.iter()).chain(face_lines.iter()) {
+            writeln!(self.target, "{}", line)?;
+        }
+        self.target.flush()
+    }
+}
@@ -1,16 +1,17 @@
+use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use ObjectType::ObjectGroup;
 use crate::core::bounds::Bounds;
 use crate::shapes::cone::Cone;
 use crate::shapes::cube::{Cube};
 use crate::shapes::cylinder::Cylinder;
-use crate::shapes::group::Group;
+use crate::shapes::group::{Group, GroupNode};
 use crate::core::intersection::Intersection;
 use crate::core::intersections::{Intersections, intersections};
 use crate::material::{Material};
 use crate::core::math::Float;
 use crate::core::matrix::Matrix;
-use crate::object::ObjectType::{CsgGroup, ObjectShape, SmoothTriangleGroup, TriangleGroup};
+use crate::object::ObjectType::{CsgGroup, ObjectInstance, ObjectShape, SmoothTriangleGroup, TriangleGroup};
 use crate::shapes::plane::Plane;
 use crate::core::ray::Ray;
 use crate::shapes::shape::Shape;
@@ -19,6 +20,9 @@ use crate::core::tuple::Tuple;
 use crate::shapes::csg::Csg;
 use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
 use crate::shapes::triangle_model::TriangleModel;
+use crate::shapes::superellipsoid::Superellipsoid;
+use crate::shapes::instance::Instance;
+use crate::shapes::uv_mapping::UvFrame;
 
 pub static OBJECT_COUNTER: AtomicUsize = AtomicUsize::new(0);
 pub static INTERSECTION_COUNTER: AtomicUsize = AtomicUsize::new(0);
@@ -31,13 +35,15 @@ pub struct Object {
     pub object_id : usize,
     pub object_type: ObjectType,
     material: Material,
+    explicit_material: bool,    // false while the material is the default one or inherited from a group
     transformation: Matrix<4>,
     transformation_inverse: Matrix<4>,    // optimization: keep inverse transformation
     transformation_inverse_transpose: Matrix<4>, // optimization: keep inverse transformation transpose
+    parent: Option<Arc<GroupNode>>,
 }
 
 #[derive(Debug, Clone)]
-pub enum ObjectType { ObjectShape(Shape), ObjectGroup(Group), TriangleGroup(TriangleModel), SmoothTriangleGroup(SmoothTriangleModel), CsgGroup(Csg)}
+pub enum ObjectType { ObjectShape(Shape), ObjectGroup(Group), TriangleGroup(TriangleModel), SmoothTriangleGroup(SmoothTriangleModel), CsgGroup(Csg), ObjectInstance(Instance)}
 
 impl Object {
     pub fn group(&self) -> Option<&Group> {
@@ -50,11 +56,35 @@ impl Object {
     pub fn normal_to_world(&self, normal: &Tuple) -> Tuple {
         let mut n = &self.transformation_inverse_transpose * normal;
         n.w = 0.0;
-        n.normalize()
+        let n = n.normalize();
+        match &self.parent {
+            Some(parent) => parent.normal_to_world(&n),
+            None => n,
+        }
+    }
+
+    // a direction along the surface, as a tangent, from the object space to the world space
+    pub fn vector_to_world(&self, v: &Tuple) -> Tuple {
+        let mut v = &self.transformation * v;
+        v.w = 0.0;
+        match &self.parent {
+            Some(parent) => parent.vector_to_world(&v),
+            None => v,
+        }
+    }
+
+    // the texture frame of a point of the surface, with its directions in world space
+    pub fn uv_frame(&self, world_point: &Tuple, hit: &Intersection) -> Option<UvFrame> {
+        let frame = self.shape()?.uv_frame(&self.world_to_object(world_point), hit)?;
+        Some(UvFrame { uv: frame.uv, tangent: self.vector_to_world(&frame.tangent), bitangent: self.vector_to_world(&frame.bitangent) })
     }
 
     pub fn world_to_object(&self, point: &Tuple) -> Tuple {
-        &(self.transformation_inverse) * point
+        let p = match &self.parent {
+            Some(parent) => parent.world_to_object(point),
+            None => *point,
+        };
+        &(self.transformation_inverse) * &p
     }
 
     pub fn normal_at(&self, world_point: Tuple, hit: &Intersection) -> Tuple {
@@ -65,6 +95,7 @@ impl Object {
             TriangleGroup(_) => panic!("No !"),
             SmoothTriangleGroup(_) => panic!("No !"),
             CsgGroup(_) => panic!("No !"),
+            ObjectInstance(_) => panic!("No !"),
         };
         let n = self.normal_to_world(&local_normal);
         n
@@ -76,31 +107,52 @@ impl Object {
         }
 
         return match &self.object_type {
+            ObjectShape(Shape::Voxels(voxels)) => {
+                let transformed_ray = ray.transform(&self.transformation_inverse);
+                intersections(voxels.intersect_with_index(&transformed_ray).into_iter().map(|(t, material_index)| {
+                    let mut obj = self.clone();
+                    if let Some(material) = voxels.material(material_index) {
+                        obj.set_material(material.clone());
+                    }
+                    Intersection::new(t, obj)
+                }).collect())
+            },
             ObjectShape(shape) => {
                 let transformed_ray = ray.transform(&self.transformation_inverse);
                 intersections(shape.intersect(&transformed_ray).iter().map(|t| Intersection::new(*t, self.clone() )).collect())
             },
             ObjectGroup(group) => group.intersect(&ray),
             TriangleGroup(model) => {
-
-                let v = model.intersect(&ray).into_iter().map(|(t, triangle)| {
+                let transformed_ray = ray.transform(&self.transformation_inverse);
+                let v = model.intersect(&transformed_ray).into_iter().map(|(t, triangle, u, v)| {
                     let id =  triangle.id;
-                    let mut obj = Object::new_with_id(Shape::Triangle(triangle), id);
-                    obj.set_material(self.material);
-                    Intersection::new(t, obj)
+                    let texture_uv = triangle.texture_uv(u, v);
+                    let vertex_color = triangle.vertex_color(u, v);
+                    let obj = self.mesh_part(Shape::Triangle(triangle), id, model.face_material(&triangle));
+                    let mut x = Intersection::new_uv(t, obj, u, v);
+                    x.texture_uv = texture_uv;
+                    x.vertex_color = vertex_color;
+                    x
                 }).collect();
                 intersections(v)
             },
             SmoothTriangleGroup(model) => {
-                let v = model.intersect(&ray).into_iter().map(|(t, smooth_triangle, u, v)| {
+                let transformed_ray = ray.transform(&self.transformation_inverse);
+                let v = model.intersect(&transformed_ray).into_iter().map(|(t, smooth_triangle, u, v)| {
                     let id  = smooth_triangle.triangle.id;
-                    let mut obj = Object::new_with_id(Shape::SmoothTriangle(smooth_triangle), id);
-                    obj.set_material(self.material);
-                    Intersection::new_uv(t, obj, u, v)
+                    let texture_uv = smooth_triangle.triangle.texture_uv(u, v);
+                    let vertex_color = smooth_triangle.triangle.vertex_color(u, v);
+                    let material = model.face_material(&smooth_triangle.triangle);
+                    let obj = self.mesh_part(Shape::SmoothTriangle(smooth_triangle), id, material);
+                    let mut x = Intersection::new_uv(t, obj, u, v);
+                    x.texture_uv = texture_uv;
+                    x.vertex_color = vertex_color;
+                    x
                 }).collect();
                 intersections(v)
             },
-            CsgGroup(csg) => csg.intersect(&ray)
+            CsgGroup(csg) => csg.intersect(&ray),
+            ObjectInstance(instance) => instance.intersect(ray, &self.transformation, &self.transformation_inverse, &self.parent),
         };
     }
 
@@ -108,9 +160,10 @@ impl Object {
         return match &self.object_type {
             ObjectShape(shape) => shape.bounds().transform(&self.transformation),
             ObjectGroup(group) => group.bounds(),
-            TriangleGroup(model) => model.bounds(),
-            SmoothTriangleGroup(model) => model.bounds(),
-            CsgGroup(csg) => csg.bounds()
+            TriangleGroup(model) => model.bounds().transform(&self.transformation),
+            SmoothTriangleGroup(model) => model.bounds().transform(&self.transformation),
+            CsgGroup(csg) => csg.bounds(),
+            ObjectInstance(instance) => instance.bounds(&self.transformation),
         };
     }
     pub(crate) fn includes(&self, other_obj: &Object) -> bool {
@@ -119,30 +172,33 @@ impl Object {
             ObjectGroup(object_group) => object_group.includes(other_obj),
             TriangleGroup(triangle_group) => triangle_group.includes(other_obj),
             SmoothTriangleGroup(smooth_triangle_group) => smooth_triangle_group.includes(other_obj),
-            CsgGroup(csg) => csg.includes(other_obj)
+            CsgGroup(csg) => csg.includes(other_obj),
+            ObjectInstance(instance) => instance.includes(other_obj),
         }
     }
 
+    // only the local transformation is stored, the children of a group are left untouched
     pub fn set_transformation(&mut self, transformation: Matrix<4>) -> &Self {
-
         match &mut self.object_type {
-            ObjectGroup(group) => {
-                group.set_transformation(transformation);
-            },
-            ObjectShape(_) => {
-                self.transformation = transformation;
-                self.transformation_inverse = self.transformation.inverse();
-                self.transformation_inverse_transpose = self.transformation_inverse.transpose();
-            }
-            TriangleGroup(model) => {
-                model.set_transformation(transformation);
-            }
-            SmoothTriangleGroup(model) => model.set_transformation(transformation),
+            ObjectGroup(group) => group.set_transformation(transformation),
             CsgGroup(csg) => csg.set_transformation(transformation),
+            _ => {}
         }
+        self.transformation = transformation;
+        self.transformation_inverse = self.transformation.inverse();
+        self.transformation_inverse_transpose = self.transformation_inverse.transpose();
         self
     }
 
+    pub(crate) fn set_parent(&mut self, parent: Option<Arc<GroupNode>>) {
+        match &self.object_type {
+            ObjectGroup(group) => group.set_parent(parent.clone()),
+            CsgGroup(csg) => csg.set_parent(parent.clone()),
+            _ => {}
+        }
+        self.parent = parent;
+    }
+
     pub fn transformation(&self) -> &Matrix<4> {
         &self.transformation
     }
@@ -156,18 +212,75 @@ impl Object {
     }}
     pub fn material(&self) -> &Material { &(self.material) }
     pub fn set_material(&mut self, material: Material) -> &Self {
-        self.material = material;
+        if let ObjectInstance(instance) = &mut self.object_type {
+            instance.material = Some(material.clone());
+        }
+        self.explicit_material = true;
+        self.apply_material(material);
         self
     }
 
+    // the material of the nearest ancestor, unless the object has its own.
+    // Instances keep the materials of their shared object.
+    pub(crate) fn inherit_material(&mut self, material: Material) {
+        if !self.explicit_material {
+            self.apply_material(material);
+        }
+    }
+
+    fn apply_material(&mut self, material: Material) {
+        match &mut self.object_type {
+            ObjectGroup(group) => group.inherit_material(material.clone()),
+            CsgGroup(csg) => csg.group.inherit_material(material.clone()),
+            _ => {}
+        }
+        self.material = material;
+    }
+
+    // a triangle of a mesh, placed like the mesh itself, with the face material or the mesh one
+    fn mesh_part(&self, shape: Shape, id: usize, face_material: Option<&Material>) -> Object {
+        let mut object = Object::new_with_id(shape, id);
+        object.material = face_material.unwrap_or(&self.material).clone();
+        object.transformation = self.transformation;
+        object.transformation_inverse = self.transformation_inverse;
+        object.transformation_inverse_transpose = self.transformation_inverse_transpose;
+        object.parent = self.parent.clone();
+        object
+    }
+
+    // the transformation from the object space to the space of the root of its hierarchy, and its inverse
+    pub(crate) fn transformation_to_root(&self) -> (Matrix<4>, Matrix<4>) {
+        match &self.parent {
+            Some(parent) => {
+                let (transformation, transformation_inverse) = parent.transformation_to_world();
+                (&transformation * &self.transformation, &self.transformation_inverse * &transformation_inverse)
+            },
+            None => (self.transformation, self.transformation_inverse),
+        }
+    }
+
+    // the same object taken out of its hierarchy and placed under a new parent with the given transformation,
+    // the inverse is given to avoid computing it again
+    pub(crate) fn placed_in(&self, transformation: &Matrix<4>, transformation_inverse: &Matrix<4>, parent: &Option<Arc<GroupNode>>) -> Object {
+        let (local_transformation, local_transformation_inverse) = self.transformation_to_root();
+        let mut object = self.clone();
+        object.transformation = transformation * &local_transformation;
+        object.transformation_inverse = &local_transformation_inverse * transformation_inverse;
+        object.transformation_inverse_transpose = object.transformation_inverse.transpose();
+        object.parent = parent.clone();
+        object
+    }
+
     pub fn new(shape: Shape) -> Object {
         Object {
             object_id: get_next_unique_shape_id(),
             object_type: ObjectShape(shape),
             material: Material::new(),
+            explicit_material: false,
             transformation: Matrix::<4>::identity(),
             transformation_inverse: Matrix::<4>::identity(),
             transformation_inverse_transpose: Matrix::<4>::identity(),
+            parent: None,
         }
     }
 
@@ -176,21 +289,28 @@ impl Object {
             object_id: id,
             object_type: ObjectShape(shape),
             material: Material::new(),
+            explicit_material: false,
             transformation: Matrix::<4>::identity(),
             transformation_inverse: Matrix::<4>::identity(),
             transformation_inverse_transpose: Matrix::<4>::identity(),
+            parent: None,
         }
     }
 
     pub fn new_group(group: Group) -> Object {
-        Object {
+        let transformation = *group.transformation();
+        let mut object = Object {
             object_id: get_next_unique_shape_id(),
             object_type: ObjectGroup(group),
             material: Material::new(),
+            explicit_material: false,
             transformation: Matrix::<4>::identity(),
             transformation_inverse: Matrix::<4>::identity(),
             transformation_inverse_transpose: Matrix::<4>::identity(),
-        }
+            parent: None,
+        };
+        object.set_transformation(transformation);
+        object
     }
 
     pub fn new_triangle_group(model: TriangleModel) -> Object {
@@ -198,9 +318,11 @@ impl Object {
             object_id: get_next_unique_shape_id(),
             object_type: TriangleGroup(model),
             material: Material::new(),
+            explicit_material: false,
             transformation: Matrix::<4>::identity(),
             transformation_inverse: Matrix::<4>::identity(),
             transformation_inverse_transpose: Matrix::<4>::identity(),
+            parent: None,
         }
     }
 
@@ -209,20 +331,40 @@ impl Object {
             object_id: get_next_unique_shape_id(),
             object_type: SmoothTriangleGroup(model),
             material: Material::new(),
+            explicit_material: false,
             transformation: Matrix::<4>::identity(),
             transformation_inverse: Matrix::<4>::identity(),
             transformation_inverse_transpose: Matrix::<4>::identity(),
+            parent: None,
         }
     }
 
     pub fn new_csg(csg: Csg) -> Object {
-        Object {
+        let transformation = *csg.group.transformation();
+        let mut object = Object {
             object_id: get_next_unique_shape_id(),
             object_type: CsgGroup(csg),
             material: Material::new(),
+            explicit_material: false,
             transformation: Matrix::<4>::identity(),
             transformation_inverse: Matrix::<4>::identity(),
             transformation_inverse_transpose: Matrix::<4>::identity(),
+            parent: None,
+        };
+        object.set_transformation(transformation);
+        object
+    }
+
+    pub fn new_instance(object: Arc<Object>) -> Object {
+        Object {
+            object_id: get_next_unique_shape_id(),
+            object_type: ObjectInstance(Instance::new(object)),
+            material: Material::new(),
+            explicit_material: false,
+            transformation: Matrix::<4>::identity(),
+            transformation_inverse: Matrix::<4>::identity(),
+            transformation_inverse_transpose: Matrix::<4>::identity(),
+            parent: None,
         }
     }
 
@@ -232,7 +374,8 @@ impl Object {
             ObjectGroup(group) => group.get_child_ids(),
             TriangleGroup(triangle_group) => triangle_group.get_child_ids(),
             SmoothTriangleGroup(smooth_triangle_group) => smooth_triangle_group.get_child_ids(),
-            CsgGroup(csg) => csg.get_child_ids()
+            CsgGroup(csg) => csg.get_child_ids(),
+            ObjectInstance(instance) => instance.get_child_ids(),
         }
     }
 }
@@ -273,3 +416,8 @@ pub fn build_cone(min: Float, max: Float) -> Object {
     let cone = Shape::Cone(Cone::from(min, max, true));
     Object::new(cone)
 }
+
+pub fn build_superellipsoid(e1: Float, e2: Float) -> Object {
+    let superellipsoid = Shape::Superellipsoid(Superellipsoid::new(e1, e2));
+    Object::new(superellipsoid)
+}
@@ -0,0 +1,64 @@
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+use std::sync::Arc;
+use crate::colors::Color;
+use crate::core::math::Float;
+use crate::core::tuple::Tuple;
+
+#[derive(Debug)]
+pub struct Texture {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<Color>,
+}
+
+impl Texture {
+    // pixels are given row by row, from the top left corner
+    pub fn new(width: usize, height: usize, pixels: Vec<Color>) -> Result<Self, Error> {
+        if width == 0 || height == 0 || pixels.len() != width * height {
+            return Err(Error::new(ErrorKind::InvalidInput, format!("{} pixels for a {}x{} texture", pixels.len(), width, height)));
+        }
+        Ok(Texture { width, height, pixels })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Texture, image::ImageError> {
+        let image = image::open(path)?.into_rgb8();
+        let pixels = image.pixels().map(|pixel| Color::new(
+            pixel[0] as Float / 255.0,
+            pixel[1] as Float / 255.0,
+            pixel[2] as Float / 255.0,
+        )).collect();
+        Texture::new(image.width() as usize, image.height() as usize, pixels).map_err(image::ImageError::IoError)
+    }
+
+    pub fn pixel_at(&self, x: usize, y: usize) -> Color {
+        self.pixels[y * self.width + x]
+    }
+}
+
+// An image mapped with texture coordinates: (0, 0) is the bottom left corner, (1, 1) the top right corner.
+// The texture is shared by the copies of the pattern.
+#[derive(Debug, Clone)]
+pub struct ImagePattern {
+    pub texture: Arc<Texture>,
+}
+
+impl ImagePattern {
+    pub fn new(texture: Texture) -> Self {
+        ImagePattern { texture: Arc::new(texture) }
+    }
+
+    pub fn uv_pattern_at(&self, u: Float, v: Float) -> Color {
+        let texture = &self.texture;
+        let u = u - u.floor();
+        let v = v - v.floor();
+        let x = ((u * texture.width as Float) as usize).min(texture.width - 1);
+        let y = (((1.0 - v) * texture.height as Float) as usize).min(texture.height - 1);
+        texture.pixel_at(x, y)
+    }
+
+    // without texture coordinates the image is repeated on the xz plane
+    pub fn pattern_at(&self, point: &Tuple) -> Color {
+        self.uv_pattern_at(point.x, point.z)
+    }
+}
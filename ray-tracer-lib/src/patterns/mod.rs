@@ -3,3 +3,6 @@ pub mod stripe;
 pub mod gradient;
 pub mod ring;
 pub mod checker;
+pub mod image;
+pub mod noise;
+pub mod normal_map;
@@ -0,0 +1,62 @@
+use crate::core::math::Float;
+use crate::core::tuple::Tuple;
+
+// the pseudo-random gradient of a lattice point, one of 16
+fn lattice_hash(x: i64, y: i64, z: i64) -> usize {
+    let mut h = (x.wrapping_mul(73856093) ^ y.wrapping_mul(19349663) ^ z.wrapping_mul(83492791)) as u64;
+    h ^= h >> 13;
+    h = h.wrapping_mul(0x5bd1e995);
+    h ^= h >> 15;
+    (h & 15) as usize
+}
+
+// the dot product of the offset with one of the 12 edge directions of a cube (4 are repeated)
+fn gradient(hash: usize, x: Float, y: Float, z: Float) -> Float {
+    let u = if hash < 8 { x } else { y };
+    let v = match hash {
+        0..=3 => y,
+        12 | 14 => x,
+        _ => z,
+    };
+    (if hash & 1 == 0 { u } else { -u }) + (if hash & 2 == 0 { v } else { -v })
+}
+
+fn fade(t: Float) -> Float {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: Float, a: Float, b: Float) -> Float {
+    a + t * (b - a)
+}
+
+// Improved Perlin noise, between -1 and 1 and 0 at the integer points.
+pub fn perlin_noise(p: &Tuple) -> Float {
+    let (xf, yf, zf) = (p.x.floor(), p.y.floor(), p.z.floor());
+    let (x, y, z) = (xf as i64, yf as i64, zf as i64);
+    let (dx, dy, dz) = (p.x - xf, p.y - yf, p.z - zf);
+    let (u, v, w) = (fade(dx), fade(dy), fade(dz));
+
+    let corner = |i: i64, j: i64, k: i64| {
+        gradient(lattice_hash(x + i, y + j, z + k), dx - i as Float, dy - j as Float, dz - k as Float)
+    };
+    let noise = lerp(w,
+        lerp(v, lerp(u, corner(0, 0, 0), corner(1, 0, 0)), lerp(u, corner(0, 1, 0), corner(1, 1, 0))),
+        lerp(v, lerp(u, corner(0, 0, 1), corner(1, 0, 1)), lerp(u, corner(0, 1, 1), corner(1, 1, 1))));
+    noise.clamp(-1.0, 1.0)
+}
+
+// Octaves of noise of doubling frequencies, each weighted by the persistence times the previous
+// one, normalized between -1 and 1.
+pub fn fractal_noise(p: &Tuple, octaves: usize, persistence: Float) -> Float {
+    let mut sum = 0.0;
+    let mut amplitudes = 0.0;
+    let mut amplitude = 1.0;
+    let mut frequency = 1.0;
+    for _ in 0..octaves {
+        sum += perlin_noise(&(*p * frequency)) * amplitude;
+        amplitudes += amplitude;
+        amplitude *= persistence;
+        frequency *= 2.0;
+    }
+    if amplitudes > 0.0 { sum / amplitudes } else { 0.0 }
+}
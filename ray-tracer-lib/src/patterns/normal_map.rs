@@ -0,0 +1,66 @@
+use crate::core::intersection::Intersection;
+use crate::core::math::{EPSILON, Float};
+use crate::core::tuple::{Tuple, vector};
+use crate::object::Object;
+use crate::patterns::image::{ImagePattern, Texture};
+use crate::patterns::pattern::Pattern;
+
+// the step of the height differences of bump maps
+const BUMP_DELTA: Float = 0.0001;
+
+// How the normals of a surface are changed before shading.
+#[derive(Debug, Clone)]
+pub enum NormalMap {
+    None,
+    // the brightness of the pattern, in object space, is a height above the surface, times the scale
+    Bump { pattern: Pattern, scale: Float },
+    // the colors of the image are normals in the texture frame of the surface: red along u,
+    // green along v and blue along the normal, the strength scales the slopes
+    Image { image: ImagePattern, strength: Float },
+}
+
+impl NormalMap {
+    pub fn bump(pattern: Pattern, scale: Float) -> NormalMap {
+        NormalMap::Bump { pattern, scale }
+    }
+
+    pub fn image(texture: Texture, strength: Float) -> NormalMap {
+        NormalMap::Image { image: ImagePattern::new(texture), strength }
+    }
+
+    // the normal at a point of a hit, from its outward world normal; image maps keep the normal
+    // where the surface has no texture coordinates
+    pub fn normal_at(&self, object: &Object, world_point: &Tuple, normal: Tuple, hit: &Intersection) -> Tuple {
+        match self {
+            NormalMap::None => normal,
+            NormalMap::Bump { pattern, scale } => {
+                let height = |x: Float, y: Float, z: Float| {
+                    pattern.height_at(&object.world_to_object(&(*world_point + vector(x, y, z))), None)
+                };
+                let gradient = vector(
+                    height(BUMP_DELTA, 0.0, 0.0) - height(-BUMP_DELTA, 0.0, 0.0),
+                    height(0.0, BUMP_DELTA, 0.0) - height(0.0, -BUMP_DELTA, 0.0),
+                    height(0.0, 0.0, BUMP_DELTA) - height(0.0, 0.0, -BUMP_DELTA),
+                ) / (2.0 * BUMP_DELTA);
+                // only the slope along the surface tilts the normal
+                let slope = gradient - normal * gradient.dot(&normal);
+                (normal - slope * *scale).normalize()
+            }
+            NormalMap::Image { image, strength } => {
+                let frame = match object.uv_frame(world_point, hit) {
+                    Some(frame) => frame,
+                    None => return normal,
+                };
+                let tangent = frame.tangent - normal * frame.tangent.dot(&normal);
+                let bitangent = frame.bitangent - normal * frame.bitangent.dot(&normal);
+                if tangent.magnitude() < EPSILON || bitangent.magnitude() < EPSILON {
+                    return normal;
+                }
+                let color = image.uv_pattern_at(frame.uv.0, frame.uv.1);
+                let (x, y, z) = (color.r * 2.0 - 1.0, color.g * 2.0 - 1.0, color.b * 2.0 - 1.0);
+                let mapped = tangent.normalize() * (x * strength) + bitangent.normalize() * (y * strength) + normal * z;
+                if mapped.magnitude() < EPSILON { normal } else { mapped.normalize() }
+            }
+        }
+    }
+}
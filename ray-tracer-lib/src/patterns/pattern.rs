@@ -3,11 +3,13 @@ use crate::core::matrix::Matrix;
 use crate::object::Object;
 use crate::patterns::checker::CheckerPattern;
 use crate::patterns::gradient::GradientPattern;
+use crate::patterns::image::{ImagePattern, Texture};
 use crate::patterns::ring::RingPattern;
 use crate::patterns::stripe::StripePattern;
+use crate::core::math::Float;
 use crate::core::tuple::Tuple;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct Pattern {
     pub pattern : Patterns,
     pub inverse_transform : Matrix<4>,
@@ -40,21 +42,54 @@ impl Pattern {
         Self::from(Patterns::Checker(CheckerPattern::new(color_a, color_b)))
     }
 
+    pub fn image(texture: Texture) -> Pattern {
+        Self::from(Patterns::Image(ImagePattern::new(texture)))
+    }
+
+    // the vertex colors of the meshes, the material color elsewhere
+    pub fn vertex_color() -> Pattern {
+        Self::from(Patterns::VertexColor)
+    }
+
     pub fn test() -> Pattern {
         Self::from(Patterns::Test)
     }
 
     pub fn pattern_at_object(&self, object: &Object, world_point: Tuple) -> Color {
-        let object_point = object.transformation_inverse() * &world_point;
-        let pattern_point = &self.inverse_transform * &object_point;
-        match self.pattern {
-            Patterns::None => {object.material().color}
-            Patterns::Stripe(stripes) => {stripes.pattern_at(&pattern_point)}
-            Patterns::Test => {Color::new(pattern_point.x, pattern_point.y, pattern_point.z)}
-            Patterns::Gradient(gradient) => {gradient.pattern_at(&pattern_point)}
-            Patterns::Ring(ring) => {ring.pattern_at(&pattern_point)}
-            Patterns::Checker(checker) => {checker.pattern_at(&pattern_point)}
+        self.pattern_at_object_uv(object, world_point, None, None)
+    }
+
+    // the texture coordinates of the hit, when known, are used by image patterns,
+    // and its interpolated vertex color by vertex color patterns
+    pub fn pattern_at_object_uv(&self, object: &Object, world_point: Tuple, texture_uv: Option<(Float, Float)>, vertex_color: Option<Color>) -> Color {
+        if let (Patterns::VertexColor, Some(color)) = (&self.pattern, vertex_color) {
+            return color;
         }
+        let object_point = object.world_to_object(&world_point);
+        self.pattern_at(&object_point, texture_uv).unwrap_or(object.material().color)
+    }
+
+    // the color at a point in object space, None for the patterns giving the material color
+    pub fn pattern_at(&self, object_point: &Tuple, texture_uv: Option<(Float, Float)>) -> Option<Color> {
+        if let (Patterns::Image(image), Some((u, v))) = (&self.pattern, texture_uv) {
+            return Some(image.uv_pattern_at(u, v));
+        }
+        let pattern_point = &self.inverse_transform * object_point;
+        match &self.pattern {
+            Patterns::None => {None}
+            Patterns::Stripe(stripes) => {Some(stripes.pattern_at(&pattern_point))}
+            Patterns::Test => {Some(Color::new(pattern_point.x, pattern_point.y, pattern_point.z))}
+            Patterns::Gradient(gradient) => {Some(gradient.pattern_at(&pattern_point))}
+            Patterns::Ring(ring) => {Some(ring.pattern_at(&pattern_point))}
+            Patterns::Checker(checker) => {Some(checker.pattern_at(&pattern_point))}
+            Patterns::Image(image) => {Some(image.pattern_at(&pattern_point))}
+            Patterns::VertexColor => {None}
+        }
+    }
+
+    // the brightness of the pattern, used as a height by displacements
+    pub fn height_at(&self, object_point: &Tuple, texture_uv: Option<(Float, Float)>) -> Float {
+        self.pattern_at(object_point, texture_uv).map_or(0.0, |color| (color.r + color.g + color.b) / 3.0)
     }
 
     pub fn set_pattern_transform(&mut self, transform: &Matrix<4>) {
@@ -62,12 +97,14 @@ impl Pattern {
     }
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub enum Patterns {
     None,
     Stripe(StripePattern),
     Test,
     Gradient(GradientPattern),
     Ring(RingPattern),
-    Checker(CheckerPattern)
+    Checker(CheckerPattern),
+    Image(ImagePattern),
+    VertexColor,
 }
@@ -0,0 +1,300 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Read;
+use std::str::SplitWhitespace;
+use crate::colors::Color;
+use crate::core::math::Float;
+use crate::core::tuple::{point, Tuple, vector};
+use crate::mesh::bytes::Bytes;
+use crate::mesh::error::{MeshError, MeshErrorReason};
+use crate::shapes::smooth_triangle::SmoothTriangle;
+use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
+use crate::shapes::triangle::Triangle;
+use crate::shapes::triangle_model::TriangleModel;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum PlyFormat { Ascii, BinaryLittleEndian, BinaryBigEndian }
+
+#[derive(Debug, Copy, Clone)]
+enum PlyType { Int8, UInt8, Int16, UInt16, Int32, UInt32, Float32, Float64 }
+
+#[derive(Debug)]
+enum PlyProperty {
+    Scalar(String, PlyType),
+    List(String, PlyType, PlyType),  // the type of the count, then the type of the items
+}
+
+#[derive(Debug)]
+struct PlyElement {
+    name: String,
+    count: usize,
+    properties: Vec<PlyProperty>,
+}
+
+struct PlyHeader {
+    format: PlyFormat,
+    elements: Vec<PlyElement>,
+    lines: usize,
+}
+
+// the values of the body, a line per element in ASCII files
+enum Values<'a, 'b> {
+    Ascii(SplitWhitespace<'a>),
+    Binary(&'b mut Bytes<'a>),
+}
+
+impl PlyType {
+    fn from(name: &str) -> Option<PlyType> {
+        match name {
+            "char" | "int8" => Some(PlyType::Int8),
+            "uchar" | "uint8" => Some(PlyType::UInt8),
+            "short" | "int16" => Some(PlyType::Int16),
+            "ushort" | "uint16" => Some(PlyType::UInt16),
+            "int" | "int32" => Some(PlyType::Int32),
+            "uint" | "uint32" => Some(PlyType::UInt32),
+            "float" | "float32" => Some(PlyType::Float32),
+            "double" | "float64" => Some(PlyType::Float64),
+            _ => None,
+        }
+    }
+
+    // the value of a full color channel
+    fn color_scale(&self) -> Float {
+        match self {
+            PlyType::UInt8 => 255.0,
+            PlyType::UInt16 => 65535.0,
+            _ => 1.0,
+        }
+    }
+}
+
+// the list items reserved up front, more are added as they are read
+const MAX_PREALLOCATED_ITEMS: usize = 16;
+
+fn is_whole(value: Float) -> bool {
+    value.is_finite() && value.fract() == 0.0
+}
+
+impl Values<'_, '_> {
+    fn read(&mut self, ply_type: PlyType) -> Result<Float, MeshErrorReason> {
+        match self {
+            Values::Ascii(items) => {
+                let item = items.next().ok_or(MeshErrorReason::MissingValue)?;
+                item.parse::<Float>().map_err(|_| MeshErrorReason::InvalidNumber(String::from(item)))
+            }
+            Values::Binary(bytes) => Ok(match ply_type {
+                PlyType::Int8 => i8::from_le_bytes(bytes.take()?) as Float,
+                PlyType::UInt8 => u8::from_le_bytes(bytes.take()?) as Float,
+                PlyType::Int16 => i16::from_le_bytes(bytes.take()?) as Float,
+                PlyType::UInt16 => u16::from_le_bytes(bytes.take()?) as Float,
+                PlyType::Int32 => i32::from_le_bytes(bytes.take()?) as Float,
+                PlyType::UInt32 => u32::from_le_bytes(bytes.take()?) as Float,
+                PlyType::Float32 => f32::from_le_bytes(bytes.take()?) as Float,
+                PlyType::Float64 => f64::from_le_bytes(bytes.take()?) as Float,
+            }),
+        }
+    }
+}
+
+// Reads ASCII and binary PLY files: the vertex positions, normals, colors and texture coordinates
+// and the faces. The mesh is a smooth model when every vertex has a normal.
+pub struct PlyReader<T> {
+    pub source : T,
+    pub vertices : Vec<Tuple>,
+    pub normals : Vec<Tuple>,
+    pub colors : Vec<Color>,
+    pub texture_coordinates : Vec<(Float, Float)>,
+    pub models: HashMap<String, TriangleModel>,
+    pub smooth_models: HashMap<String, SmoothTriangleModel>,
+}
+
+impl<T> PlyReader<T> where T: Read {
+    pub fn new(source : T) -> Self {
+        Self { source, vertices: vec![], normals: vec![], colors: vec![], texture_coordinates: vec![],
+            models: HashMap::new(), smooth_models: HashMap::new() }
+    }
+
+    pub fn read(&mut self) -> Result<(), MeshError> {
+        let mut data = vec![];
+        self.source.read_to_end(&mut data).map_err(|error| MeshError { line: 0, reason: MeshErrorReason::Io(error) })?;
+
+        let (PlyHeader { format, elements, lines: header_lines }, body) = Self::read_header(&data)?;
+        let mut faces: Vec<Vec<usize>> = vec![];
+        let mut line = header_lines;
+        let text = match format {
+            PlyFormat::Ascii => String::from_utf8_lossy(body),
+            _ => Cow::Borrowed(""),
+        };
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        let mut bytes = Bytes::new(body, format == PlyFormat::BinaryBigEndian);
+
+        for element in elements.iter() {
+            for _ in 0..element.count {
+                let mut values = match format {
+                    PlyFormat::Ascii => {
+                        line += 1;
+                        Values::Ascii(lines.next().ok_or(MeshError { line, reason: MeshErrorReason::UnexpectedEnd })?.split_whitespace())
+                    }
+                    _ => Values::Binary(&mut bytes),
+                };
+                let error_line = if format == PlyFormat::Ascii { line } else { 0 };
+                self.read_element(element, &mut values, &mut faces).map_err(|reason| MeshError { line: error_line, reason })?;
+            }
+        }
+
+        self.add_model(&faces).map_err(|reason| MeshError { line: 0, reason })
+    }
+
+    // returns the header and the body
+    fn read_header(data: &[u8]) -> Result<(PlyHeader, &[u8]), MeshError> {
+        let header_error = |line, message: &str| MeshError { line, reason: MeshErrorReason::InvalidHeader(String::from(message)) };
+        let end = data.windows(10).position(|window| window == b"end_header")
+            .ok_or(header_error(0, "no end_header"))?;
+        let body_start = data[end..].iter().position(|byte| *byte == b'\n').map_or(data.len(), |i| end + i + 1);
+        let header = String::from_utf8_lossy(&data[..end]);
+
+        let mut format = None;
+        let mut elements: Vec<PlyElement> = vec![];
+        let mut header_lines = 1;
+        for (i, line) in header.lines().enumerate() {
+            header_lines = i + 2;
+            let items: Vec<&str> = line.split_whitespace().collect();
+            match items.first() {
+                Some(&"ply") if i == 0 => {}
+                _ if i == 0 => return Err(header_error(1, "not a PLY file")),
+                Some(&"format") => format = Some(match items.get(1) {
+                    Some(&"ascii") => PlyFormat::Ascii,
+                    Some(&"binary_little_endian") => PlyFormat::BinaryLittleEndian,
+                    Some(&"binary_big_endian") => PlyFormat::BinaryBigEndian,
+                    _ => return Err(header_error(i + 1, "unknown format")),
+                }),
+                Some(&"element") => {
+                    let (name, count) = match (items.get(1), items.get(2).and_then(|count| count.parse::<usize>().ok())) {
+                        (Some(name), Some(count)) => (String::from(*name), count),
+                        _ => return Err(header_error(i + 1, "invalid element")),
+                    };
+                    elements.push(PlyElement { name, count, properties: vec![] });
+                }
+                Some(&"property") => {
+                    let element = elements.last_mut().ok_or(header_error(i + 1, "property without element"))?;
+                    let property = match items.as_slice() {
+                        [_, "list", count_type, item_type, name] => match (PlyType::from(count_type), PlyType::from(item_type)) {
+                            (Some(count_type), Some(item_type)) => PlyProperty::List(String::from(*name), count_type, item_type),
+                            _ => return Err(header_error(i + 1, "unknown property type")),
+                        },
+                        [_, property_type, name] => match PlyType::from(property_type) {
+                            Some(property_type) => PlyProperty::Scalar(String::from(*name), property_type),
+                            None => return Err(header_error(i + 1, "unknown property type")),
+                        },
+                        _ => return Err(header_error(i + 1, "invalid property")),
+                    };
+                    element.properties.push(property);
+                }
+                _ => {}
+            }
+        }
+        let format = format.ok_or(header_error(header_lines, "no format"))?;
+        Ok((PlyHeader { format, elements, lines: header_lines }, &data[body_start..]))
+    }
+
+    fn read_element(&mut self, element: &PlyElement, values: &mut Values, faces: &mut Vec<Vec<usize>>) -> Result<(), MeshErrorReason> {
+        let mut scalars: HashMap<&str, (Float, PlyType)> = HashMap::new();
+        let mut indices = None;
+        for property in element.properties.iter() {
+            match property {
+                PlyProperty::Scalar(name, property_type) => {
+                    scalars.insert(name, (values.read(*property_type)?, *property_type));
+                }
+                PlyProperty::List(name, count_type, item_type) => {
+                    let count = values.read(*count_type)?;
+                    if !is_whole(count) || count < 0.0 {
+                        return Err(MeshErrorReason::InvalidNumber(count.to_string()));
+                    }
+                    // the count is not trusted before its items are read
+                    let count = count as usize;
+                    let mut items = Vec::with_capacity(count.min(MAX_PREALLOCATED_ITEMS));
+                    for _ in 0..count {
+                        items.push(values.read(*item_type)?);
+                    }
+                    if name == "vertex_indices" || name == "vertex_index" {
+                        indices = Some(items);
+                    }
+                }
+            }
+        }
+
+        let value = |name: &str| scalars.get(name).map(|(value, _)| *value);
+        match element.name.as_str() {
+            "vertex" => {
+                let (x, y, z) = match (value("x"), value("y"), value("z")) {
+                    (Some(x), Some(y), Some(z)) => (x, y, z),
+                    _ => return Err(MeshErrorReason::MissingValue),
+                };
+                self.vertices.push(point(x, y, z));
+                if let (Some(nx), Some(ny), Some(nz)) = (value("nx"), value("ny"), value("nz")) {
+                    self.normals.push(vector(nx, ny, nz));
+                }
+                if let (Some((r, r_type)), Some(g), Some(b)) = (scalars.get("red"), value("green"), value("blue")) {
+                    let scale = r_type.color_scale();
+                    self.colors.push(Color::new(r / scale, g / scale, b / scale));
+                }
+                let uv = ["u", "s", "texture_u"].iter().zip(["v", "t", "texture_v"].iter())
+                    .find_map(|(u, v)| value(u).zip(value(v)));
+                if let Some(uv) = uv {
+                    self.texture_coordinates.push(uv);
+                }
+            }
+            "face" => {
+                let indices = indices.ok_or(MeshErrorReason::MissingValue)?;
+                if indices.len() < 3 {
+                    return Err(MeshErrorReason::NotEnoughVertices);
+                }
+                let face = indices.iter().map(|index| match *index {
+                    index if !is_whole(index) => Err(MeshErrorReason::InvalidNumber(index.to_string())),
+                    index if index < 0.0 => Err(MeshErrorReason::VertexOutOfRange(index as isize)),
+                    index => Ok(index as usize),
+                }).collect::<Result<Vec<usize>, MeshErrorReason>>()?;
+                faces.push(face);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn add_model(&mut self, faces: &[Vec<usize>]) -> Result<(), MeshErrorReason> {
+        let count = self.vertices.len();
+        let smooth = !self.normals.is_empty() && self.normals.len() == count;
+        let with_colors = self.colors.len() == count;
+        let with_uvs = self.texture_coordinates.len() == count;
+        let mut triangles = vec![];
+        let mut smooth_triangles = vec![];
+        for face in faces.iter() {
+            if let Some(index) = face.iter().find(|index| **index >= count) {
+                return Err(MeshErrorReason::VertexOutOfRange(*index as isize));
+            }
+            // fan triangulation
+            for i in 1..face.len() - 1 {
+                let corners = [face[0], face[i], face[i + 1]];
+                let mut triangle = Triangle::new(self.vertices[corners[0]], self.vertices[corners[1]], self.vertices[corners[2]]);
+                if with_colors {
+                    triangle.colors = Some(corners.map(|corner| self.colors[corner]));
+                }
+                if with_uvs {
+                    triangle.uvs = Some(corners.map(|corner| self.texture_coordinates[corner]));
+                }
+                if smooth {
+                    let [n1, n2, n3] = corners.map(|corner| self.normals[corner]);
+                    smooth_triangles.push(SmoothTriangle { triangle, n1, n2, n3 });
+                } else {
+                    triangles.push(triangle);
+                }
+            }
+        }
+        if smooth {
+            self.smooth_models.insert(String::from("Default"), SmoothTriangleModel::new(smooth_triangles));
+        } else {
+            self.models.insert(String::from("Default"), TriangleModel::new(triangles));
+        }
+        Ok(())
+    }
+}
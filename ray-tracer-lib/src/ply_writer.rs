@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use crate::colors::Color;
+use crate::core::math::Float;
+use crate::core::tuple::{Tuple, vector};
+use crate::mesh::faces::{MeshFace, model_faces, smooth_model_faces, world_faces};
+use crate::mesh::normals::vertex_key;
+use crate::object::Object;
+use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
+use crate::shapes::triangle_model::TriangleModel;
+
+// Writes triangles to an ASCII or binary (little endian) PLY file.
+// The vertices have normals when a face is smooth (the flat faces give their face normal),
+// texture coordinates and colors when every face has them.
+pub struct PlyWriter<W> {
+    pub target : W,
+    pub binary : bool,
+}
+
+impl PlyWriter<BufWriter<File>> {
+    pub fn create<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        Ok(PlyWriter::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+// a vertex with all its properties, the unused ones are left at their default
+#[derive(Debug, Copy, Clone)]
+struct PlyVertex {
+    position: Tuple,
+    normal: Tuple,
+    uv: (Float, Float),
+    color: Color,
+}
+
+impl PlyVertex {
+    fn key(&self) -> [u64; 11] {
+        let bits = |value: Float| (value + 0.0).to_bits();
+        let (p, n) = (vertex_key(&self.position), vertex_key(&self.normal));
+        [p.0, p.1, p.2, n.0, n.1, n.2, bits(self.uv.0), bits(self.uv.1),
+            bits(self.color.r), bits(self.color.g), bits(self.color.b)]
+    }
+}
+
+impl<W> PlyWriter<W> where W: Write {
+    pub fn new(target : W) -> Self {
+        Self { target, binary: false }
+    }
+
+    pub fn write_model(&mut self, model: &TriangleModel) -> std::io::Result<()> {
+        self.write_faces(&model_faces(model))
+    }
+
+    pub fn write_smooth_model(&mut self, model: &SmoothTriangleModel) -> std::io::Result<()> {
+        self.write_faces(&smooth_model_faces(model))
+    }
+
+    // the triangles of the object and its descendants, with their world transformations
+    pub fn write_object(&mut self, object: &Object) -> std::io::Result<()> {
+        self.write_faces(&world_faces(object))
+    }
+
+    pub fn write_faces(&mut self, faces: &[MeshFace]) -> std::io::Result<()> {
+        let with_normals = faces.iter().any(|face| face.normals.is_some());
+        let with_uvs = !faces.is_empty() && faces.iter().all(|face| face.triangle.uvs.is_some());
+        let with_colors = !faces.is_empty() && faces.iter().all(|face| face.triangle.colors.is_some());
+
+        let mut vertices: Vec<PlyVertex> = vec![];
+        let mut indices: HashMap<[u64; 11], usize> = HashMap::new();
+        let mut triangles = vec![];
+        for face in faces.iter() {
+            let triangle = &face.triangle;
+            let mut corners = [0; 3];
+            for (i, position) in [triangle.p1, triangle.p2, triangle.p3].into_iter().enumerate() {
+                let vertex = PlyVertex {
+                    position,
+                    normal: match (with_normals, face.normals) {
+                        (false, _) => vector(0.0, 0.0, 0.0),
+                        (true, Some(normals)) => normals[i],
+                        (true, None) => triangle.normal,
+                    },
+                    uv: triangle.uvs.filter(|_| with_uvs).map_or((0.0, 0.0), |uvs| uvs[i]),
+                    color: triangle.colors.filter(|_| with_colors).map_or(Color::new(0.0, 0.0, 0.0), |colors| colors[i]),
+                };
+                corners[i] = *indices.entry(vertex.key()).or_insert_with(|| {
+                    vertices.push(vertex);
+                    vertices.len() - 1
+                });
+            }
+            triangles.push(corners);
+        }
+
+        writeln!(self.target, "ply")?;
+        writeln!(self.target, "format {} 1.0", if self.binary { "binary_little_endian" } else { "ascii" })?;
+        writeln!(self.target, "element vertex {}", vertices.len())?;
+        for name in ["x", "y", "z"] {
+            writeln!(self.target, "property float {}", name)?;
+        }
+        if with_normals {
+            for name in ["nx", "ny", "nz"] {
+                writeln!(self.target, "property float {}", name)?;
+            }
+        }
+        if with_uvs {
+            for name in ["s", "t"] {
+                writeln!(self.target, "property float {}", name)?;
+            }
+        }
+        if with_colors {
+            for name in ["red", "green", "blue"] {
+                writeln!(self.target, "property uchar {}", name)?;
+            }
+        }
+        writeln!(self.target, "element face {}", triangles.len())?;
+        writeln!(self.target, "property list uchar int vertex_indices")?;
+        writeln!(self.target, "end_header")?;
+
+        for vertex in vertices.iter() {
+            let mut values = vec![vertex.position.x, vertex.position.y, vertex.position.z];
+            if with_normals {
+                values.extend([vertex.normal.x, vertex.normal.y, vertex.normal.z]);
+            }
+            if with_uvs {
+                values.extend([vertex.uv.0, vertex.uv.1]);
+            }
+            let channel = |value: Float| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+            let color = [channel(vertex.color.r), channel(vertex.color.g), channel(vertex.color.b)];
+            if self.binary {
+                for value in values.iter() {
+                    self.target.write_all(&(*value as f32).to_le_bytes())?;
+                }
+                if with_colors {
+                    self.target.write_all(&color)?;
+                }
+            } else {
+                let mut items: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+                if with_colors {
+                    items.extend(color.iter().map(|channel| channel.to_string()));
+                }
+                writeln!(self.target, "{}", items.join(" "))?;
+            }
+        }
+        for corners in triangles.iter() {
+            if self.binary {
+                self.target.write_all(&[3])?;
+                for corner in corners.iter() {
+                    self.target.write_all(&(*corner as i32).to_le_bytes())?;
+                }
+            } else {
+                writeln!(self.target, "3 {} {} {}", corners[0], corners[1], corners[2])?;
+            }
+        }
+        self.target.flush()
+    }
+}
@@ -1,10 +1,10 @@
+use std::sync::Arc;
 use crate::core::bounds::Bounds;
-use crate::core::intersection::Intersection;
 use crate::core::intersections::{Intersections, intersections};
 use crate::core::matrix::Matrix;
 use crate::core::ray::Ray;
 use crate::object::Object;
-use crate::shapes::group::Group;
+use crate::shapes::group::{Group, GroupNode};
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum CsgOperation { Union, Intersection, Difference }
@@ -17,21 +17,13 @@ pub struct Csg {
 
 impl Csg {
     pub(crate) fn intersect(&self, ray: &Ray) -> Intersections {
-        let intersections_with_bounds = self.group.bounds().intersect(&ray);
-        if intersections_with_bounds.is_empty() {
-            return intersections(vec![]);
-        }
-        
-        let left = self.left();
-        let mut left_xs = left.intersect(ray);
-        let right = self.right();
-        let right_xs = right.intersect(ray);
-
-        for intersection in right_xs.intersections.iter() {
-            left_xs.push(intersection.clone());
+        // the group moves the ray into the csg space and returns the hits of both children sorted
+        let xs = self.group.intersect(ray);
+        if xs.intersections.is_empty() {
+            return xs;
         }
 
-        self.filter_intersections(left_xs)
+        self.filter_intersections(xs)
     }
 
     pub(crate) fn includes(&self, object: &Object) -> bool {
@@ -46,6 +38,10 @@ impl Csg {
         self.group.set_transformation(transformation);
     }
 
+    pub(crate) fn set_parent(&self, parent: Option<Arc<GroupNode>>) {
+        self.group.set_parent(parent);
+    }
+
     pub fn new(csg_operation: CsgOperation, left: Object, right: Object) -> Self {
         let mut group = Group::new();
         group.add(left);
@@ -81,8 +77,7 @@ impl Csg {
             let left = self.left();
             let lhit = left.includes(obj);
             if Csg::intersection_allowed(&self.csg_operation, lhit, inl, inr) {
-                let intersection = Intersection::new(i.t, i.object.clone());
-                result.intersections.push(intersection);
+                result.intersections.push(i.clone());
             }
             // depending on which object was hit, toggle either inl or inr
             if lhit {
@@ -1,27 +1,140 @@
 use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
 use crate::core::bounds::Bounds;
 use crate::core::intersections::{Intersections, intersections};
 use crate::core::matrix::Matrix;
 use crate::object::{Object, ObjectType};
 use crate::core::ray::Ray;
+use crate::core::tuple::Tuple;
+use crate::material::Material;
+
+// The transformation of a group, shared with its children: a child reaches the world
+// by walking up its parents, so moving a group never touches its descendants.
+#[derive(Debug)]
+pub struct GroupNode {
+    transformation: RwLock<NodeTransformation>,
+    parent: RwLock<Option<Arc<GroupNode>>>,
+}
 
 #[derive(Debug, Clone)]
+struct NodeTransformation {
+    transformation: Matrix<4>,
+    transformation_inverse: Matrix<4>,
+    transformation_inverse_transpose: Matrix<4>,
+}
+
+impl NodeTransformation {
+    fn new(transformation: Matrix<4>) -> Self {
+        let transformation_inverse = transformation.inverse();
+        let transformation_inverse_transpose = transformation_inverse.transpose();
+        NodeTransformation { transformation, transformation_inverse, transformation_inverse_transpose }
+    }
+}
+
+impl GroupNode {
+    fn new(transformation: NodeTransformation, parent: Option<Arc<GroupNode>>) -> Self {
+        GroupNode { transformation: RwLock::new(transformation), parent: RwLock::new(parent) }
+    }
+
+    fn set_transformation(&self, transformation: NodeTransformation) {
+        *self.transformation.write().unwrap() = transformation;
+    }
+
+    fn set_parent(&self, parent: Option<Arc<GroupNode>>) {
+        *self.parent.write().unwrap() = parent;
+    }
+
+    pub fn world_to_object(&self, point: &Tuple) -> Tuple {
+        let p = match self.parent.read().unwrap().as_ref() {
+            Some(parent) => parent.world_to_object(point),
+            None => *point,
+        };
+        &self.transformation.read().unwrap().transformation_inverse * &p
+    }
+
+    pub fn normal_to_world(&self, normal: &Tuple) -> Tuple {
+        let mut n = &self.transformation.read().unwrap().transformation_inverse_transpose * normal;
+        n.w = 0.0;
+        let n = n.normalize();
+        match self.parent.read().unwrap().as_ref() {
+            Some(parent) => parent.normal_to_world(&n),
+            None => n,
+        }
+    }
+
+    pub fn vector_to_world(&self, v: &Tuple) -> Tuple {
+        let mut v = &self.transformation.read().unwrap().transformation * v;
+        v.w = 0.0;
+        match self.parent.read().unwrap().as_ref() {
+            Some(parent) => parent.vector_to_world(&v),
+            None => v,
+        }
+    }
+
+    // the transformation from the group space to the world space, and its inverse
+    pub(crate) fn transformation_to_world(&self) -> (Matrix<4>, Matrix<4>) {
+        let node = self.transformation.read().unwrap().clone();
+        match self.parent.read().unwrap().as_ref() {
+            Some(parent) => {
+                let (transformation, transformation_inverse) = parent.transformation_to_world();
+                (&transformation * &node.transformation, &node.transformation_inverse * &transformation_inverse)
+            },
+            None => (node.transformation, node.transformation_inverse),
+        }
+    }
+}
+
+#[derive(Debug)]
 pub struct Group {
-    pub children : Vec<Object>,
-    bounds : Bounds,
+    children : Vec<Object>,
+    bounds : Bounds,    // in the group space
     transformation : Matrix<4>,
+    transformation_inverse : Matrix<4>,
+    node : Arc<GroupNode>,
+    material : Option<Material>,   // given to the children without their own material
     children_ids : HashSet<usize>
 }
 
+impl Clone for Group {
+    // the clone gets its own node, its children must follow it
+    fn clone(&self) -> Self {
+        let node = self.node.transformation.read().unwrap().clone();
+        let parent = self.node.parent.read().unwrap().clone();
+        let node = Arc::new(GroupNode::new(node, parent));
+        let children = self.children.iter().map(|child| {
+            let mut child = child.clone();
+            child.set_parent(Some(node.clone()));
+            child
+        }).collect();
+        Group {
+            children,
+            bounds: self.bounds.clone(),
+            transformation: self.transformation,
+            transformation_inverse: self.transformation_inverse,
+            node,
+            material: self.material.clone(),
+            children_ids: self.children_ids.clone(),
+        }
+    }
+}
+
 impl Group {
+    // the bounds in the parent space
     pub fn bounds(&self) -> Bounds {
-        self.bounds.clone()
+        if self.children.is_empty() {
+            return self.bounds.clone();
+        }
+        self.bounds.transform(&self.transformation)
     }
 
     pub fn new() -> Self {
+        let transformation = NodeTransformation::new(Matrix::<4>::identity());
         Self {  children: vec![],
                 bounds: Bounds::new(),
-                transformation: Matrix::<4>::identity(),
+                transformation: transformation.transformation,
+                transformation_inverse: transformation.transformation_inverse,
+                node: Arc::new(GroupNode::new(transformation, None)),
+                material: None,
                 children_ids: HashSet::new()}
     }
 
@@ -33,42 +146,56 @@ impl Group {
         &self.children[i]
     }
 
+    // the children are added with add, which updates the bounds and the parents
+    pub fn children(&self) -> &[Object] {
+        &self.children
+    }
+
     pub fn from(objects: Vec<Object>, transformation: Matrix<4>) -> Object {
         let mut group = Group::new();
         group.set_transformation(transformation);
-        objects.iter().for_each( |a|  { group.add(a.clone())});
+        objects.into_iter().for_each( |a|  { group.add(a)});
         Object::new_group(group)
     }
 
+    pub fn transformation(&self) -> &Matrix<4> {
+        &self.transformation
+    }
+
+    // the children keep their own transformation, only the group node is updated
     pub fn set_transformation(&mut self, transformation: Matrix<4>) {
-        let mut transformed_objects: Vec<Object> = vec![];
-        let mut bounds = Bounds::new();
-        for object in self.children.iter_mut() {
-            object.set_transformation(&transformation * object.transformation());
-            let transformed_object = object.clone();
-            let transformed_bounds = transformed_object.bounds();
-            bounds.extend(&transformed_bounds);
-            transformed_objects.push(transformed_object);
-        }
+        let transformation = NodeTransformation::new(transformation);
+        self.transformation = transformation.transformation;
+        self.transformation_inverse = transformation.transformation_inverse;
+        self.node.set_transformation(transformation);
+    }
 
-        self.transformation = transformation;
-        self.children = transformed_objects;
-        self.bounds = bounds;
+    pub(crate) fn set_parent(&self, parent: Option<Arc<GroupNode>>) {
+        self.node.set_parent(parent);
     }
 
-    pub fn add(&mut self, child: Object) {
-        let mut transformed_children = child.clone();
-        transformed_children.set_transformation(&self.transformation * child.transformation());
+    pub(crate) fn inherit_material(&mut self, material: Material) {
+        for child in self.children.iter_mut() {
+            child.inherit_material(material.clone());
+        }
+        self.material = Some(material);
+    }
+
+    pub fn add(&mut self, mut child: Object) {
+        child.set_parent(Some(self.node.clone()));
+        if let Some(material) = &self.material {
+            child.inherit_material(material.clone());
+        }
         if self.children.is_empty() {
-            self.bounds = transformed_children.bounds();
+            self.bounds = child.bounds();
         } else {
-            self.bounds.extend(&transformed_children.bounds());
+            self.bounds.extend(&child.bounds());
         }
-        self.children.push(transformed_children);
         self.children_ids.insert(child.object_id);
         for id in child.get_child_ids() {
             self.children_ids.insert(id);
         }
+        self.children.push(child);
     }
     pub fn get_child_ids(&self) -> Vec<usize> {
         let mut ids = vec![];
@@ -82,12 +209,13 @@ impl Group {
 
     pub fn intersect(&self, ray: &Ray) -> Intersections {
         let mut xs = intersections(vec![]);
-        if self.bounds.intersect(&ray).is_empty() {
+        let local_ray = ray.transform(&self.transformation_inverse);
+        if self.bounds.intersect(&local_ray).is_empty() {
             return xs;
         }
 
         for child in self.children.iter() {
-            let child_xs = child.intersect(ray);
+            let child_xs = child.intersect(&local_ray);
             for x in child_xs.intersections.iter() {
                 xs.intersections.push(x.clone());
             }
@@ -110,4 +238,4 @@ impl Group {
         }
         return false;
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+use crate::core::bounds::Bounds;
+use crate::core::intersections::{Intersections, intersections};
+use crate::core::matrix::Matrix;
+use crate::core::ray::Ray;
+use crate::material::Material;
+use crate::object::Object;
+use crate::shapes::group::GroupNode;
+
+// A placement of a shared object: the geometry is never copied nor transformed,
+// the instance only keeps its own transformation (in the owning Object) and an optional material.
+// Rays are transformed into the shared object space at the instance boundary.
+#[derive(Debug, Clone)]
+pub struct Instance {
+    pub object: Arc<Object>,
+    pub material: Option<Material>,
+}
+
+impl Instance {
+    pub fn new(object: Arc<Object>) -> Self {
+        Instance { object, material: None }
+    }
+
+    pub(crate) fn intersect(&self, ray: &Ray, transformation: &Matrix<4>, transformation_inverse: &Matrix<4>, parent: &Option<Arc<GroupNode>>) -> Intersections {
+        let transformed_ray = ray.transform(transformation_inverse);
+        let xs = self.object.intersect(&transformed_ray);
+        // the hit objects are placed under the instance parent so normals and patterns are computed as usual
+        intersections(xs.intersections.into_vec().into_iter().map(|mut x| {
+            x.object = x.object.placed_in(transformation, transformation_inverse, parent);
+            if let Some(material) = &self.material {
+                x.object.set_material(material.clone());
+            }
+            x
+        }).collect())
+    }
+
+    pub(crate) fn bounds(&self, transformation: &Matrix<4>) -> Bounds {
+        self.object.bounds().transform(transformation)
+    }
+
+    pub(crate) fn includes(&self, object: &Object) -> bool {
+        self.object.includes(object)
+    }
+
+    pub(crate) fn get_child_ids(&self) -> Vec<usize> {
+        self.object.get_child_ids()
+    }
+}
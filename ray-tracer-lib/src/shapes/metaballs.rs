@@ -0,0 +1,156 @@
+use crate::core::bounds::Bounds;
+use crate::core::math::{EPSILON, Float};
+use crate::core::ray::Ray;
+use crate::core::tuple::{point, Tuple, vector};
+
+// number of samples per ball radius when marching along the ray to find a sign change
+const STEPS_PER_RADIUS: Float = 16.0;
+// number of bisections to refine a root once it is bracketed
+const BISECTIONS: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metaball {
+    pub center: Tuple,
+    pub radius: Float,
+    pub weight: Float,
+}
+
+impl Metaball {
+    pub fn new(center: Tuple, radius: Float, weight: Float) -> Self {
+        Metaball { center, radius, weight }
+    }
+
+    // "soft object" falloff: weight * (1 - r²/R²)³ inside the radius of influence, 0 outside
+    pub fn field_at(&self, p: &Tuple) -> Float {
+        let d = *p - self.center;
+        let r2 = d.dot(&d) / (self.radius * self.radius);
+        if r2 >= 1.0 {
+            return 0.0;
+        }
+        let k = 1.0 - r2;
+        self.weight * k * k * k
+    }
+
+    pub fn gradient_at(&self, p: &Tuple) -> Tuple {
+        let d = *p - self.center;
+        let inv_r2 = 1.0 / (self.radius * self.radius);
+        let r2 = d.dot(&d) * inv_r2;
+        if r2 >= 1.0 {
+            return vector(0.0, 0.0, 0.0);
+        }
+        let k = 1.0 - r2;
+        d * (-6.0 * self.weight * k * k * inv_r2)
+    }
+
+    // the t values where the ray enters and leaves the sphere of influence
+    fn influence(&self, ray: &Ray) -> Option<(Float, Float)> {
+        let ball_to_ray = ray.origin - self.center;
+        let a = ray.direction.dot(&ray.direction);
+        let b = 2.0 * ray.direction.dot(&ball_to_ray);
+        let c = ball_to_ray.dot(&ball_to_ray) - self.radius * self.radius;
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant <= 0.0 {
+            return None;
+        }
+        let sqrt_disc = discriminant.sqrt();
+        Some(((-b - sqrt_disc) / (2.0 * a), (-b + sqrt_disc) / (2.0 * a)))
+    }
+}
+
+// Implicit surface where the sum of the balls' fields equals the threshold.
+// Balls with a negative weight carve the surface of the others.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metaballs {
+    pub balls: Vec<Metaball>,
+    pub threshold: Float,
+}
+
+impl Metaballs {
+    pub fn new(balls: Vec<Metaball>, threshold: Float) -> Self {
+        Metaballs { balls, threshold }
+    }
+
+    pub fn field_at(&self, p: &Tuple) -> Float {
+        self.balls.iter().map(|ball| ball.field_at(p)).sum()
+    }
+
+    pub fn intersect(&self, ray: &Ray) -> Vec<Float> {
+        let mut xs = vec![];
+        let speed = ray.direction.magnitude();
+        let f = |t: Float| self.field_at(&ray.position(t)) - self.threshold;
+
+        for (t_min, t_max, min_radius) in self.intervals(ray) {
+            let step = min_radius / (STEPS_PER_RADIUS * speed);
+            let n = ((t_max - t_min) / step).ceil().max(1.0) as usize;
+            let mut t0 = t_min;
+            let mut f0 = f(t0);
+            for i in 1..=n {
+                let t1 = t_min + (t_max - t_min) * i as Float / n as Float;
+                let f1 = f(t1);
+                if (f0 < 0.0) != (f1 < 0.0) {
+                    xs.push(Self::bisect(&f, t0, t1, f0));
+                }
+                t0 = t1;
+                f0 = f1;
+            }
+        }
+
+        xs
+    }
+
+    fn bisect(f: &dyn Fn(Float) -> Float, mut t0: Float, mut t1: Float, f0: Float) -> Float {
+        let below = f0 < 0.0;
+        for _ in 0..BISECTIONS {
+            let t = (t0 + t1) / 2.0;
+            if (f(t) < 0.0) == below {
+                t0 = t;
+            } else {
+                t1 = t;
+            }
+        }
+        (t0 + t1) / 2.0
+    }
+
+    // merged spans of the ray inside the spheres of influence of the positive balls,
+    // with the smallest radius met in each span to size the marching step.
+    // Outside these spans the field is <= 0 so it can't reach a positive threshold.
+    fn intervals(&self, ray: &Ray) -> Vec<(Float, Float, Float)> {
+        let mut spans: Vec<(Float, Float, Float)> = self.balls.iter()
+            .filter(|ball| ball.weight > 0.0)
+            .filter_map(|ball| ball.influence(ray).map(|(t0, t1)| (t0, t1, ball.radius)))
+            .collect();
+        spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut merged: Vec<(Float, Float, Float)> = vec![];
+        for span in spans {
+            match merged.last_mut() {
+                Some(last) if span.0 <= last.1 => {
+                    last.1 = Float::max(last.1, span.1);
+                    last.2 = Float::min(last.2, span.2);
+                }
+                _ => merged.push(span),
+            }
+        }
+        merged
+    }
+
+    pub fn normal_at(&self, p: Tuple) -> Tuple {
+        let gradient = self.balls.iter().fold(vector(0.0, 0.0, 0.0), |acc, ball| acc + ball.gradient_at(&p));
+        if gradient.magnitude() < EPSILON * EPSILON {
+            return vector(0.0, 1.0, 0.0);
+        }
+        // the field decreases outward so the normal is opposite to the gradient
+        (-gradient).normalize()
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        let mut bounds = Bounds::new();
+        for ball in self.balls.iter().filter(|ball| ball.weight > 0.0) {
+            let c = ball.center;
+            let r = ball.radius;
+            bounds.add(&point(c.x - r, c.y - r, c.z - r));
+            bounds.add(&point(c.x + r, c.y + r, c.z + r));
+        }
+        bounds
+    }
+}
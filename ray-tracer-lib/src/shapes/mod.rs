@@ -10,3 +10,9 @@ pub mod triangle_model;
 pub mod smooth_triangle;
 pub mod smooth_triangle_model;
 pub mod csg;
+pub mod voxels;
+pub mod metaballs;
+pub mod superellipsoid;
+pub mod quadric;
+pub mod instance;
+pub mod uv_mapping;
@@ -0,0 +1,168 @@
+use crate::core::bounds::Bounds;
+use crate::core::math::{EPSILON, equals, Float, INFINITY};
+use crate::core::matrix::{Determinant, Matrix};
+use crate::core::ray::Ray;
+use crate::core::tuple::{point, Tuple, vector};
+
+// General quadric surface:
+//   a x² + b y² + c z² + d xy + e xz + f yz + g x + h y + i z + j = 0
+// the coefficients are stored in this order. Points where the left side is negative are inside.
+// Like cylinders, the surface can be clipped between min and max along the y axis and closed with caps.
+#[derive(Debug, Copy, Clone)]
+pub struct Quadric {
+    pub coefficients: [Float; 10],
+    pub min: Float,
+    pub max: Float,
+    pub closed: bool,
+}
+
+impl PartialEq for Quadric {
+    fn eq(&self, other: &Self) -> bool {
+        self.coefficients == other.coefficients && self.min == other.min && self.max == other.max && self.closed == other.closed
+    }
+}
+
+impl Quadric {
+    pub fn new(coefficients: [Float; 10]) -> Self {
+        Quadric { coefficients, min: -INFINITY, max: INFINITY, closed: false }
+    }
+
+    pub fn from(coefficients: [Float; 10], min: Float, max: Float, closed: bool) -> Self {
+        Quadric { coefficients, min, max, closed }
+    }
+
+    // x² + z² - y = 0
+    pub fn paraboloid() -> [Float; 10] {
+        [1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, -1.0, 0.0, 0.0]
+    }
+
+    // x² - y² + z² - 1 = 0
+    pub fn hyperboloid_one_sheet() -> [Float; 10] {
+        [1.0, -1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0]
+    }
+
+    // x² - y² + z² + 1 = 0, negative inside the sheets
+    pub fn hyperboloid_two_sheets() -> [Float; 10] {
+        [1.0, -1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0]
+    }
+
+    // x²/rx² + z²/rz² - 1 = 0
+    pub fn elliptic_cylinder(rx: Float, rz: Float) -> [Float; 10] {
+        [1.0 / (rx * rx), 0.0, 1.0 / (rz * rz), 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0]
+    }
+
+    pub fn value_at(&self, p: &Tuple) -> Float {
+        let [a, b, c, d, e, f, g, h, i, j] = self.coefficients;
+        a * p.x * p.x + b * p.y * p.y + c * p.z * p.z
+            + d * p.x * p.y + e * p.x * p.z + f * p.y * p.z
+            + g * p.x + h * p.y + i * p.z + j
+    }
+
+    pub fn intersect(&self, ray: &Ray) -> Vec<Float> {
+        let [a, b, c, d, e, f, g, h, i, _] = self.coefficients;
+        let o = ray.origin;
+        let dir = ray.direction;
+        let mut xs = vec![];
+
+        // substitute the ray into the equation to get qa t² + qb t + qc = 0
+        let qa = a * dir.x * dir.x + b * dir.y * dir.y + c * dir.z * dir.z
+            + d * dir.x * dir.y + e * dir.x * dir.z + f * dir.y * dir.z;
+        let qb = 2.0 * (a * o.x * dir.x + b * o.y * dir.y + c * o.z * dir.z)
+            + d * (o.x * dir.y + o.y * dir.x) + e * (o.x * dir.z + o.z * dir.x) + f * (o.y * dir.z + o.z * dir.y)
+            + g * dir.x + h * dir.y + i * dir.z;
+        let qc = self.value_at(&o);
+
+        let mut candidates = vec![];
+        if equals(qa, 0.0) {
+            // the equation is linear along the ray
+            if !equals(qb, 0.0) {
+                candidates.push(-qc / qb);
+            }
+        } else {
+            let disc = qb * qb - 4.0 * qa * qc;
+            if disc >= 0.0 {
+                let inv_2a = 1.0 / (2.0 * qa);
+                let sqrt_disc = disc.sqrt();
+                let t0 = (-qb - sqrt_disc) * inv_2a;
+                let t1 = (-qb + sqrt_disc) * inv_2a;
+                candidates.push(Float::min(t0, t1));
+                candidates.push(Float::max(t0, t1));
+            }
+        }
+
+        for t in candidates {
+            let y = o.y + t * dir.y;
+            if self.min < y && y < self.max {
+                xs.push(t);
+            }
+        }
+
+        self.intersect_caps(ray, &mut xs);
+
+        xs
+    }
+
+    // a point of the cap plane is on the cap if it is inside the quadric
+    fn check_cap(&self, ray: &Ray, t: Float) -> bool {
+        self.value_at(&ray.position(t)) <= 0.0
+    }
+
+    fn intersect_caps(&self, ray: &Ray, xs: &mut Vec<Float>) {
+        if !self.closed || equals(ray.direction.y, 0.0) {
+            return;
+        }
+
+        if self.min.is_finite() {
+            let t = (self.min - ray.origin.y) / ray.direction.y;
+            if self.check_cap(ray, t) {
+                xs.push(t);
+            }
+        }
+
+        if self.max.is_finite() {
+            let t = (self.max - ray.origin.y) / ray.direction.y;
+            if self.check_cap(ray, t) {
+                xs.push(t);
+            }
+        }
+    }
+
+    pub fn normal_at(&self, p: &Tuple) -> Tuple {
+        if self.closed && self.value_at(p) < -EPSILON {
+            if p.y >= self.max - EPSILON {
+                return vector(0.0, 1.0, 0.0);
+            } else if p.y <= self.min + EPSILON {
+                return vector(0.0, -1.0, 0.0);
+            }
+        }
+
+        let [a, b, c, d, e, f, g, h, i, _] = self.coefficients;
+        vector(2.0 * a * p.x + d * p.y + e * p.z + g,
+               2.0 * b * p.y + d * p.x + f * p.z + h,
+               2.0 * c * p.z + e * p.x + f * p.y + i)
+    }
+
+    // The surface is bounded when the matrix of its quadratic terms is definite (an ellipsoid):
+    // centered on c, it is (p - c)ᵀ A (p - c) = -value_at(c) and reaches c ± sqrt(-value_at(c) (A⁻¹)ᵢᵢ) on each axis.
+    // Other quadrics are unbounded, only clipped along y.
+    pub fn bounds(&self) -> Bounds {
+        let [a, b, c, d, e, f, g, h, i, _] = self.coefficients;
+        let quadratic = Matrix::<3>::new([[a, d / 2.0, e / 2.0], [d / 2.0, b, f / 2.0], [e / 2.0, f / 2.0, c]]);
+        let (minor1, minor2, determinant) = (a, a * b - d * d / 4.0, quadratic.determinant());
+        let definite = minor2 > 0.0 && ((minor1 > 0.0 && determinant > 0.0) || (minor1 < 0.0 && determinant < 0.0));
+        if !definite {
+            return Bounds::from(point(-INFINITY, self.min, -INFINITY), point(INFINITY, self.max, INFINITY));
+        }
+
+        // the inverse matrix, A is symmetric
+        let inverse = |row: usize, col: usize| quadratic.cofactor(row, col) / determinant;
+        let linear = [g, h, i];
+        let center: Vec<Float> = (0..3).map(|row| -0.5 * (0..3).map(|col| inverse(row, col) * linear[col]).sum::<Float>()).collect();
+        let center = point(center[0], center[1], center[2]);
+        let level = -self.value_at(&center);
+        let radius = |axis: usize| (level * inverse(axis, axis)).max(0.0).sqrt();
+        let (rx, ry, rz) = (radius(0), radius(1), radius(2));
+        Bounds::from(point(center.x - rx, Float::max(center.y - ry, self.min), center.z - rz),
+                     point(center.x + rx, Float::min(center.y + ry, self.max), center.z + rz))
+    }
+}
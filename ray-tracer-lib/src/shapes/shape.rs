@@ -11,9 +11,14 @@ use crate::shapes::sphere::Sphere;
 use crate::core::tuple::{Tuple};
 use crate::shapes::smooth_triangle::SmoothTriangle;
 use crate::shapes::triangle::Triangle;
+use crate::shapes::voxels::Voxels;
+use crate::shapes::metaballs::Metaballs;
+use crate::shapes::superellipsoid::Superellipsoid;
+use crate::shapes::quadric::Quadric;
+use crate::shapes::uv_mapping::{cubic_frame, cylindrical_frame, planar_frame, spherical_frame, UvFrame};
 
 #[derive(Debug, Clone)]
-pub enum Shape {Sphere(Sphere), Plane(Plane), Cube(Cube), Cylinder(Cylinder), Cone(Cone), Triangle(Triangle), SmoothTriangle(SmoothTriangle)}
+pub enum Shape {Sphere(Sphere), Plane(Plane), Cube(Cube), Cylinder(Cylinder), Cone(Cone), Triangle(Triangle), SmoothTriangle(SmoothTriangle), Voxels(Voxels), Metaballs(Metaballs), Superellipsoid(Superellipsoid), Quadric(Quadric)}
 
 impl PartialEq for Shape {
     fn eq(&self, other: &Self) -> bool {
@@ -25,6 +30,10 @@ impl PartialEq for Shape {
             (Shape::Cone(cone1), Shape::Cone(cone2)) => cone1.eq(cone2),
             (Shape::Triangle(triangle1), Shape::Triangle(triangle2)) => triangle1.eq(triangle2),
             (Shape::SmoothTriangle(smooth_triangle1), Shape::SmoothTriangle(smooth_triangle2)) => smooth_triangle1.eq(smooth_triangle2),
+            (Shape::Voxels(voxels1), Shape::Voxels(voxels2)) => voxels1.eq(voxels2),
+            (Shape::Metaballs(metaballs1), Shape::Metaballs(metaballs2)) => metaballs1.eq(metaballs2),
+            (Shape::Superellipsoid(superellipsoid1), Shape::Superellipsoid(superellipsoid2)) => superellipsoid1.eq(superellipsoid2),
+            (Shape::Quadric(quadric1), Shape::Quadric(quadric2)) => quadric1.eq(quadric2),
             _ => false
         }
     }
@@ -39,7 +48,11 @@ impl Shape {
             Shape::Cylinder(cylinder) => cylinder.bounds(),
             Shape::Cone(cone) => cone.bounds(),
             Shape::Triangle(triangle) => triangle.bounds(),
-            Shape::SmoothTriangle(smooth_triangle) => smooth_triangle.bounds()
+            Shape::SmoothTriangle(smooth_triangle) => smooth_triangle.bounds(),
+            Shape::Voxels(voxels) => voxels.bounds(),
+            Shape::Metaballs(metaballs) => metaballs.bounds(),
+            Shape::Superellipsoid(superellipsoid) => superellipsoid.bounds(),
+            Shape::Quadric(quadric) => quadric.bounds(),
         }
     }
 
@@ -52,6 +65,23 @@ impl Shape {
             Shape::Cone(cone) => cone.normal_at(&p),
             Shape::Triangle(triangle) => triangle.normal_at(&p),
             Shape::SmoothTriangle(smooth_triangle) => smooth_triangle.normal_at(&p, hit),
+            Shape::Voxels(voxels) => voxels.normal_at(p),
+            Shape::Metaballs(metaballs) => metaballs.normal_at(p),
+            Shape::Superellipsoid(superellipsoid) => superellipsoid.normal_at(p),
+            Shape::Quadric(quadric) => quadric.normal_at(&p),
+        }
+    }
+
+    // the texture frame of a point in object space, None for the shapes without texture coordinates
+    pub fn uv_frame(&self, p: &Tuple, hit: &Intersection) -> Option<UvFrame> {
+        match self {
+            Shape::Sphere(_) => spherical_frame(p),
+            Shape::Plane(_) => planar_frame(p),
+            Shape::Cube(_) => cubic_frame(p),
+            Shape::Cylinder(_) | Shape::Cone(_) => cylindrical_frame(p),
+            Shape::Triangle(triangle) => triangle.uv_frame(hit.u, hit.v, &triangle.normal),
+            Shape::SmoothTriangle(smooth_triangle) => smooth_triangle.triangle.uv_frame(hit.u, hit.v, &smooth_triangle.normal_at(p, hit)),
+            _ => None,
         }
     }
 
@@ -64,6 +94,10 @@ impl Shape {
             Shape::Cone(cone) => cone.intersect(ray),
             Shape::Triangle(triangle) => triangle.intersect(ray),
             Shape::SmoothTriangle(smooth_triangle) => smooth_triangle.intersect(ray),
+            Shape::Voxels(voxels) => voxels.intersect(ray),
+            Shape::Metaballs(metaballs) => metaballs.intersect(ray),
+            Shape::Superellipsoid(superellipsoid) => superellipsoid.intersect(ray),
+            Shape::Quadric(quadric) => quadric.intersect(ray),
         }
     }
 }
@@ -1,38 +1,27 @@
 use std::collections::HashSet;
 use crate::core::bounds::Bounds;
 use crate::core::math::Float;
-use crate::core::matrix::Matrix;
 use crate::core::ray::Ray;
+use crate::material::Material;
 use crate::object::Object;
 use crate::shapes::smooth_triangle::SmoothTriangle;
+use crate::shapes::triangle::Triangle;
 
 #[derive(Debug, Clone)]
 pub struct SmoothTriangleModel {
     pub smooth_triangles : Vec<SmoothTriangle>,
     pub bounds : Bounds,
+    pub materials : Vec<Material>,  // the palette of the faces with a material index
     triangles_ids : HashSet<usize>
 }
 
 impl SmoothTriangleModel {
     pub fn new(smooth_triangles : Vec<SmoothTriangle>) -> Self {
-        let mut model = Self {smooth_triangles, bounds: Bounds::new(), triangles_ids: HashSet::new()};
+        let mut model = Self {smooth_triangles, bounds: Bounds::new(), materials: vec![], triangles_ids: HashSet::new()};
         model.init();
         model
     }
 
-    pub(crate) fn set_transformation(& mut self, transformation: Matrix<4>) {
-        let transformed_triangles = self.smooth_triangles.iter().map( |t| SmoothTriangle::new(
-            &transformation * &t.triangle.p1,
-            &transformation * &t.triangle.p2,
-            &transformation * &t.triangle.p3,
-            &transformation * &t.n1,
-            &transformation * &t.n2,
-            &transformation * &t.n3,
-        )).collect();
-        self.smooth_triangles = transformed_triangles;
-        self.init();
-    }
-
     pub(crate) fn bounds(&self) -> Bounds {
         self.bounds.clone()
     }
@@ -66,6 +55,10 @@ impl SmoothTriangleModel {
         self.bounds = bounds;
     }
 
+    pub(crate) fn face_material(&self, triangle: &Triangle) -> Option<&Material> {
+        triangle.material_index.and_then(|index| self.materials.get(index))
+    }
+
     pub(crate) fn includes(&self, object: &Object) -> bool {
         self.triangles_ids.contains(&object.object_id)
     }
@@ -0,0 +1,162 @@
+use crate::core::bounds::Bounds;
+use crate::core::math::{EPSILON, Float};
+use crate::core::ray::Ray;
+use crate::core::tuple::{point, Tuple, vector};
+
+// number of samples per unit of path inside the bounding box to bracket the roots of non convex shapes
+const STEPS_PER_UNIT: Float = 64.0;
+const MIN_STEPS: usize = 16;
+// number of golden section steps to find the deepest point of a convex shape along a ray
+const MINIMIZATION_STEPS: usize = 64;
+// number of bisections to shrink a bracket before the Newton refinement
+const BISECTIONS: usize = 40;
+const NEWTON_STEPS: usize = 8;
+
+// Superellipsoid inside the [-1, 1] cube, y being the "north-south" axis:
+//   (|x|^(2/e2) + |z|^(2/e2))^(e2/e1) + |y|^(2/e1) = 1
+// e1 = e2 = 1 gives a sphere, small values a rounded cube, 2 an octahedron
+// and bigger values pinched "star" shapes.
+#[derive(Debug, Copy, Clone)]
+pub struct Superellipsoid {
+    pub e1: Float,
+    pub e2: Float,
+}
+
+impl PartialEq for Superellipsoid {
+    fn eq(&self, other: &Self) -> bool {
+        self.e1 == other.e1 && self.e2 == other.e2
+    }
+}
+
+impl Superellipsoid {
+    pub fn new(e1: Float, e2: Float) -> Self {
+        Superellipsoid { e1, e2 }
+    }
+
+    // inside-outside function: < 0 inside, 0 on the surface, > 0 outside
+    pub fn inside_outside(&self, p: &Tuple) -> Float {
+        let g = p.x.abs().powf(2.0 / self.e2) + p.z.abs().powf(2.0 / self.e2);
+        g.powf(self.e2 / self.e1) + p.y.abs().powf(2.0 / self.e1) - 1.0
+    }
+
+    fn gradient(&self, p: &Tuple) -> Tuple {
+        let a = 2.0 / self.e2;
+        let b = 2.0 / self.e1;
+        let g = p.x.abs().powf(a) + p.z.abs().powf(a);
+        let g_factor = if g > 0.0 { b * g.powf(self.e2 / self.e1 - 1.0) } else { 0.0 };
+        let d = |value: Float, exponent: Float| if value == 0.0 { 0.0 } else { value.signum() * value.abs().powf(exponent - 1.0) };
+        vector(g_factor * d(p.x, a), b * d(p.y, b), g_factor * d(p.z, a))
+    }
+
+    pub fn intersect(&self, ray: &Ray) -> Vec<Float> {
+        let mut xs = vec![];
+        let bounds_xs = self.bounds().intersect(ray);
+        if bounds_xs.is_empty() {
+            return xs;
+        }
+
+        let (t_min, t_max) = (bounds_xs[0], bounds_xs[1]);
+        let f = |t: Float| self.inside_outside(&ray.position(t));
+        if self.e1 <= 2.0 && self.e2 <= 2.0 {
+            return self.intersect_convex(ray, t_min, t_max);
+        }
+
+        // the sampling is as dense for short paths in the box as for long ones
+        let length = (t_max - t_min) * ray.direction.magnitude();
+        let steps = ((length * STEPS_PER_UNIT).ceil() as usize).max(MIN_STEPS);
+        let mut t0 = t_min;
+        let mut f0 = f(t0);
+        for i in 1..=steps {
+            let t1 = t_min + (t_max - t_min) * i as Float / steps as Float;
+            let f1 = f(t1);
+            if (f0 < 0.0) != (f1 < 0.0) {
+                xs.push(self.refine(ray, t0, t1, f0));
+            }
+            t0 = t1;
+            f0 = f1;
+        }
+
+        xs
+    }
+
+    // With exponents up to 2 the shape is convex, the inside-outside function decreases then increases
+    // along a ray: its minimum splits the path in the box in two parts with a root at most, even for grazing rays.
+    fn intersect_convex(&self, ray: &Ray, t_min: Float, t_max: Float) -> Vec<Float> {
+        let f = |t: Float| self.inside_outside(&ray.position(t));
+        let ratio = ((5.0 as Float).sqrt() - 1.0) / 2.0;
+        let (mut a, mut b) = (t_min, t_max);
+        let (mut c, mut d) = (b - ratio * (b - a), a + ratio * (b - a));
+        let (mut fc, mut fd) = (f(c), f(d));
+        for _ in 0..MINIMIZATION_STEPS {
+            if fc < fd {
+                (b, d, fd) = (d, c, fc);
+                c = b - ratio * (b - a);
+                fc = f(c);
+            } else {
+                (a, c, fc) = (c, d, fd);
+                d = a + ratio * (b - a);
+                fd = f(d);
+            }
+        }
+        let t_mid = (a + b) / 2.0;
+        let f_mid = f(t_mid);
+        if f_mid >= 0.0 {
+            return vec![];
+        }
+
+        let mut xs = vec![];
+        let (f_min, f_max) = (f(t_min), f(t_max));
+        if f_min >= 0.0 {
+            xs.push(self.refine(ray, t_min, t_mid, f_min));
+        }
+        if f_max >= 0.0 {
+            xs.push(self.refine(ray, t_mid, t_max, f_mid));
+        }
+        xs
+    }
+
+    // the root is bracketed in [t0, t1]: shrink the bracket then polish it with Newton's method,
+    // falling back to the bracket middle if an iteration leaves it.
+    fn refine(&self, ray: &Ray, mut t0: Float, mut t1: Float, f0: Float) -> Float {
+        let below = f0 < 0.0;
+        for _ in 0..BISECTIONS {
+            let t = (t0 + t1) / 2.0;
+            if (self.inside_outside(&ray.position(t)) < 0.0) == below {
+                t0 = t;
+            } else {
+                t1 = t;
+            }
+        }
+
+        let mut t = (t0 + t1) / 2.0;
+        for _ in 0..NEWTON_STEPS {
+            let p = ray.position(t);
+            let df = self.gradient(&p).dot(&ray.direction);
+            if df.abs() < EPSILON * EPSILON {
+                break;
+            }
+            let next = t - self.inside_outside(&p) / df;
+            if next < t0 || next > t1 {
+                break;
+            }
+            let done = (next - t).abs() < EPSILON * EPSILON;
+            t = next;
+            if done {
+                break;
+            }
+        }
+        t
+    }
+
+    pub fn normal_at(&self, p: Tuple) -> Tuple {
+        let gradient = self.gradient(&p);
+        if gradient.magnitude() < EPSILON * EPSILON {
+            return vector(0.0, p.y.signum(), 0.0);
+        }
+        gradient.normalize()
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds::from(point(-1.0, -1.0, -1.0), point(1.0, 1.0, 1.0))
+    }
+}
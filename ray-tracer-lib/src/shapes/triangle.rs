@@ -1,8 +1,10 @@
+use crate::colors::Color;
 use crate::core::bounds::Bounds;
 use crate::core::math;
 use crate::core::math::Float;
+use crate::shapes::uv_mapping::UvFrame;
 use crate::core::ray::Ray;
-use crate::core::tuple::Tuple;
+use crate::core::tuple::{Tuple, vector};
 use crate::object::get_next_unique_shape_id;
 
 #[derive(Debug, Clone, Copy)]
@@ -14,6 +16,10 @@ pub struct Triangle {
     pub e1: Tuple,
     pub e2: Tuple,
     pub normal: Tuple,
+    pub uvs: Option<[(Float, Float); 3]>,   // texture coordinates of p1, p2, p3
+    pub material_index: Option<usize>,      // the material of the face in the model palette
+    pub colors: Option<[Color; 3]>,         // the colors of p1, p2, p3
+    pub tangents: Option<[Tuple; 3]>,       // the tangents of p1, p2, p3 along u, w is the handedness of the bitangent (1 or -1)
 }
 
 impl PartialEq for Triangle {
@@ -28,7 +34,45 @@ impl Triangle {
         let e1 = p2-p1;
         let e2 = p3-p1;
         let normal = (e2 * &e1).normalize();
-        Self {id, p1, p2, p3, e1, e2, normal}
+        Self {id, p1, p2, p3, e1, e2, normal, uvs: None, material_index: None, colors: None, tangents: None}
+    }
+
+    // the texture coordinates at the barycentric coordinates (u, v) of an intersection
+    pub fn texture_uv(&self, u: Float, v: Float) -> Option<(Float, Float)> {
+        self.uvs.map(|[uv1, uv2, uv3]| (
+            uv1.0 * (1.0 - u - v) + uv2.0 * u + uv3.0 * v,
+            uv1.1 * (1.0 - u - v) + uv2.1 * u + uv3.1 * v,
+        ))
+    }
+
+    // the vertex colors interpolated at the barycentric coordinates (u, v) of an intersection
+    pub fn vertex_color(&self, u: Float, v: Float) -> Option<Color> {
+        self.colors.map(|[c1, c2, c3]| c1 * (1.0 - u - v) + c2 * u + c3 * v)
+    }
+
+    // the directions along the face in which the texture coordinates increase
+    pub fn face_uv_frame(&self) -> Option<(Tuple, Tuple)> {
+        let [uv1, uv2, uv3] = self.uvs?;
+        let (du1, dv1) = (uv2.0 - uv1.0, uv2.1 - uv1.1);
+        let (du2, dv2) = (uv3.0 - uv1.0, uv3.1 - uv1.1);
+        let det = du1 * dv2 - du2 * dv1;
+        if det.abs() < math::EPSILON * math::EPSILON {
+            return None;
+        }
+        Some(((self.e1 * dv2 - self.e2 * dv1) / det, (self.e2 * du1 - self.e1 * du2) / det))
+    }
+
+    // the texture frame at the barycentric coordinates (u, v) of an intersection, around the given normal:
+    // from the vertex tangents when known, from the face otherwise
+    pub fn uv_frame(&self, u: Float, v: Float, normal: &Tuple) -> Option<UvFrame> {
+        let uv = self.texture_uv(u, v)?;
+        if let Some([t1, t2, t3]) = self.tangents {
+            let t = t1 * (1.0 - u - v) + t2 * u + t3 * v;
+            let tangent = vector(t.x, t.y, t.z);
+            return Some(UvFrame { uv, tangent, bitangent: (*normal * &tangent) * t1.w.signum() });
+        }
+        let (tangent, bitangent) = self.face_uv_frame()?;
+        Some(UvFrame { uv, tangent, bitangent })
     }
 
     pub fn intersect(&self, ray: &Ray) -> Vec<Float> {
@@ -1,8 +1,8 @@
 use std::collections::HashSet;
 use crate::core::bounds::Bounds;
 use crate::core::math::Float;
-use crate::core::matrix::Matrix;
 use crate::core::ray::Ray;
+use crate::material::Material;
 use crate::object::Object;
 use crate::shapes::triangle::Triangle;
 
@@ -10,41 +10,31 @@ use crate::shapes::triangle::Triangle;
 pub struct TriangleModel {
     pub triangles : Vec<Triangle>,
     pub bounds : Bounds,
+    pub materials : Vec<Material>,  // the palette of the faces with a material index
     triangles_ids : HashSet<usize>
 }
 
 impl TriangleModel {
     pub fn new(triangles : Vec<Triangle>) -> Self {
-        let mut model = Self {triangles, bounds: Bounds::new(), triangles_ids: HashSet::new()};
+        let mut model = Self {triangles, bounds: Bounds::new(), materials: vec![], triangles_ids: HashSet::new()};
         model.init();
         model
     }
 
-    pub(crate) fn set_transformation(& mut self, transformation: Matrix<4>) {
-        let transformed_triangles = self.triangles.iter().map( |t| Triangle::new(
-            &transformation * &t.p1,
-            &transformation * &t.p2,
-            &transformation * &t.p3
-        )).collect();
-        self.triangles = transformed_triangles;
-        self.init();
-    }
-
     pub(crate) fn bounds(&self) -> Bounds {
         self.bounds.clone()
     }
 
-    pub(crate) fn intersect(&self, ray: &Ray) -> Vec<(Float, Triangle)> {
+    pub(crate) fn intersect(&self, ray: &Ray) -> Vec<(Float, Triangle, Float, Float)> {
         if self.bounds.intersect(&ray).is_empty() {
             return vec![];
         }
         let mut xs = vec![];
 
         for triangle in self.triangles.iter() {
-            let child_xs = triangle.intersect(ray);
-            for t in child_xs.iter() {
-                let x = (*t, triangle.clone());
-                xs.push(x);
+            let (t, u, v) = triangle.intersect_uv(ray);
+            if ! Float::is_nan(t) {
+                xs.push((t, *triangle, u, v));
             }
         }
 
@@ -65,6 +55,10 @@ impl TriangleModel {
         self.bounds = bounds;
     }
 
+    pub(crate) fn face_material(&self, triangle: &Triangle) -> Option<&Material> {
+        triangle.material_index.and_then(|index| self.materials.get(index))
+    }
+
     pub(crate) fn includes(&self, object: &Object) -> bool {
         self.triangles_ids.contains(&object.object_id)
     }
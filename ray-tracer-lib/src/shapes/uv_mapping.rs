@@ -0,0 +1,60 @@
+use crate::core::math::{EPSILON, Float, PI};
+use crate::core::tuple::{Tuple, vector};
+
+// The texture coordinates of a point of a surface, and the directions along the surface
+// in which they increase (not normalized), in object space.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct UvFrame {
+    pub uv: (Float, Float),
+    pub tangent: Tuple,     // along u
+    pub bitangent: Tuple,   // along v
+}
+
+// u goes around the y axis, v from the bottom (0) to the top (1), undefined at the poles
+pub fn spherical_frame(p: &Tuple) -> Option<UvFrame> {
+    let theta = p.x.atan2(p.z);
+    let radius = vector(p.x, p.y, p.z).magnitude();
+    let phi = (p.y / radius).acos();
+    let u = 1.0 - (theta / (2.0 * PI) + 0.5);
+    let v = 1.0 - phi / PI;
+    let rho = (p.x * p.x + p.z * p.z).sqrt();
+    if rho < EPSILON {
+        return None;
+    }
+    let tangent = vector(-p.z, 0.0, p.x);
+    let bitangent = vector(-p.x * p.y / rho, rho, -p.z * p.y / rho);
+    Some(UvFrame { uv: (u, v), tangent, bitangent })
+}
+
+// the xz plane is tiled with unit squares
+pub fn planar_frame(p: &Tuple) -> Option<UvFrame> {
+    Some(UvFrame { uv: (p.x.rem_euclid(1.0), p.z.rem_euclid(1.0)), tangent: vector(1.0, 0.0, 0.0), bitangent: vector(0.0, 0.0, 1.0) })
+}
+
+// u goes around the y axis, v along it, tiled with unit lengths
+pub fn cylindrical_frame(p: &Tuple) -> Option<UvFrame> {
+    let theta = p.x.atan2(p.z);
+    let u = 1.0 - (theta / (2.0 * PI) + 0.5);
+    Some(UvFrame { uv: (u, p.y.rem_euclid(1.0)), tangent: vector(-p.z, 0.0, p.x), bitangent: vector(0.0, 1.0, 0.0) })
+}
+
+// each face of the cube from -1 to 1 is mapped to the unit square, seen from the outside
+// with the sides upright and the top and bottom facing the front
+pub fn cubic_frame(p: &Tuple) -> Option<UvFrame> {
+    let half = |value: Float| (value + 1.0).rem_euclid(2.0) / 2.0;
+    let max = p.x.abs().max(p.y.abs()).max(p.z.abs());
+    let (uv, tangent, bitangent) = if max == p.x {
+        ((half(-p.z), half(p.y)), vector(0.0, 0.0, -1.0), vector(0.0, 1.0, 0.0))
+    } else if max == -p.x {
+        ((half(p.z), half(p.y)), vector(0.0, 0.0, 1.0), vector(0.0, 1.0, 0.0))
+    } else if max == p.y {
+        ((half(p.x), half(-p.z)), vector(1.0, 0.0, 0.0), vector(0.0, 0.0, -1.0))
+    } else if max == -p.y {
+        ((half(p.x), half(p.z)), vector(1.0, 0.0, 0.0), vector(0.0, 0.0, 1.0))
+    } else if max == p.z {
+        ((half(p.x), half(p.y)), vector(1.0, 0.0, 0.0), vector(0.0, 1.0, 0.0))
+    } else {
+        ((half(-p.x), half(p.y)), vector(-1.0, 0.0, 0.0), vector(0.0, 1.0, 0.0))
+    };
+    Some(UvFrame { uv, tangent, bitangent })
+}
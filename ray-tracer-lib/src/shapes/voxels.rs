@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::core::bounds::Bounds;
+use crate::core::math::{EPSILON, Float, INFINITY};
+use crate::core::ray::Ray;
+use crate::core::tuple::{point, Tuple, vector};
+use crate::material::Material;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VoxelCells {
+    Dense(Vec<Option<usize>>),
+    Sparse(HashMap<(usize, usize, usize), usize>),
+}
+
+// A grid of nx * ny * nz unit voxels, from (0, 0, 0) to (nx, ny, nz) in object space.
+// Each filled voxel stores an index in the material palette.
+// Cells and materials are shared so cloning the shape (done for every intersection) stays cheap.
+#[derive(Debug, Clone)]
+pub struct Voxels {
+    pub nx: usize,
+    pub ny: usize,
+    pub nz: usize,
+    cells: Arc<VoxelCells>,
+    materials: Arc<Vec<Material>>,
+}
+
+impl PartialEq for Voxels {
+    fn eq(&self, other: &Self) -> bool {
+        self.nx == other.nx && self.ny == other.ny && self.nz == other.nz && self.cells == other.cells
+    }
+}
+
+impl Voxels {
+    pub fn dense(nx: usize, ny: usize, nz: usize) -> Self {
+        let cells = VoxelCells::Dense(vec![None; nx * ny * nz]);
+        Voxels { nx, ny, nz, cells: Arc::new(cells), materials: Arc::new(vec![]) }
+    }
+
+    pub fn sparse(nx: usize, ny: usize, nz: usize) -> Self {
+        let cells = VoxelCells::Sparse(HashMap::new());
+        Voxels { nx, ny, nz, cells: Arc::new(cells), materials: Arc::new(vec![]) }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, z: usize, material_index: Option<usize>) {
+        if x >= self.nx || y >= self.ny || z >= self.nz {
+            return;
+        }
+        let index = self.index(x, y, z);
+        match Arc::make_mut(&mut self.cells) {
+            VoxelCells::Dense(cells) => cells[index] = material_index,
+            VoxelCells::Sparse(cells) => match material_index {
+                None => { cells.remove(&(x, y, z)); }
+                Some(material_index) => { cells.insert((x, y, z), material_index); }
+            },
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Option<usize> {
+        if x >= self.nx || y >= self.ny || z >= self.nz {
+            return None;
+        }
+        match self.cells.as_ref() {
+            VoxelCells::Dense(cells) => cells[self.index(x, y, z)],
+            VoxelCells::Sparse(cells) => cells.get(&(x, y, z)).copied(),
+        }
+    }
+
+    // adds a material to the palette and returns its index
+    pub fn add_material(&mut self, material: Material) -> usize {
+        let materials = Arc::make_mut(&mut self.materials);
+        materials.push(material);
+        materials.len() - 1
+    }
+
+    pub fn material(&self, material_index: usize) -> Option<&Material> {
+        self.materials.get(material_index)
+    }
+
+    fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        (z * self.ny + y) * self.nx + x
+    }
+
+    // returns the voxel containing the point, None if the point is outside the grid
+    fn get_at(&self, p: &Tuple) -> Option<usize> {
+        if p.x < 0.0 || p.y < 0.0 || p.z < 0.0 {
+            return None;
+        }
+        self.get(p.x.floor() as usize, p.y.floor() as usize, p.z.floor() as usize)
+    }
+
+    pub fn intersect(&self, ray: &Ray) -> Vec<Float> {
+        self.intersect_with_index(ray).into_iter().map(|(t, _)| t).collect()
+    }
+
+    // 3D-DDA (Amanatides & Woo) walk through the grid.
+    // Each run of filled voxels along the ray gives an entry and an exit intersection,
+    // with the material index of the voxel entered (or left).
+    pub fn intersect_with_index(&self, ray: &Ray) -> Vec<(Float, usize)> {
+        let mut xs = vec![];
+        let bounds_xs = self.bounds().intersect(ray);
+        if bounds_xs.is_empty() || self.nx == 0 || self.ny == 0 || self.nz == 0 {
+            return xs;
+        }
+
+        let (t_enter, t_leave) = (bounds_xs[0], bounds_xs[1]);
+        let start = ray.position(t_enter);
+        let size = [self.nx, self.ny, self.nz];
+        let mut cell = [0_usize; 3];
+        let mut step = [0_isize; 3];
+        let mut t_max = [INFINITY; 3];
+        let mut t_delta = [INFINITY; 3];
+
+        for axis in 0..3 {
+            let c = start[axis].floor().max(0.0) as usize;
+            cell[axis] = usize::min(c, size[axis] - 1);
+            let direction = ray.direction[axis];
+            if direction.abs() < EPSILON {
+                continue;
+            }
+            let (s, boundary) = if direction > 0.0 {
+                (1, (cell[axis] + 1) as Float)
+            } else {
+                (-1, cell[axis] as Float)
+            };
+            step[axis] = s;
+            t_max[axis] = (boundary - ray.origin[axis]) / direction;
+            t_delta[axis] = (1.0 / direction).abs();
+        }
+
+        let mut t = t_enter;
+        let mut current: Option<usize> = None;
+        loop {
+            let voxel = self.get(cell[0], cell[1], cell[2]);
+            match (current, voxel) {
+                (None, Some(material_index)) => xs.push((t, material_index)),
+                (Some(material_index), None) => xs.push((t, material_index)),
+                _ => {}
+            }
+            current = voxel;
+
+            // step along the axis whose next boundary is the closest
+            let mut axis = 0;
+            if t_max[1] < t_max[axis] {
+                axis = 1;
+            }
+            if t_max[2] < t_max[axis] {
+                axis = 2;
+            }
+            if t_max[axis] > t_leave || step[axis] == 0 {
+                break;
+            }
+            t = t_max[axis];
+            t_max[axis] += t_delta[axis];
+            if step[axis] < 0 && cell[axis] == 0 {
+                break;
+            }
+            cell[axis] = (cell[axis] as isize + step[axis]) as usize;
+            if cell[axis] >= size[axis] {
+                break;
+            }
+        }
+
+        if let Some(material_index) = current {
+            xs.push((t_leave, material_index));
+        }
+
+        xs
+    }
+
+    // the face normal of the voxel: the point lies on the grid plane closest to it,
+    // the normal goes from the filled side to the empty side.
+    pub fn normal_at(&self, p: Tuple) -> Tuple {
+        let mut axis = 0;
+        let mut min_dist = INFINITY;
+        for i in 0..3 {
+            let dist = (p[i] - p[i].round()).abs();
+            if dist < min_dist {
+                min_dist = dist;
+                axis = i;
+            }
+        }
+
+        let plane = p[axis].round();
+        let offset = |value: Float| match axis {
+            0 => point(value, p.y, p.z),
+            1 => point(p.x, value, p.z),
+            _ => point(p.x, p.y, value),
+        };
+        let below = self.get_at(&offset(plane - 0.5));
+        let above = self.get_at(&offset(plane + 0.5));
+        let sign = if above.is_some() && below.is_none() { -1.0 } else { 1.0 };
+
+        match axis {
+            0 => vector(sign, 0.0, 0.0),
+            1 => vector(0.0, sign, 0.0),
+            _ => vector(0.0, 0.0, sign),
+        }
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        Bounds::from(point(0.0, 0.0, 0.0), point(self.nx as Float, self.ny as Float, self.nz as Float))
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::io::Read;
+use crate::colors::Color;
+use crate::core::math::Float;
+use crate::core::tuple::{point, Tuple};
+use crate::mesh::bytes::Bytes;
+use crate::mesh::error::{MeshError, MeshErrorReason};
+use crate::shapes::triangle::Triangle;
+use crate::shapes::triangle_model::TriangleModel;
+
+// Reads ASCII and binary STL files.
+// STL only has facet normals so the models are flat, each solid gives a named model.
+// The face colors of binary files follow the VisCAM / SolidView convention (bit 15 set, 5 bits per channel).
+pub struct StlReader<T> {
+    pub source : T,
+    pub models : HashMap<String, TriangleModel>,
+}
+
+impl<T> StlReader<T> where T: Read {
+    pub fn new(source : T) -> Self {
+        Self { source, models: HashMap::new() }
+    }
+
+    pub fn read(&mut self) -> Result<(), MeshError> {
+        let mut data = vec![];
+        self.source.read_to_end(&mut data).map_err(|error| MeshError { line: 0, reason: MeshErrorReason::Io(error) })?;
+
+        // binary files may also start with "solid", their size tells them apart
+        let binary_size = data.get(80..84).map(|count| 84 + 50 * u32::from_le_bytes(count.try_into().unwrap()) as usize);
+        if data.starts_with(b"solid") && binary_size != Some(data.len()) {
+            self.read_ascii(&data)
+        } else {
+            self.read_binary(&data).map_err(|reason| MeshError { line: 0, reason })
+        }
+    }
+
+    fn read_binary(&mut self, data: &[u8]) -> Result<(), MeshErrorReason> {
+        let mut bytes = Bytes::new(data, false);
+        bytes.skip(80)?;
+        let count = bytes.u32()?;
+        let mut triangles = vec![];
+        for _ in 0..count {
+            bytes.skip(12)?;
+            let mut points = [point(0.0, 0.0, 0.0); 3];
+            for p in points.iter_mut() {
+                *p = point(bytes.f32()? as Float, bytes.f32()? as Float, bytes.f32()? as Float);
+            }
+            let attribute = bytes.u16()?;
+            let mut triangle = Triangle::new(points[0], points[1], points[2]);
+            if attribute & 0x8000 != 0 {
+                let channel = |shift: u16| ((attribute >> shift) & 0x1f) as Float / 31.0;
+                let color = Color::new(channel(10), channel(5), channel(0));
+                triangle.colors = Some([color; 3]);
+            }
+            triangles.push(triangle);
+        }
+        self.models.insert(String::from("Default"), TriangleModel::new(triangles));
+        Ok(())
+    }
+
+    fn read_ascii(&mut self, data: &[u8]) -> Result<(), MeshError> {
+        let text = String::from_utf8_lossy(data);
+        let mut name = String::from("Default");
+        let mut triangles = vec![];
+        let mut points: Vec<Tuple> = vec![];
+        for (i, line) in text.lines().enumerate() {
+            let error = |reason| MeshError { line: i + 1, reason };
+            let items: Vec<&str> = line.split_whitespace().collect();
+            match items.first() {
+                Some(&"solid") => {
+                    name = items.get(1).map_or(String::from("Default"), |name| String::from(*name));
+                    triangles = vec![];
+                }
+                Some(&"outer") => points = vec![],
+                Some(&"vertex") => {
+                    if items.len() < 4 {
+                        return Err(error(MeshErrorReason::MissingValue));
+                    }
+                    let mut xyz = [0.0; 3];
+                    for (value, item) in xyz.iter_mut().zip(items[1..4].iter()) {
+                        *value = item.parse::<Float>().map_err(|_| error(MeshErrorReason::InvalidNumber(String::from(*item))))?;
+                    }
+                    points.push(point(xyz[0], xyz[1], xyz[2]));
+                }
+                Some(&"endloop") => {
+                    if points.len() < 3 {
+                        return Err(error(MeshErrorReason::NotEnoughVertices));
+                    }
+                    for j in 1..points.len() - 1 {
+                        triangles.push(Triangle::new(points[0], points[j], points[j + 1]));
+                    }
+                }
+                Some(&"endsolid") => {
+                    self.models.insert(name.clone(), TriangleModel::new(triangles.clone()));
+                    triangles = vec![];
+                }
+                _ => {}
+            }
+        }
+        // a truncated file without endsolid
+        if !triangles.is_empty() {
+            self.models.insert(name, TriangleModel::new(triangles));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use crate::aov::{Aov, AovBuffers, SurfaceSample};
+    use crate::camera::Camera;
+    use crate::canvas::Canvas;
+    use crate::colors::Color;
+    use crate::core::math::PI;
+    use crate::core::ray::ray;
+    use crate::core::transform::{translation, view_transform};
+    use crate::core::tuple::{point, vector};
+    use crate::lights::point_light::PointLight;
+    use crate::object::{build_plane, build_sphere};
+    use crate::tests::helper::tests::build_world;
+    use crate::world::World;
+
+    fn camera() -> Camera {
+        let mut camera = Camera::new(11, 11, PI / 2.0);
+        camera.set_transform(view_transform(point(0.0, 0.0, -5.0), point(0.0, 0.0, 0.0), vector(0.0, 1.0, 0.0)));
+        camera
+    }
+
+    #[test]
+    fn rendering_the_output_variables_test() {
+        let world = build_world();
+        let (image, buffers) = camera().render_with_aovs(&world, "", &Aov::ALL);
+        assert_eq!(image.pixel_at(5, 5), Color::new(0.38066, 0.47583, 0.2855));
+
+        let value = |aov: Aov, x: usize, y: usize| buffers.get(aov).unwrap().pixel_at(x, y);
+        assert_eq!(value(Aov::Depth, 5, 5), Color::new(4.0, 4.0, 4.0));
+        assert_eq!(value(Aov::Normal, 5, 5), Color::new(0.0, 0.0, -1.0));
+        let id = world.objects[0].object_id as f64;
+        assert_eq!(value(Aov::ObjectId, 5, 5), Color::new(id, id, id));
+        assert_eq!(value(Aov::Albedo, 5, 5), Color::new(0.8, 1.0, 0.6));
+        assert_eq!(value(Aov::Shadow, 5, 5), Color::black());
+
+        // the corners see no object
+        for (_, buffer) in buffers.iter() {
+            assert_eq!(buffer.alpha_at(0, 0), 0.0);
+            assert_eq!(buffer.pixel_at(0, 0), Color::black());
+            assert_eq!(buffer.alpha_at(5, 5), 1.0);
+        }
+    }
+
+    #[test]
+    fn rendering_without_output_variables_test() {
+        let (_, buffers) = camera().render_with_aovs(&build_world(), "", &[]);
+        assert!(buffers.is_empty());
+        let buffers = AovBuffers::new(&[Aov::Depth, Aov::Depth], 2, 2);
+        assert_eq!(buffers.iter().count(), 1);
+        assert!(buffers.get(Aov::Normal).is_none());
+    }
+
+    #[test]
+    fn the_shadow_is_the_fraction_of_the_lights_hidden_test() {
+        let mut sphere = build_sphere();
+        sphere.set_transformation(translation(0.0, 2.0, 0.0));
+        let mut world = World::new();
+        world.set_objects(vec![build_plane(), sphere]);
+        world.set_lights(vec![PointLight::new(point(0.0, 10.0, 0.0), Color::white()),
+                              PointLight::new(point(10.0, 10.0, 0.0), Color::white())]);
+        let surface = world.surface_at(&ray(point(3.0, 0.5, 0.0), vector(-6.0, -1.0, 0.0))).unwrap();
+        assert_eq!(surface.shadow, 0.5);
+        assert_eq!(surface.normal, vector(0.0, 1.0, 0.0));
+        assert_eq!(surface.depth, vector(3.0, 0.5, 0.0).magnitude());
+        assert_eq!(surface.object_id, world.objects[0].object_id);
+        assert!(world.surface_at(&ray(point(0.0, 5.0, 0.0), vector(0.0, 1.0, 0.0))).is_none());
+    }
+
+    #[test]
+    fn a_sample_gives_the_color_and_the_surface_of_a_ray_test() {
+        let world = build_world();
+        let r = ray(point(0.0, 0.0, -5.0), vector(0.0, 0.0, 1.0));
+        let (color, alpha, surface) = world.sample_at(&r, 5, true);
+        assert_eq!((color, alpha), world.color_and_alpha_at(&r, 5));
+        assert_eq!(surface.unwrap().depth, world.surface_at(&r).unwrap().depth);
+        assert!(world.sample_at(&r, 5, false).2.is_none());
+    }
+
+    #[test]
+    fn displaying_the_output_variables_test() {
+        let sample = |depth: f64, object_id: usize| SurfaceSample {
+            depth, normal: vector(0.0, 1.0, 0.0), object_id, albedo: Color::white(), shadow: 0.0,
+        };
+        let mut buffers = AovBuffers::new(&[Aov::Depth, Aov::Normal, Aov::ObjectId], 3, 1);
+        buffers.write(0, 0, Some(&sample(2.0, 7)));
+        buffers.write(1, 0, Some(&sample(6.0, 7)));
+        buffers.write(2, 0, None);
+
+        let depth: Canvas = Aov::Depth.display(buffers.get(Aov::Depth).unwrap());
+        assert_eq!(depth.pixel_at(0, 0), Color::white());
+        assert_eq!(depth.pixel_at(1, 0), Color::black());
+        assert_eq!(depth.alpha_at(2, 0), 0.0);
+        let normal = Aov::Normal.display(buffers.get(Aov::Normal).unwrap());
+        assert_eq!(normal.pixel_at(0, 0), Color::new(0.5, 1.0, 0.5));
+        assert_eq!(normal.pixel_at(2, 0), Color::black());
+        let ids = Aov::ObjectId.display(buffers.get(Aov::ObjectId).unwrap());
+        assert_eq!(ids.pixel_at(0, 0), ids.pixel_at(1, 0));
+        assert_ne!(ids.pixel_at(0, 0), Color::black());
+        assert_eq!(ids.pixel_at(2, 0), Color::black());
+    }
+
+    #[test]
+    fn parsing_the_output_variables_test() {
+        for aov in Aov::ALL {
+            assert_eq!(aov.name().parse::<Aov>(), Ok(aov));
+        }
+        assert!("position".parse::<Aov>().is_err());
+    }
+}
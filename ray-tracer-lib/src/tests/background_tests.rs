@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use image::Rgba;
+    use crate::background::Background;
+    use crate::camera::Camera;
+    use crate::canvas::Canvas;
+    use crate::colors::Color;
+    use crate::core::math::PI;
+    use crate::core::ray::ray;
+    use crate::core::transform::view_transform;
+    use crate::core::tuple::{point, vector};
+    use crate::tests::helper::tests::build_world;
+    use crate::tone_mapping::ToneMapper;
+    use crate::world::World;
+
+    fn camera(h_size: usize, v_size: usize) -> Camera {
+        let mut camera = Camera::new(h_size, v_size, PI / 2.0);
+        camera.set_transform(view_transform(point(0.0, 0.0, -5.0), point(0.0, 0.0, 0.0), vector(0.0, 1.0, 0.0)));
+        camera
+    }
+
+    #[test]
+    fn the_color_of_the_background_test() {
+        let up = ray(point(0.0, 0.0, 0.0), vector(0.0, 2.0, 0.0));
+        let down = ray(point(0.0, 0.0, 0.0), vector(0.0, -1.0, 0.0));
+        let side = ray(point(0.0, 0.0, 0.0), vector(1.0, 0.0, 0.0));
+        let solid = Background::Solid(Color::new(0.2, 0.3, 0.4));
+        assert_eq!(solid.color(&up), Color::new(0.2, 0.3, 0.4));
+        assert_eq!(solid.alpha(), 1.0);
+        let gradient = Background::Gradient { bottom: Color::white(), top: Color::new(0.0, 0.0, 1.0) };
+        assert_eq!(gradient.color(&up), Color::new(0.0, 0.0, 1.0));
+        assert_eq!(gradient.color(&down), Color::white());
+        assert_eq!(gradient.color(&side), Color::new(0.5, 0.5, 1.0));
+        assert_eq!(Background::Transparent.color(&up), Color::black());
+        assert_eq!(Background::Transparent.alpha(), 0.0);
+        assert_eq!(World::new().background, Background::Solid(Color::black()));
+    }
+
+    #[test]
+    fn the_rays_which_miss_see_the_background_test() {
+        let mut world = build_world();
+        world.set_background(Background::Solid(Color::white()));
+        let miss = ray(point(0.0, 0.0, -5.0), vector(0.0, 1.0, 0.0));
+        assert_eq!(world.color_at(&miss, 5), Color::white());
+        world.set_background(Background::Transparent);
+        assert_eq!(world.color_and_alpha_at(&miss, 5), (Color::black(), 0.0));
+        let hit = ray(point(0.0, 0.0, -5.0), vector(0.0, 0.0, 1.0));
+        assert_eq!(world.color_and_alpha_at(&hit, 5), (Color::new(0.38066, 0.47583, 0.2855), 1.0));
+    }
+
+    #[test]
+    fn rendering_covers_every_pixel_test() {
+        let mut world = World::new();
+        world.set_background(Background::Solid(Color::white()));
+        let mut camera = camera(37, 21);
+        camera.block_size = 8;
+        let canvas = camera.render(&world, "");
+        assert!(canvas.pixels().iter().all(|color| *color == Color::white()));
+        assert!(!canvas.has_alpha());
+    }
+
+    #[test]
+    fn rendering_on_a_transparent_background_test() {
+        let mut world = build_world();
+        world.set_background(Background::Transparent);
+        let canvas = camera(11, 11).render(&world, "");
+        assert!(canvas.has_alpha());
+        assert_eq!(canvas.alpha_at(5, 5), 1.0);
+        assert_eq!(canvas.alpha_at(0, 0), 0.0);
+        assert_eq!(canvas.alpha_at(10, 10), 0.0);
+        let image = canvas.to_rgba_image(&ToneMapper::new());
+        assert_eq!(image.get_pixel(0, 0), &Rgba([0, 0, 0, 0]));
+        assert_eq!(image.get_pixel(5, 5)[3], 255);
+    }
+
+    #[test]
+    fn the_alpha_channel_of_a_canvas_test() {
+        let mut canvas = Canvas::new(3, 2);
+        assert!(!canvas.has_alpha());
+        assert_eq!(canvas.alpha_at(1, 1), 1.0);
+        canvas.write_alpha(1, 1, 0.5);
+        canvas.write_alpha(3, 0, 0.0);
+        assert_eq!(canvas.alphas().unwrap(), &[1.0, 1.0, 1.0, 1.0, 0.5, 1.0]);
+        let region = canvas.region(1, 1, 2, 1).to_canvas();
+        assert_eq!(region.alphas().unwrap(), &[0.5, 1.0]);
+        let image = canvas.to_rgba_image(&ToneMapper::new());
+        assert_eq!(image.get_pixel(1, 1), &Rgba([0, 0, 0, 128]));
+        let copy = Canvas::from_rgba_image(&image, false);
+        assert_eq!(copy.alpha_at(1, 1), 128.0 / 255.0);
+    }
+
+    #[test]
+    fn parsing_the_background_test() {
+        assert_eq!("transparent".parse::<Background>(), Ok(Background::Transparent));
+        assert_eq!("1,0.5,0".parse::<Background>(), Ok(Background::Solid(Color::new(1.0, 0.5, 0.0))));
+        assert_eq!("0,0,0:0.2,0.4,1".parse::<Background>(),
+                   Ok(Background::Gradient { bottom: Color::black(), top: Color::new(0.2, 0.4, 1.0) }));
+        assert!("1,0".parse::<Background>().is_err());
+        assert!("white".parse::<Background>().is_err());
+    }
+}
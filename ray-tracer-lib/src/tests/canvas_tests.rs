@@ -1,7 +1,20 @@
 #[cfg(test)]
 mod tests {
+    use image::{Rgb, Rgb32FImage, RgbImage};
+    use rayon::prelude::*;
     use crate::canvas::Canvas;
     use crate::colors::Color;
+    use crate::core::math::Float;
+    use crate::tone_mapping::ToneMapper;
+
+    // each pixel has its own color
+    fn gradient_canvas(width: usize, height: usize) -> Canvas {
+        let mut canvas = Canvas::new(width, height);
+        for (x, y, color) in canvas.iter_mut() {
+            *color = Color::new(x as Float, y as Float, 0.5);
+        }
+        canvas
+    }
 
     #[test]
     fn creating_a_canvas_test() {
@@ -61,4 +74,78 @@ mod tests {
         let pixel_data = canvas.pixel_data();
         assert_eq!(pixel_data, "255 204 153 255 204 153 255 204 153 255 204 153 255 204 153 255 204 \n153 255 204 153 255 204 153 255 204 153 255 204 153 \n255 204 153 255 204 153 255 204 153 255 204 153 255 204 153 255 204 \n153 255 204 153 255 204 153 255 204 153 255 204 153 \n")
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn the_pixels_are_stored_row_after_row_test() {
+        let canvas = gradient_canvas(3, 2);
+        assert_eq!(canvas.pixels().len(), 6);
+        assert_eq!(canvas.pixels()[4], Color::new(1.0, 1.0, 0.5));
+        assert_eq!(canvas.row(1), &[Color::new(0.0, 1.0, 0.5), Color::new(1.0, 1.0, 0.5), Color::new(2.0, 1.0, 0.5)]);
+        assert_eq!(canvas.rows().len(), 2);
+        assert_eq!(canvas.rows().next_back().unwrap(), canvas.row(1));
+        let coordinates: Vec<(usize, usize)> = canvas.iter().map(|(x, y, _)| (x, y)).collect();
+        assert_eq!(coordinates, vec![(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn changing_rows_and_pixels_in_parallel_test() {
+        let mut canvas = Canvas::new(4, 3);
+        canvas.par_rows_mut().enumerate().for_each(|(y, row)| row.fill(Color::new(y as Float, 0.0, 0.0)));
+        assert_eq!(canvas.pixel_at(3, 2), Color::new(2.0, 0.0, 0.0));
+        canvas.par_iter_mut().for_each(|(x, _, color)| color.g = x as Float);
+        assert_eq!(canvas.pixel_at(3, 2), Color::new(2.0, 3.0, 0.0));
+        let total: Float = canvas.par_iter().map(|(_, _, color)| color.r).sum();
+        assert_eq!(total, 12.0);
+        canvas.row_mut(0)[1] = Color::white();
+        assert_eq!(canvas.pixel_at(1, 0), Color::white());
+        assert_eq!(Canvas::new(0, 3).rows_mut().count(), 0);
+    }
+
+    #[test]
+    fn a_region_of_a_canvas_test() {
+        let canvas = gradient_canvas(5, 4);
+        let region = canvas.region(1, 2, 3, 2);
+        assert_eq!(region.pixel_at(0, 0), Color::new(1.0, 2.0, 0.5));
+        assert_eq!(region.row(1).len(), 3);
+        assert_eq!(region.rows().count(), 2);
+        let cropped = region.to_canvas();
+        assert_eq!((cropped.width, cropped.height), (3, 2));
+        assert_eq!(cropped.pixel_at(2, 1), Color::new(3.0, 3.0, 0.5));
+    }
+
+    #[test]
+    #[should_panic]
+    fn a_region_outside_the_canvas_test() {
+        Canvas::new(5, 4).region(3, 0, 3, 1);
+    }
+
+    #[test]
+    fn converting_to_and_from_8_bit_images_test() {
+        let mut canvas = Canvas::new(2, 1);
+        canvas.write_pixel(0, 0, Color::new(1.5, 0.5, -0.5));
+        let image = canvas.to_rgb_image(&ToneMapper::new());
+        assert_eq!((image.width(), image.height()), (2, 1));
+        assert_eq!(image.get_pixel(0, 0), &Rgb([255, 128, 0]));
+
+        let mut image = RgbImage::new(2, 1);
+        image.put_pixel(1, 0, Rgb([255, 188, 0]));
+        let canvas = Canvas::from_rgb_image(&image, true);
+        assert_eq!(canvas.pixel_at(1, 0), Color::new(1.0, 0.5029, 0.0));
+        let mut srgb = ToneMapper::new();
+        srgb.srgb = true;
+        assert_eq!(canvas.to_rgb_image(&srgb), image);
+
+        // without sRGB the values are kept as they are both ways
+        let canvas = Canvas::from_rgb_image(&image, false);
+        assert_eq!(canvas.pixel_at(1, 0), Color::new(1.0, 188.0 / 255.0, 0.0));
+        assert_eq!(canvas.to_rgb_image(&ToneMapper::new()), image);
+    }
+
+    #[test]
+    fn converting_to_and_from_float_images_test() {
+        let canvas = gradient_canvas(3, 2);
+        let image = Rgb32FImage::from(&canvas);
+        assert_eq!(image.get_pixel(2, 1), &Rgb([2.0, 1.0, 0.5]));
+        assert_eq!(Canvas::from(&image).pixels(), canvas.pixels());
+    }
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use crate::canvas::Canvas;
+    use crate::colors::Color;
+    use crate::core::math::Float;
+    use crate::denoise::{DenoiseGuides, Denoiser};
+
+    // a grey image with a noise in [-0.2, 0.2] from a linear congruential generator
+    fn noisy(width: usize, height: usize) -> Canvas {
+        let mut seed: u64 = 42;
+        let mut canvas = Canvas::new(width, height);
+        for (_, _, color) in canvas.iter_mut() {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let noise = ((seed >> 33) as Float / (1u64 << 31) as Float - 0.5) * 0.4;
+            *color = Color::new(0.5 + noise, 0.5 + noise, 0.5 + noise);
+        }
+        canvas
+    }
+
+    fn variance(canvas: &Canvas) -> Float {
+        let n = canvas.pixels().len() as Float;
+        let mean = canvas.pixels().iter().map(|c| c.r).sum::<Float>() / n;
+        canvas.pixels().iter().map(|c| (c.r - mean).powi(2)).sum::<Float>() / n
+    }
+
+    // black on the left, white on the right, and a guide telling the halves apart
+    fn halves(width: usize, height: usize) -> (Canvas, Canvas) {
+        let mut image = Canvas::new(width, height);
+        let mut normal = Canvas::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let right = x >= width / 2;
+                image.write_pixel(x, y, if right { Color::white() } else { Color::black() });
+                normal.write_pixel(x, y, if right { Color::new(0.0, 1.0, 0.0) } else { Color::new(1.0, 0.0, 0.0) });
+            }
+        }
+        (image, normal)
+    }
+
+    #[test]
+    fn a_flat_image_is_left_unchanged_test() {
+        let mut image = Canvas::new(9, 7);
+        image.pixels_mut().fill(Color::new(0.2, 0.4, 0.6));
+        let denoised = Denoiser::new().denoise(&image, &DenoiseGuides::default()).unwrap();
+        assert!(denoised.pixels().iter().all(|c| *c == Color::new(0.2, 0.4, 0.6)));
+    }
+
+    #[test]
+    fn denoising_lowers_the_noise_test() {
+        let image = noisy(32, 32);
+        let denoised = Denoiser::new().denoise(&image, &DenoiseGuides::default()).unwrap();
+        assert!(variance(&denoised) < variance(&image) / 10.0);
+    }
+
+    #[test]
+    fn the_guides_keep_the_edges_test() {
+        let (image, normal) = halves(16, 8);
+        let mut denoiser = Denoiser::new();
+        denoiser.color_sigma = 100.0;
+        let blurred = denoiser.denoise(&image, &DenoiseGuides::default()).unwrap();
+        assert!(blurred.pixel_at(7, 4).r > 0.2);
+        let guides = DenoiseGuides { normal: Some(&normal), ..DenoiseGuides::default() };
+        let kept = denoiser.denoise(&image, &guides).unwrap();
+        assert!(kept.pixel_at(7, 4).r < 0.01);
+        assert!(kept.pixel_at(8, 4).r > 0.99);
+    }
+
+    #[test]
+    fn the_depth_guide_keeps_the_edges_test() {
+        let (image, _) = halves(16, 8);
+        let mut depth = Canvas::new(16, 8);
+        for (x, _, color) in depth.iter_mut() {
+            let d = if x >= 8 { 10.0 } else { 2.0 };
+            *color = Color::new(d, d, d);
+        }
+        let mut denoiser = Denoiser::new();
+        denoiser.color_sigma = 100.0;
+        let kept = denoiser.denoise(&image, &DenoiseGuides { depth: Some(&depth), ..DenoiseGuides::default() }).unwrap();
+        assert!(kept.pixel_at(7, 4).r < 0.01);
+    }
+
+    #[test]
+    fn null_sigmas_keep_the_pixels_finite_test() {
+        let (image, albedo) = halves(16, 8);
+        let depth = Canvas::new(16, 8);
+        let mut denoiser = Denoiser::new();
+        denoiser.normal_sigma = 0.0;
+        denoiser.depth_sigma = 0.0;
+        denoiser.albedo_sigma = 0.0;
+        let guides = DenoiseGuides { normal: Some(&albedo), depth: Some(&depth), albedo: Some(&albedo) };
+        let denoised = denoiser.denoise(&image, &guides).unwrap();
+        assert!(denoised.pixels().iter().all(|c| c.r.is_finite() && c.g.is_finite() && c.b.is_finite()));
+        assert!(denoised.pixel_at(7, 4).r < 0.01);
+    }
+
+    #[test]
+    fn the_uncovered_pixels_are_not_mixed_with_the_covered_ones_test() {
+        let (image, mut albedo) = halves(16, 8);
+        for y in 0..8 {
+            for x in 8..16 {
+                albedo.write_alpha(x, y, 0.0);
+            }
+        }
+        let mut image = image;
+        image.write_alpha(15, 0, 0.0);
+        let mut denoiser = Denoiser::new();
+        denoiser.color_sigma = 100.0;
+        denoiser.albedo_sigma = 100.0;
+        let denoised = denoiser.denoise(&image, &DenoiseGuides { albedo: Some(&albedo), ..DenoiseGuides::default() }).unwrap();
+        assert_eq!(denoised.pixel_at(7, 4), Color::black());
+        assert_eq!(denoised.pixel_at(8, 4), Color::white());
+        assert_eq!(denoised.alpha_at(15, 0), 0.0);
+        assert_eq!(denoised.alpha_at(14, 0), 1.0);
+    }
+
+    #[test]
+    fn the_guides_must_be_as_large_as_the_image_test() {
+        let normal = Canvas::new(4, 4);
+        let result = Denoiser::new().denoise(&Canvas::new(4, 3), &DenoiseGuides { normal: Some(&normal), ..DenoiseGuides::default() });
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
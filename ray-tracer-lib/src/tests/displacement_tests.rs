@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod tests {
+    use crate::colors::Color;
+    use crate::core::math::{equals, Float};
+    use crate::core::transform::scaling;
+    use crate::core::tuple::{point, Tuple};
+    use crate::mesh::displacement::{Displacement, pattern_height};
+    use crate::mesh::half_edge::HalfEdgeMesh;
+    use crate::patterns::noise::{fractal_noise, perlin_noise};
+    use crate::patterns::pattern::Pattern;
+    use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
+    use crate::shapes::triangle::Triangle;
+    use crate::shapes::triangle_model::TriangleModel;
+
+    // a unit square in the z = 0 plane, its normal is -z
+    fn square() -> TriangleModel {
+        let (a, b, c, d) = (point(0.0, 0.0, 0.0), point(1.0, 0.0, 0.0), point(1.0, 1.0, 0.0), point(0.0, 1.0, 0.0));
+        let mut t1 = Triangle::new(a, b, c);
+        t1.uvs = Some([(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)]);
+        let mut t2 = Triangle::new(a, c, d);
+        t2.uvs = Some([(0.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+        TriangleModel::new(vec![t1, t2])
+    }
+
+    fn vertices(model: &SmoothTriangleModel) -> Vec<Tuple> {
+        model.smooth_triangles.iter().flat_map(|smooth_triangle| {
+            let triangle = &smooth_triangle.triangle;
+            [triangle.p1, triangle.p2, triangle.p3]
+        }).collect()
+    }
+
+    #[test]
+    fn perlin_noise_is_zero_on_the_lattice_test() {
+        for p in [point(0.0, 0.0, 0.0), point(1.0, -2.0, 3.0), point(-5.0, 7.0, 11.0)] {
+            assert_eq!(perlin_noise(&p), 0.0);
+        }
+    }
+
+    #[test]
+    fn perlin_noise_is_bounded_and_continuous_test() {
+        let mut values = vec![];
+        for i in 0..1000 {
+            let p = point(i as Float * 0.137, i as Float * 0.071 - 20.0, i as Float * 0.029);
+            let noise = perlin_noise(&p);
+            assert!((-1.0..=1.0).contains(&noise));
+            assert!((perlin_noise(&(p + Tuple::new(0.001, 0.0, 0.0, 0.0))) - noise).abs() < 0.01);
+            values.push(noise);
+        }
+        assert!(values.iter().any(|value| *value > 0.2));
+        assert!(values.iter().any(|value| *value < -0.2));
+    }
+
+    #[test]
+    fn one_octave_of_fractal_noise_is_perlin_noise_test() {
+        let p = point(0.3, 1.7, -2.2);
+        assert_eq!(fractal_noise(&p, 1, 0.5), perlin_noise(&p));
+        assert!((-1.0..=1.0).contains(&fractal_noise(&p, 5, 0.5)));
+    }
+
+    #[test]
+    fn the_height_of_a_pattern_is_its_brightness_test() {
+        let pattern = Pattern::stripe(Color::new(1.0, 1.0, 1.0), Color::new(0.0, 0.0, 0.0));
+        assert_eq!(pattern.height_at(&point(0.5, 0.0, 0.0), None), 1.0);
+        assert_eq!(pattern.height_at(&point(1.5, 0.0, 0.0), None), 0.0);
+        assert_eq!(Pattern::new().height_at(&point(0.5, 0.0, 0.0), None), 0.0);
+    }
+
+    #[test]
+    fn a_constant_height_moves_the_vertices_along_their_normal_test() {
+        let mut displacement = Displacement::new(|_: &Tuple, _| 1.0);
+        displacement.scale = 0.5;
+        let model = displacement.displace(&square());
+        assert_eq!(model.smooth_triangles.len(), 2);
+        assert!(vertices(&model).iter().all(|p| equals(p.z, -0.5)));
+        assert_eq!(model.smooth_triangles[0].n1, model.smooth_triangles[0].triangle.normal);
+    }
+
+    #[test]
+    fn the_edges_are_split_to_the_edge_length_without_cracks_test() {
+        let mut displacement = Displacement::new(|_: &Tuple, _| 0.0);
+        displacement.edge_length = 0.2;
+        let model = displacement.displace(&square());
+        assert!(model.smooth_triangles.len() > 32);
+        let triangles: Vec<Triangle> = model.smooth_triangles.iter().map(|smooth_triangle| smooth_triangle.triangle).collect();
+        for triangle in triangles.iter() {
+            assert!(triangle.e1.magnitude() <= 0.2 && triangle.e2.magnitude() <= 0.2);
+        }
+        // the edges on the boundary of the mesh are only the sides of the square
+        let mesh = HalfEdgeMesh::from_triangles(&triangles).unwrap();
+        let boundary: Float = mesh.half_edges.iter().enumerate().filter(|(_, half_edge)| half_edge.twin.is_none())
+            .map(|(h, half_edge)| (mesh.vertices[half_edge.origin] - mesh.vertices[mesh.destination(h)]).magnitude())
+            .sum();
+        assert!(equals(boundary, 4.0));
+    }
+
+    #[test]
+    fn the_height_is_given_the_texture_coordinates_test() {
+        let mut displacement = Displacement::new(|_: &Tuple, uv: Option<(Float, Float)>| uv.unwrap().0);
+        displacement.edge_length = 0.5;
+        let model = displacement.displace(&square());
+        for smooth_triangle in model.smooth_triangles.iter() {
+            let triangle = &smooth_triangle.triangle;
+            let uvs = triangle.uvs.unwrap();
+            for (p, (u, _)) in [triangle.p1, triangle.p2, triangle.p3].iter().zip(uvs) {
+                assert!(equals(p.x, u));
+                assert!(equals(p.z, -u));
+            }
+        }
+    }
+
+    #[test]
+    fn displacing_with_a_pattern_test() {
+        let mut pattern = Pattern::gradient(Color::new(0.0, 0.0, 0.0), Color::new(1.0, 1.0, 1.0));
+        pattern.set_pattern_transform(&scaling(2.0, 1.0, 1.0));
+        let mut displacement = Displacement::new(pattern_height(pattern));
+        displacement.edge_length = 0.25;
+        let model = displacement.displace(&square());
+        // the gradient goes from 0 to 0.5 across the square
+        assert!(vertices(&model).iter().all(|p| equals(p.z, -p.x / 2.0)));
+        for smooth_triangle in model.smooth_triangles.iter() {
+            assert_eq!(smooth_triangle.n1, smooth_triangle.triangle.normal);
+            assert!(equals(smooth_triangle.n1.x * 2.0, smooth_triangle.n1.z));
+        }
+    }
+}
@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod tests {
+    use crate::colors::Color;
+    use crate::core::math::{equals, Float};
+    use crate::core::ray::ray;
+    use crate::core::tuple::{point, vector};
+    use crate::gltf_reader::GltfReader;
+    use crate::lights::point_light::PointLight;
+    use crate::mesh::error::MeshErrorReason;
+
+    // a triangle around the origin in the xy plane
+    const TRIANGLE: [f32; 9] = [-1.0, -1.0, 0.0, 1.0, -1.0, 0.0, 0.0, 1.0, 0.0];
+
+    fn glb(json: &str, bin: &[u8]) -> Vec<u8> {
+        let mut json = json.as_bytes().to_vec();
+        json.resize(json.len().div_ceil(4) * 4, b' ');
+        let mut bin = bin.to_vec();
+        bin.resize(bin.len().div_ceil(4) * 4, 0);
+        let length = 12 + 8 + json.len() + 8 + bin.len();
+
+        let mut data = vec![];
+        data.extend_from_slice(b"glTF");
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&(length as u32).to_le_bytes());
+        data.extend_from_slice(&(json.len() as u32).to_le_bytes());
+        data.extend_from_slice(b"JSON");
+        data.extend_from_slice(&json);
+        data.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        data.extend_from_slice(b"BIN\0");
+        data.extend_from_slice(&bin);
+        data
+    }
+
+    fn triangle_bytes() -> Vec<u8> {
+        TRIANGLE.iter().flat_map(|value| value.to_le_bytes()).collect()
+    }
+
+    const SCENE: &str = r#"{
+        "asset": {"version": "2.0"},
+        "scene": 0,
+        "scenes": [{"nodes": [0, 2, 3, 4]}],
+        "nodes": [
+            {"name": "parent", "translation": [0, 0, -5], "children": [1]},
+            {"name": "triangle", "mesh": 0, "translation": [1, 0, 0]},
+            {"name": "camera", "camera": 0, "translation": [0, 0, 5]},
+            {"name": "lamp", "translation": [0, 10, 0], "extensions": {"KHR_lights_punctual": {"light": 0}}},
+            {"name": "sun", "rotation": [-0.7071068, 0, 0, 0.7071068], "extensions": {"KHR_lights_punctual": {"light": 1}}}
+        ],
+        "meshes": [{"primitives": [{"attributes": {"POSITION": 0}, "material": 0}]}],
+        "materials": [{"pbrMetallicRoughness": {"baseColorFactor": [1, 0, 0, 1], "metallicFactor": 0, "roughnessFactor": 1}}],
+        "cameras": [{"type": "perspective", "perspective": {"yfov": 1.0, "znear": 0.1}}],
+        "extensionsUsed": ["KHR_lights_punctual"],
+        "extensions": {"KHR_lights_punctual": {"lights": [
+            {"type": "point", "color": [1, 1, 1], "intensity": 2},
+            {"type": "directional", "color": [1, 0.5, 0.5], "intensity": 1}
+        ]}},
+        "buffers": [{"byteLength": 36}],
+        "bufferViews": [{"buffer": 0, "byteOffset": 0, "byteLength": 36}],
+        "accessors": [{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [-1, -1, 0], "max": [1, 1, 0]}]
+    }"#;
+
+    #[test]
+    fn nodes_are_groups_with_their_transformation_test() {
+        let data = glb(SCENE, &triangle_bytes());
+        let mut gltf_reader = GltfReader::new(data.as_slice());
+        gltf_reader.read().unwrap();
+        assert_eq!(gltf_reader.objects.len(), 1);
+
+        let r = ray(point(1.5, -0.5, 0.0), vector(0.0, 0.0, -1.0));
+        let xs = gltf_reader.objects[0].intersect(&r);
+        let hit = xs.hit().unwrap();
+        assert!(equals(hit.t, 5.0));
+        assert_eq!(hit.object.material().color, Color::new(1.0, 0.0, 0.0));
+
+        let r = ray(point(0.0, -0.5, 0.0), vector(0.0, 0.0, -1.0));
+        assert!(gltf_reader.objects[0].intersect(&r).hit().is_none());
+    }
+
+    #[test]
+    fn cameras_are_placed_by_their_node_test() {
+        let data = glb(SCENE, &triangle_bytes());
+        let mut gltf_reader = GltfReader::new(data.as_slice());
+        gltf_reader.read().unwrap();
+        assert_eq!(gltf_reader.cameras.len(), 1);
+
+        let camera = gltf_reader.cameras[0].camera(101, 101);
+        assert!(equals(camera.field_of_view, 1.0));
+        let r = camera.ray_for_pixel(50, 50);
+        assert_eq!(r.origin, point(0.0, 0.0, 5.0));
+        assert_eq!(r.direction, vector(0.0, 0.0, -1.0));
+        // the image is not mirrored: the left pixels look toward -x
+        assert!(camera.ray_for_pixel(0, 50).direction.x < 0.0);
+        assert!(camera.ray_for_pixel(50, 0).direction.y > 0.0);
+    }
+
+    #[test]
+    fn the_vertical_field_of_view_is_kept_test() {
+        let data = glb(SCENE, &triangle_bytes());
+        let mut gltf_reader = GltfReader::new(data.as_slice());
+        gltf_reader.read().unwrap();
+        let camera = gltf_reader.cameras[0].camera(200, 100);
+        assert!(equals(camera.half_height, Float::tan(0.5)));
+    }
+
+    #[test]
+    fn punctual_lights_become_point_lights_test() {
+        let data = glb(SCENE, &triangle_bytes());
+        let mut gltf_reader = GltfReader::new(data.as_slice());
+        gltf_reader.light_scale = 0.5;
+        gltf_reader.read().unwrap();
+        assert_eq!(gltf_reader.lights.len(), 2);
+        assert!(gltf_reader.lights[0] == PointLight::new(point(0.0, 10.0, 0.0), Color::new(1.0, 1.0, 1.0)));
+        // the sun points down, it is far above
+        let sun = gltf_reader.lights[1].position();
+        assert!(sun.y > 1000.0);
+        assert!(equals(sun.x, 0.0) && equals(sun.z / sun.y, 0.0));
+        assert_eq!(gltf_reader.lights[1].intensity(), Color::new(0.5, 0.25, 0.25));
+    }
+
+    #[test]
+    fn a_mesh_used_twice_is_shared_test() {
+        let json = SCENE.replace(r#""scenes": [{"nodes": [0, 2, 3, 4]}]"#, r#""scenes": [{"nodes": [0, 1]}]"#);
+        let data = glb(&json, &triangle_bytes());
+        let mut gltf_reader = GltfReader::new(data.as_slice());
+        gltf_reader.read().unwrap();
+        assert_eq!(gltf_reader.objects.len(), 2);
+
+        let r = ray(point(1.5, -0.5, 0.0), vector(0.0, 0.0, -1.0));
+        let xs = gltf_reader.objects[1].intersect(&r);
+        assert!(equals(xs.hit().unwrap().t, 0.0));
+        let xs = gltf_reader.objects[0].intersect(&r);
+        assert!(equals(xs.hit().unwrap().t, 5.0));
+    }
+
+    #[test]
+    fn an_attribute_shorter_than_the_positions_is_an_error_test() {
+        let json = SCENE
+            .replace(r#""attributes": {"POSITION": 0}"#, r#""attributes": {"POSITION": 0, "TEXCOORD_0": 1}"#)
+            .replace(r#""max": [1, 1, 0]}]"#, r#""max": [1, 1, 0]}, {"bufferView": 0, "componentType": 5126, "count": 2, "type": "VEC2"}]"#);
+        let data = glb(&json, &triangle_bytes());
+        let mut gltf_reader = GltfReader::new(data.as_slice());
+        let error = gltf_reader.read().unwrap_err();
+        assert!(matches!(error.reason, MeshErrorReason::Gltf(_)));
+    }
+
+    #[test]
+    fn an_invalid_document_is_an_error_test() {
+        let mut gltf_reader = GltfReader::new("{ \"asset\": ".as_bytes());
+        let error = gltf_reader.read().unwrap_err();
+        assert!(matches!(error.reason, MeshErrorReason::Gltf(_)));
+        assert_eq!(error.line, 0);
+    }
+}
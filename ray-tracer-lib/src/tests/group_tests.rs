@@ -1,7 +1,12 @@
 #[cfg(test)]
 mod tests {
     use crate::core::matrix::Matrix;
-    use crate::object::{build_sphere, Object};
+    use crate::colors::Color;
+    use crate::material::Material;
+    use crate::object::{build_cube, build_sphere, Object, ObjectType};
+    use crate::shapes::csg::{Csg, CsgOperation};
+    use crate::shapes::triangle::Triangle;
+    use crate::shapes::triangle_model::TriangleModel;
     use crate::core::ray::ray;
     use crate::core::transform::{rotation_y, scaling, translation};
     use crate::core::tuple::{point, vector};
@@ -123,4 +128,140 @@ mod tests {
         let n = s_ref.normal_to_world(&vector(sqrt3div3, sqrt3div3, sqrt3div3));
         assert_eq!(&n, &vector(0.2857, 0.4286, -0.8571));
     }
+
+    #[test]
+    fn children_keep_their_local_transformation_test() {
+        let mut s = build_sphere();
+        s.set_transformation(translation(5.0, 0.0, 0.0));
+        let g = Group::from(vec![s], scaling(2.0, 2.0, 2.0));
+
+        let s_ref = g.group().unwrap().child(0);
+        assert_eq!(s_ref.transformation(), &translation(5.0, 0.0, 0.0));
+        assert_eq!(g.transformation(), &scaling(2.0, 2.0, 2.0));
+    }
+
+    #[test]
+    fn moving_a_parent_moves_its_descendants_test() {
+        let mut s = build_sphere();
+        s.set_transformation(translation(5.0, 0.0, 0.0));
+        let g2 = Group::from(vec![s], scaling(2.0, 2.0, 2.0));
+        let mut g1 = Group::from(vec![g2], Matrix::<4>::identity());
+        g1.set_transformation(rotation_y(PI / 2.0));
+
+        let g2_ref = g1.group().unwrap().child(0);
+        let s_ref = g2_ref.group().unwrap().child(0);
+        assert_eq!(s_ref.transformation(), &translation(5.0, 0.0, 0.0));
+        assert_eq!(&s_ref.world_to_object(&point(-2.0, 0.0, -10.0)), &point(0.0, 0.0, -1.0));
+
+        let r = ray(point(-10.0, 0.0, -10.0), vector(1.0, 0.0, 0.0));
+        let xs = g1.intersect(&r);
+        assert_eq!(xs.count(), 2);
+        assert_eq!(xs.intersections[0].t, 8.0);
+        assert_eq!(xs.intersections[1].t, 12.0);
+    }
+
+    #[test]
+    fn moving_a_parent_moves_the_children_of_a_csg_test() {
+        let mut s = build_sphere();
+        s.set_transformation(translation(5.0, 0.0, 0.0));
+        let mut cube = build_cube();
+        cube.set_transformation(translation(0.0, 100.0, 0.0));
+        let csg = Object::new_csg(Csg::new(CsgOperation::Union, s, cube));
+        let g2 = Group::from(vec![csg], scaling(2.0, 2.0, 2.0));
+        let mut g1 = Group::from(vec![g2], Matrix::<4>::identity());
+        g1.set_transformation(rotation_y(PI / 2.0));
+
+        let g2_ref = &g1.group().unwrap().children()[0];
+        let s_ref = match &g2_ref.group().unwrap().children()[0].object_type {
+            ObjectType::CsgGroup(csg) => csg.group.child(0),
+            _ => panic!("the child of the group is a csg"),
+        };
+        assert_eq!(&s_ref.world_to_object(&point(-2.0, 0.0, -10.0)), &point(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn a_cloned_group_can_be_moved_alone_test() {
+        let mut s = build_sphere();
+        s.set_transformation(translation(5.0, 0.0, 0.0));
+        let mut g1 = Group::from(vec![s], Matrix::<4>::identity());
+        let mut g2 = g1.clone();
+        g2.set_transformation(translation(0.0, 10.0, 0.0));
+        g1.set_transformation(translation(0.0, -10.0, 0.0));
+
+        let s1_ref = g1.group().unwrap().child(0);
+        let s2_ref = g2.group().unwrap().child(0);
+        assert_eq!(&s1_ref.world_to_object(&point(5.0, -10.0, 0.0)), &point(0.0, 0.0, 0.0));
+        assert_eq!(&s2_ref.world_to_object(&point(5.0, 10.0, 0.0)), &point(0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn the_bounds_of_a_group_are_in_the_parent_space_test() {
+        let mut s = build_sphere();
+        s.set_transformation(translation(5.0, 0.0, 0.0));
+        let mut g = Group::new();
+        g.add(s);
+        g.set_transformation(translation(0.0, 2.0, 0.0));
+
+        let bounds = g.bounds();
+        assert_eq!(bounds.min, point(4.0, 1.0, -1.0));
+        assert_eq!(bounds.max, point(6.0, 3.0, 1.0));
+    }
+
+    #[test]
+    fn children_inherit_the_material_of_their_group_test() {
+        let mut red = Material::new();
+        red.color = Color::red();
+        let mut green = Material::new();
+        green.color = Color::green();
+
+        let s1 = build_sphere();
+        let mut s2 = build_sphere();
+        s2.set_material(green);
+        let inner = Group::from(vec![build_sphere()], Matrix::<4>::identity());
+        let mut g = Group::from(vec![s1, s2, inner], Matrix::<4>::identity());
+        g.set_material(red);
+
+        let group = g.group().unwrap();
+        assert_eq!(group.child(0).material().color, Color::red());
+        assert_eq!(group.child(1).material().color, Color::green());
+        assert_eq!(group.child(2).group().unwrap().child(0).material().color, Color::red());
+    }
+
+    #[test]
+    fn the_nearest_ancestor_material_is_used_test() {
+        let mut red = Material::new();
+        red.color = Color::red();
+        let mut green = Material::new();
+        green.color = Color::green();
+
+        let mut inner = Group::from(vec![build_sphere()], Matrix::<4>::identity());
+        inner.set_material(green);
+        let mut g = Group::new();
+        g.inherit_material(red);
+        g.add(inner);
+        g.add(build_sphere());
+
+        assert_eq!(g.child(0).group().unwrap().child(0).material().color, Color::green());
+        assert_eq!(g.child(1).material().color, Color::red());
+    }
+
+    #[test]
+    fn csg_and_triangle_groups_inherit_the_material_test() {
+        let mut red = Material::new();
+        red.color = Color::red();
+
+        let csg = Object::new_csg(Csg::new(CsgOperation::Union, build_sphere(), build_cube()));
+        let triangle = Triangle::new(point(0.0, 1.0, 0.0), point(-1.0, 0.0, 0.0), point(1.0, 0.0, 0.0));
+        let mut model = Object::new_triangle_group(TriangleModel::new(vec![triangle]));
+        model.set_transformation(translation(0.0, 0.0, 5.0));
+        let mut g = Group::from(vec![csg, model], Matrix::<4>::identity());
+        g.set_material(red);
+
+        let r = ray(point(0.0, 0.0, -5.0), vector(0.0, 0.0, 1.0));
+        let xs = g.intersect(&r);
+        assert_eq!(xs.count(), 3);
+        for x in xs.intersections.iter() {
+            assert_eq!(x.object.material().color, Color::red());
+        }
+    }
 }
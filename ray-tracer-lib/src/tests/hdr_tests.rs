@@ -0,0 +1,113 @@
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, ErrorKind};
+    use crate::canvas::Canvas;
+    use crate::colors::Color;
+    use crate::core::math::Float;
+    use crate::hdr::{from_rgbe, read_hdr, read_image, read_pfm, to_rgbe, write_hdr, write_pfm};
+
+    fn hdr_canvas() -> Canvas {
+        let mut canvas = Canvas::new(3, 2);
+        canvas.write_pixel(0, 0, Color::new(1.0, 0.5, 0.25));
+        canvas.write_pixel(1, 0, Color::new(12.5, 3.0, 0.0));
+        canvas.write_pixel(2, 1, Color::new(0.001, 200.0, 40.0));
+        canvas
+    }
+
+    fn close(c1: Color, c2: Color, tolerance: Float) -> bool {
+        let max = c2.r.max(c2.g).max(c2.b);
+        (c1.r - c2.r).abs() <= max * tolerance && (c1.g - c2.g).abs() <= max * tolerance && (c1.b - c2.b).abs() <= max * tolerance
+    }
+
+    #[test]
+    fn encoding_a_color_with_a_shared_exponent_test() {
+        assert_eq!(to_rgbe(Color::new(1.0, 0.5, 0.25)), [128, 64, 32, 129]);
+        assert_eq!(to_rgbe(Color::new(12.5, 3.0, 0.0)), [200, 48, 0, 132]);
+        assert_eq!(to_rgbe(Color::black()), [0, 0, 0, 0]);
+        assert_eq!(to_rgbe(Color::new(-1.0, 0.5, 0.0)), [0, 128, 0, 128]);
+        assert_eq!(from_rgbe([0, 0, 0, 0]), Color::black());
+        assert!(close(from_rgbe([200, 48, 0, 132]), Color::new(12.5, 3.0, 0.0), 0.01));
+    }
+
+    #[test]
+    fn a_hdr_image_keeps_the_colors_above_one_test() {
+        let canvas = hdr_canvas();
+        let mut data = vec![];
+        write_hdr(&canvas, &mut data).unwrap();
+        assert!(data.starts_with(b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X 3\n"));
+        let image = read_hdr(&mut Cursor::new(data)).unwrap();
+        assert_eq!((image.width, image.height), (3, 2));
+        for y in 0..2 {
+            for x in 0..3 {
+                assert!(close(image.pixel_at(x, y), canvas.pixel_at(x, y), 0.01));
+            }
+        }
+    }
+
+    #[test]
+    fn reading_run_length_encoded_scanlines_test() {
+        let mut data = b"#?RGBE\nEXPOSURE=1.0\n\n+Y 2 +X 8\n".to_vec();
+        for _ in 0..2 {
+            data.extend_from_slice(&[2, 2, 0, 8]);
+            // red: a run of 8, green: 8 literal values, blue: two runs, exponent: a run
+            data.extend_from_slice(&[136, 128]);
+            data.extend_from_slice(&[8, 0, 16, 32, 48, 64, 80, 96, 112]);
+            data.extend_from_slice(&[132, 0, 132, 255]);
+            data.extend_from_slice(&[136, 129]);
+        }
+        let image = read_image(&mut Cursor::new(data)).unwrap();
+        assert_eq!((image.width, image.height), (8, 2));
+        assert!(close(image.pixel_at(1, 0), Color::new(1.0, 0.125, 0.0), 0.01));
+        assert!(close(image.pixel_at(7, 1), Color::new(1.0, 0.875, 2.0), 0.01));
+    }
+
+    #[test]
+    fn a_pfm_image_keeps_the_floats_test() {
+        let canvas = hdr_canvas();
+        let mut data = vec![];
+        write_pfm(&canvas, &mut data).unwrap();
+        assert!(data.starts_with(b"PF\n3 2\n-1.0\n"));
+        assert_eq!(data.len(), 12 + 3 * 2 * 12);
+        // the bottom row is first
+        assert_eq!(&data[12..16], &0.0f32.to_le_bytes());
+        assert_eq!(&data[36..40], &0.001f32.to_le_bytes());
+        let image = read_image(&mut Cursor::new(data)).unwrap();
+        for y in 0..2 {
+            for x in 0..3 {
+                assert!(close(image.pixel_at(x, y), canvas.pixel_at(x, y), 1e-6));
+            }
+        }
+    }
+
+    #[test]
+    fn reading_a_big_endian_grey_pfm_image_test() {
+        let mut data = b"Pf\n2 1\n1.0\n".to_vec();
+        data.extend_from_slice(&2.5f32.to_be_bytes());
+        data.extend_from_slice(&0.5f32.to_be_bytes());
+        let image = read_pfm(&mut Cursor::new(data)).unwrap();
+        assert_eq!(image.pixel_at(0, 0), Color::new(2.5, 2.5, 2.5));
+        assert_eq!(image.pixel_at(1, 0), Color::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn invalid_images_are_errors_test() {
+        assert!(read_image(&mut Cursor::new(b"P3\n1 1\n255\n".to_vec())).is_err());
+        assert!(read_hdr(&mut Cursor::new(b"#?RADIANCE\nFORMAT=32-bit_rle_xyze\n\n-Y 1 +X 1\n".to_vec())).is_err());
+        assert!(read_hdr(&mut Cursor::new(b"#?RADIANCE\n\n-Y 2 +X 2\n\x01\x02".to_vec())).is_err());
+        assert!(read_pfm(&mut Cursor::new(b"PF\n2 x\n-1.0\n".to_vec())).is_err());
+    }
+
+    #[test]
+    fn the_size_of_an_image_is_checked_before_reading_it_test() {
+        // sizes overflowing the pixel count, or larger than the data that follows
+        let huge = format!("-Y {} +X {}\n", usize::MAX / 2, 4);
+        let error = read_hdr(&mut Cursor::new(format!("#?RADIANCE\n\n{}", huge).into_bytes())).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        let error = read_hdr(&mut Cursor::new(b"#?RADIANCE\n\n-Y 100000 +X 100000\n\x02\x02".to_vec())).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+        let error = read_pfm(&mut Cursor::new(format!("PF\n{} 3\n-1.0\n", usize::MAX).into_bytes())).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        let error = read_pfm(&mut Cursor::new(b"Pf\n100000 100000\n-1.0\n\0\0\0\0".to_vec())).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+    }
+}
@@ -1,5 +1,6 @@
 #[cfg(test)]
 pub mod tests {
+    use crate::background::Background;
     use crate::colors::Color;
     use crate::core::intersection::Intersection;
     use crate::core::transform::scaling;
@@ -7,6 +8,7 @@ pub mod tests {
     use crate::lights::point_light::PointLight;
     use crate::material::Material;
     use crate::object::{build_sphere, Object};
+    use crate::patterns::normal_map::NormalMap;
     use crate::patterns::pattern::Pattern;
     use crate::world::World;
 
@@ -21,14 +23,15 @@ pub mod tests {
             reflective: 0.0,
             transparency: 0.0,
             refractive_index: 1.0,
-            pattern: Pattern::new()
+            pattern: Pattern::new(),
+            normal_map: NormalMap::None,
         });
 
         let mut sphere_2 = build_sphere();
         sphere_2.set_transformation(scaling(0.5, 0.5, 0.5));
 
         let light = PointLight::new(point(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
-        World { objects: vec!(sphere_1, sphere_2), lights: vec!(light) }
+        World { objects: vec!(sphere_1, sphere_2), lights: vec!(light), background: Background::default() }
     }
 
     pub fn build_glass_sphere() -> Object {
@@ -41,6 +44,6 @@ pub mod tests {
     }
 
     pub fn dummy_intersection() -> Intersection {
-        Intersection {t: 0.0, object: build_sphere(), u: 0.0, v: 0.0}
+        Intersection {t: 0.0, object: build_sphere(), u: 0.0, v: 0.0, texture_uv: None, vertex_color: None}
     }
 }
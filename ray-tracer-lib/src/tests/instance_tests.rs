@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::colors::Color;
+    use crate::core::intersection::Intersection;
+    use crate::core::math::Float;
+    use crate::core::ray::ray;
+    use crate::core::transform::{scaling, translation};
+    use crate::core::tuple::{point, vector};
+    use crate::material::Material;
+    use crate::object::{build_sphere, Object};
+    use crate::shapes::group::Group;
+    use crate::shapes::triangle::Triangle;
+    use crate::shapes::triangle_model::TriangleModel;
+
+    #[test]
+    fn intersecting_an_instance_test() {
+        let sphere = Arc::new(build_sphere());
+        let mut instance = Object::new_instance(sphere.clone());
+        instance.set_transformation(translation(5.0, 0.0, 0.0));
+
+        let r = ray(point(5.0, 0.0, -5.0), vector(0.0, 0.0, 1.0));
+        let xs = instance.intersect(&r);
+        assert_eq!(xs.count(), 2);
+        assert_eq!(xs[0].t, 4.0);
+        assert_eq!(xs[1].t, 6.0);
+
+        // the shared object is not moved
+        let r = ray(point(0.0, 0.0, -5.0), vector(0.0, 0.0, 1.0));
+        assert_eq!(instance.intersect(&r).count(), 0);
+        assert_eq!(sphere.intersect(&r).count(), 2);
+    }
+
+    #[test]
+    fn the_normal_on_an_instance_test() {
+        let mut sphere = build_sphere();
+        sphere.set_transformation(scaling(2.0, 2.0, 2.0));
+        let mut instance = Object::new_instance(Arc::new(sphere));
+        instance.set_transformation(translation(5.0, 0.0, 0.0));
+
+        let r = ray(point(5.0, 0.0, -5.0), vector(0.0, 0.0, 1.0));
+        let xs = instance.intersect(&r);
+        assert_eq!(xs[0].t, 3.0);
+        let hit = &xs[0];
+        let n = hit.object.normal_at(r.position(hit.t), hit);
+        assert_eq!(n, vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn instances_share_the_geometry_test() {
+        let triangle = Triangle::new(point(0.0, 1.0, 0.0), point(-1.0, 0.0, 0.0), point(1.0, 0.0, 0.0));
+        let model = Arc::new(Object::new_triangle_group(TriangleModel::new(vec![triangle])));
+        let mut instances = vec![];
+        for i in 0..10 {
+            let mut instance = Object::new_instance(model.clone());
+            instance.set_transformation(translation(i as Float * 3.0, 0.0, 0.0));
+            instances.push(instance);
+        }
+        assert_eq!(Arc::strong_count(&model), 11);
+
+        let r = ray(point(27.0, 0.5, -2.0), vector(0.0, 0.0, 1.0));
+        let xs = instances[9].intersect(&r);
+        assert_eq!(xs.count(), 1);
+        assert_eq!(xs[0].t, 2.0);
+        assert_eq!(instances[0].intersect(&r).count(), 0);
+
+        let bounds = instances[9].bounds();
+        assert_eq!(bounds.min, point(26.0, 0.0, 0.0));
+        assert_eq!(bounds.max, point(28.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn an_instance_can_override_the_material_test() {
+        let mut red = Material::new();
+        red.color = Color::red();
+        let mut sphere = build_sphere();
+        sphere.set_material(red);
+        let sphere = Arc::new(sphere);
+
+        let r = ray(point(0.0, 0.0, -5.0), vector(0.0, 0.0, 1.0));
+        let instance = Object::new_instance(sphere.clone());
+        assert_eq!(instance.intersect(&r)[0].object.material().color, Color::red());
+
+        let mut green = Material::new();
+        green.color = Color::green();
+        let mut instance = Object::new_instance(sphere.clone());
+        instance.set_material(green);
+        assert_eq!(instance.intersect(&r)[0].object.material().color, Color::green());
+        assert_eq!(sphere.material().color, Color::red());
+    }
+
+    #[test]
+    fn an_instance_in_a_transformed_group_test() {
+        let instance = Object::new_instance(Arc::new(build_sphere()));
+        let mut group = Group::new();
+        group.set_transformation(translation(0.0, 3.0, 0.0));
+        group.add(instance);
+
+        let r = ray(point(0.0, 3.0, -5.0), vector(0.0, 0.0, 1.0));
+        let xs = group.intersect(&r);
+        assert_eq!(xs.count(), 2);
+        let hit: &Intersection = &xs[0];
+        assert_eq!(hit.object.normal_at(r.position(hit.t), hit), vector(0.0, 0.0, -1.0));
+    }
+}
@@ -0,0 +1,188 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime};
+    use crate::colors::Color;
+    use crate::core::tuple::{point, Tuple, vector};
+    use crate::mesh::cache::{ModelCache, SourceStamp};
+    use crate::mesh::error::MeshErrorReason;
+    use crate::obj_reader::ObjReader;
+    use crate::shapes::smooth_triangle::SmoothTriangle;
+    use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
+    use crate::shapes::triangle::Triangle;
+    use crate::shapes::triangle_model::TriangleModel;
+
+    const TRIANGLE: &str = "v 0 1 0
+v -1 0 0
+v 1 0 0
+f 1 2 3
+";
+
+    // a directory of its own for each test
+    fn directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("ray_tracer_mesh_cache_{}_{}", std::process::id(), name));
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn stamp() -> SourceStamp {
+        SourceStamp { size: 10, modified: (20, 30), hash: 40 }
+    }
+
+    #[test]
+    fn a_cache_keeps_the_models_test() {
+        let mut triangle = Triangle::new(point(0.0, 1.0, 0.0), point(-1.0, 0.0, 0.0), point(1.0, 0.0, 0.0));
+        triangle.uvs = Some([(0.5, 1.0), (0.0, 0.0), (1.0, 0.0)]);
+        triangle.material_index = Some(2);
+        triangle.colors = Some([Color::new(1.0, 0.0, 0.0), Color::new(0.0, 1.0, 0.0), Color::new(0.0, 0.0, 1.0)]);
+        triangle.tangents = Some([Tuple::new(1.0, 0.0, 0.0, 1.0), Tuple::new(1.0, 0.0, 0.0, -1.0), Tuple::new(0.0, 0.0, 1.0, 1.0)]);
+        let smooth_triangle = SmoothTriangle { triangle, n1: vector(0.0, 1.0, 0.0), n2: vector(-1.0, 0.0, 0.0), n3: vector(1.0, 0.0, 0.0) };
+        let mut cache = ModelCache {
+            smooth_angle: Some(0.5),
+            material_libraries: vec![String::from("materials.mtl")],
+            material_names: vec![String::from("red"), String::from("green"), String::from("blue")],
+            vertices: vec![point(0.0, 1.0, 0.0), point(-1.0, 0.0, 0.0)],
+            vertex_colors: vec![Some(Color::new(0.5, 0.25, 1.0)), None],
+            normals: vec![vector(0.0, 0.0, 1.0)],
+            texture_coordinates: vec![(0.25, 0.75)],
+            faces: vec![vec![0, 1, 2, 3], vec![1, 2, 0]],
+            ..ModelCache::default()
+        };
+        cache.models.insert(String::from("flat"), TriangleModel::new(vec![triangle]));
+        cache.smooth_models.insert(String::from("smooth"), SmoothTriangleModel::new(vec![smooth_triangle.clone()]));
+
+        let (read_stamp, read) = ModelCache::from_bytes(&cache.to_bytes(&stamp())).unwrap();
+        assert_eq!(read_stamp, stamp());
+        assert_eq!(read.smooth_angle, Some(0.5));
+        assert_eq!(read.material_libraries, cache.material_libraries);
+        assert_eq!(read.material_names, cache.material_names);
+        let flat = &read.models["flat"].triangles[0];
+        assert_eq!(*flat, triangle);
+        assert_eq!(flat.uvs, triangle.uvs);
+        assert_eq!(flat.material_index, Some(2));
+        assert_eq!(flat.colors, triangle.colors);
+        let tangents = flat.tangents.unwrap();
+        assert_eq!(tangents, triangle.tangents.unwrap());
+        assert_eq!(tangents.map(|tangent| tangent.w), [1.0, -1.0, 1.0]);
+        assert_eq!(flat.normal, triangle.normal);
+        let smooth = &read.smooth_models["smooth"].smooth_triangles[0];
+        assert_eq!(smooth.triangle, triangle);
+        assert_eq!((smooth.n1, smooth.n2, smooth.n3), (smooth_triangle.n1, smooth_triangle.n2, smooth_triangle.n3));
+        assert_eq!(read.vertices, cache.vertices);
+        assert_eq!(read.vertex_colors, cache.vertex_colors);
+        assert_eq!(read.normals, cache.normals);
+        assert_eq!(read.texture_coordinates, cache.texture_coordinates);
+        assert_eq!(read.faces, cache.faces);
+    }
+
+    #[test]
+    fn a_cache_of_another_version_is_refused_test() {
+        let mut data = ModelCache::default().to_bytes(&stamp());
+        data[4] += 1;
+        assert!(matches!(ModelCache::from_bytes(&data), Err(MeshErrorReason::InvalidHeader(_))));
+        assert!(matches!(ModelCache::from_bytes(b"OBJ?"), Err(MeshErrorReason::InvalidHeader(_))));
+    }
+
+    #[test]
+    fn the_obj_reader_writes_and_reuses_its_cache_test() {
+        let directory = directory("reuse");
+        let source = directory.join("triangle.obj");
+        fs::write(&source, TRIANGLE).unwrap();
+
+        let mut obj_reader = ObjReader::open(&source).unwrap();
+        obj_reader.read().unwrap();
+        let cache_path = ModelCache::path_for(&source);
+        assert_eq!(cache_path, directory.join("triangle.obj.rtmesh"));
+        assert!(cache_path.exists());
+
+        // a cache matching the source is read instead of the source
+        let mut cache = ModelCache::default();
+        let other = Triangle::new(point(0.0, 0.0, 0.0), point(0.0, 0.0, 1.0), point(1.0, 0.0, 0.0));
+        cache.models.insert(String::from("Default"), TriangleModel::new(vec![other]));
+        cache.save(&cache_path, &SourceStamp::of(&source).unwrap()).unwrap();
+        let mut obj_reader = ObjReader::open(&source).unwrap();
+        obj_reader.read().unwrap();
+        assert_eq!(obj_reader.models["Default"].triangles[0], other);
+        assert!(obj_reader.vertices.is_empty());
+
+        // other settings ignore the cache
+        let mut obj_reader = ObjReader::open(&source).unwrap();
+        obj_reader.smooth_angle = Some(1.0);
+        obj_reader.read().unwrap();
+        assert_eq!(obj_reader.vertices.len(), 3);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn a_cached_source_gives_the_same_reader_test() {
+        let directory = directory("same");
+        let source = directory.join("colors.obj");
+        fs::write(&source, "v 0 1 0 1 0 0\nv -1 0 0 0 1 0\nv 1 0 0 0 0 1\nv 0 -1 0\nvt 0 0\nvn 0 0 -1\nf 1 2 3 4\n").unwrap();
+        let mut first = ObjReader::open(&source).unwrap();
+        first.read().unwrap();
+        assert!(ModelCache::path_for(&source).exists());
+
+        let mut second = ObjReader::open(&source).unwrap();
+        second.read().unwrap();
+        assert_eq!(second.vertices, first.vertices);
+        assert_eq!(second.vertex_colors, first.vertex_colors);
+        assert_eq!(second.normals, first.normals);
+        assert_eq!(second.texture_coordinates, first.texture_coordinates);
+        assert_eq!(second.faces, vec![vec![0, 1, 2, 3]]);
+        assert_eq!(second.models["Default"].triangles, first.models["Default"].triangles);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn a_changed_source_is_read_again_test() {
+        let directory = directory("changed");
+        let source = directory.join("triangle.obj");
+        fs::write(&source, TRIANGLE).unwrap();
+        let mut obj_reader = ObjReader::open(&source).unwrap();
+        obj_reader.read().unwrap();
+
+        fs::write(&source, TRIANGLE.replace("v 0 1 0", "v 0 10 0")).unwrap();
+        let mut obj_reader = ObjReader::open(&source).unwrap();
+        obj_reader.read().unwrap();
+        assert_eq!(obj_reader.models["Default"].triangles[0].p1, point(0.0, 10.0, 0.0));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn a_touched_source_keeps_its_cache_test() {
+        let directory = directory("touched");
+        let source = directory.join("triangle.obj");
+        fs::write(&source, TRIANGLE).unwrap();
+        let stamp = SourceStamp::of(&source).unwrap();
+        let mut cache = ModelCache::default();
+        let other = Triangle::new(point(0.0, 0.0, 0.0), point(0.0, 0.0, 1.0), point(1.0, 0.0, 0.0));
+        cache.models.insert(String::from("Default"), TriangleModel::new(vec![other]));
+        cache.save(&ModelCache::path_for(&source), &stamp).unwrap();
+
+        let file = fs::File::options().write(true).open(&source).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(10)).unwrap();
+        assert_ne!(SourceStamp::of(&source).unwrap().modified, stamp.modified);
+        let mut obj_reader = ObjReader::open(&source).unwrap();
+        obj_reader.read().unwrap();
+        assert_eq!(obj_reader.models["Default"].triangles[0], other);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn the_cache_can_be_turned_off_test() {
+        let directory = directory("off");
+        let source = directory.join("triangle.obj");
+        fs::write(&source, TRIANGLE).unwrap();
+        let mut obj_reader = ObjReader::open(&source).unwrap();
+        obj_reader.use_cache = false;
+        obj_reader.read().unwrap();
+        assert!(!ModelCache::path_for(&source).exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,88 @@
+#[cfg(test)]
+mod tests {
+    use crate::core::math::{Float, PI, SQRT2};
+    use crate::core::tuple::{point, vector};
+    use crate::mesh::normals::{smooth_model, smooth_normals};
+    use crate::obj_reader::ObjReader;
+    use crate::shapes::triangle::Triangle;
+    use crate::shapes::triangle_model::TriangleModel;
+
+    // two faces of a cube sharing the edge from (0, 0, 0) to (0, 1, 0)
+    fn cube_edge() -> Vec<Triangle> {
+        vec![
+            Triangle::new(point(0.0, 0.0, 0.0), point(0.0, 1.0, 0.0), point(1.0, 0.0, 0.0)),
+            Triangle::new(point(0.0, 0.0, 0.0), point(0.0, 0.0, 1.0), point(0.0, 1.0, 0.0)),
+        ]
+    }
+
+    #[test]
+    fn coplanar_faces_keep_their_normal_test() {
+        let triangles = vec![
+            Triangle::new(point(-1.0, 1.0, 0.0), point(-1.0, 0.0, 0.0), point(1.0, 0.0, 0.0)),
+            Triangle::new(point(-1.0, 1.0, 0.0), point(1.0, 0.0, 0.0), point(1.0, 1.0, 0.0)),
+        ];
+        let smooth_triangles = smooth_normals(&triangles, PI / 4.0);
+        for (smooth_triangle, triangle) in smooth_triangles.iter().zip(triangles.iter()) {
+            assert_eq!(smooth_triangle.triangle, *triangle);
+            assert_eq!(smooth_triangle.n1, triangle.normal);
+            assert_eq!(smooth_triangle.n2, triangle.normal);
+            assert_eq!(smooth_triangle.n3, triangle.normal);
+        }
+    }
+
+    #[test]
+    fn an_edge_above_the_threshold_stays_hard_test() {
+        let triangles = cube_edge();
+        let smooth_triangles = smooth_normals(&triangles, PI / 4.0);
+        assert_eq!(smooth_triangles[0].n1, triangles[0].normal);
+        assert_eq!(smooth_triangles[1].n3, triangles[1].normal);
+    }
+
+    #[test]
+    fn an_edge_below_the_threshold_is_smoothed_test() {
+        let triangles = cube_edge();
+        let smooth_triangles = smooth_normals(&triangles, PI * 2.0 / 3.0);
+        let n = (triangles[0].normal + triangles[1].normal).normalize();
+        assert_eq!(n, vector(1.0 / SQRT2, 0.0, 1.0 / SQRT2));
+        // the shared vertices get the average normal, the others their face normal
+        assert_eq!(smooth_triangles[0].n1, n);
+        assert_eq!(smooth_triangles[0].n2, n);
+        assert_eq!(smooth_triangles[0].n3, triangles[0].normal);
+        assert_eq!(smooth_triangles[1].n1, n);
+        assert_eq!(smooth_triangles[1].n3, n);
+    }
+
+    #[test]
+    fn a_smooth_model_keeps_the_faces_data_test() {
+        let mut triangles = cube_edge();
+        triangles[0].uvs = Some([(0.0, 0.0), (0.0, 1.0), (1.0, 0.0)]);
+        triangles[1].material_index = Some(0);
+        let mut model = TriangleModel::new(triangles);
+        model.materials = vec![crate::material::Material::new()];
+        let smooth = smooth_model(&model, PI / 4.0);
+        assert_eq!(smooth.smooth_triangles.len(), 2);
+        assert_eq!(smooth.smooth_triangles[0].triangle.uvs, Some([(0.0, 0.0), (0.0, 1.0), (1.0, 0.0)]));
+        assert_eq!(smooth.smooth_triangles[1].triangle.material_index, Some(0));
+        assert_eq!(smooth.materials.len(), 1);
+    }
+
+    #[test]
+    fn the_obj_reader_can_generate_the_normals_test() {
+        let str = "
+v 0 0 0
+v 0 1 0
+v 1 0 0
+v 0 0 1
+g Edge
+f 1 2 3
+f 1 4 2
+";
+        let mut obj_reader = ObjReader::new(str.as_bytes());
+        obj_reader.smooth_angle = Some(100.0 as Float * PI / 180.0);
+        obj_reader.read().unwrap();
+        assert!(obj_reader.models.is_empty());
+        let model = &obj_reader.smooth_models["Edge"];
+        assert_eq!(model.smooth_triangles.len(), 2);
+        assert_eq!(model.smooth_triangles[0].n1, vector(1.0 / SQRT2, 0.0, 1.0 / SQRT2));
+    }
+}
@@ -0,0 +1,81 @@
+#[cfg(test)]
+mod tests {
+    use crate::core::math::{equals, SQRT2};
+    use crate::core::ray::ray;
+    use crate::core::tuple::{point, vector};
+    use crate::shapes::metaballs::{Metaball, Metaballs};
+
+    // with a weight of 1 and a threshold of 0.125, a lone ball of radius 2 is a sphere of radius √2
+    fn single_ball() -> Metaballs {
+        Metaballs::new(vec![Metaball::new(point(0.0, 0.0, 0.0), 2.0, 1.0)], 0.125)
+    }
+
+    #[test]
+    fn the_field_of_a_ball_test() {
+        let ball = Metaball::new(point(0.0, 0.0, 0.0), 2.0, 1.0);
+        assert!(equals(ball.field_at(&point(0.0, 0.0, 0.0)), 1.0));
+        assert!(equals(ball.field_at(&point(0.0, SQRT2, 0.0)), 0.125));
+        assert!(equals(ball.field_at(&point(0.0, 3.0, 0.0)), 0.0));
+    }
+
+    #[test]
+    fn a_ray_intersects_a_single_ball_test() {
+        let metaballs = single_ball();
+        let r = ray(point(0.0, 0.0, -5.0), vector(0.0, 0.0, 1.0));
+        let xs = metaballs.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert!(equals(xs[0], 5.0 - SQRT2));
+        assert!(equals(xs[1], 5.0 + SQRT2));
+    }
+
+    #[test]
+    fn a_ray_misses_a_single_ball_test() {
+        let metaballs = single_ball();
+        let r = ray(point(0.0, 1.8, -5.0), vector(0.0, 0.0, 1.0));
+        assert!(metaballs.intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn close_balls_blend_together_test() {
+        let metaballs = Metaballs::new(vec![
+            Metaball::new(point(-1.5, 0.0, 0.0), 2.0, 1.0),
+            Metaball::new(point(1.5, 0.0, 0.0), 2.0, 1.0),
+        ], 0.125);
+        // halfway between the centers, each ball alone would be missed
+        let r = ray(point(0.0, 0.0, -5.0), vector(0.0, 0.0, 1.0));
+        assert!(metaballs.balls[0].field_at(&point(0.0, 0.0, 0.0)) < metaballs.threshold);
+        let xs = metaballs.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert!(xs[0] > 3.0 && xs[0] < 5.0);
+    }
+
+    #[test]
+    fn a_negative_ball_carves_a_hole_test() {
+        let metaballs = Metaballs::new(vec![
+            Metaball::new(point(0.0, 0.0, 0.0), 2.0, 1.0),
+            Metaball::new(point(0.0, 0.0, 0.0), 1.0, -1.0),
+        ], 0.125);
+        let r = ray(point(0.0, 0.0, -5.0), vector(0.0, 0.0, 1.0));
+        let xs = metaballs.intersect(&r);
+        assert_eq!(xs.len(), 4);
+    }
+
+    #[test]
+    fn the_normal_of_a_single_ball_test() {
+        let metaballs = single_ball();
+        assert_eq!(metaballs.normal_at(point(0.0, SQRT2, 0.0)), vector(0.0, 1.0, 0.0));
+        assert_eq!(metaballs.normal_at(point(-1.0, 1.0, 0.0)), vector(-1.0, 1.0, 0.0).normalize());
+    }
+
+    #[test]
+    fn the_bounds_of_metaballs_ignore_negative_balls_test() {
+        let metaballs = Metaballs::new(vec![
+            Metaball::new(point(0.0, 0.0, 0.0), 1.0, 1.0),
+            Metaball::new(point(2.0, 0.0, 0.0), 1.0, 1.0),
+            Metaball::new(point(0.0, 5.0, 0.0), 1.0, -1.0),
+        ], 0.5);
+        let bounds = metaballs.bounds();
+        assert_eq!(bounds.min, point(-1.0, -1.0, -1.0));
+        assert_eq!(bounds.max, point(3.0, 1.0, 1.0));
+    }
+}
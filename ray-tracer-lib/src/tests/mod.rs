@@ -25,3 +25,25 @@ mod material_tests;
 mod obj_reader_tests;
 mod smooth_triangle_tests;
 mod csg_tests;
+mod voxels_tests;
+mod metaballs_tests;
+mod superellipsoid_tests;
+mod quadric_tests;
+mod instance_tests;
+mod mtl_reader_tests;
+mod mesh_normals_tests;
+mod stl_reader_tests;
+mod ply_reader_tests;
+mod gltf_reader_tests;
+mod obj_writer_tests;
+mod ply_writer_tests;
+mod mesh_cache_tests;
+mod vertex_color_tests;
+mod subdivision_tests;
+mod displacement_tests;
+mod normal_map_tests;
+mod hdr_tests;
+mod tone_mapping_tests;
+mod background_tests;
+mod aov_tests;
+mod denoise_tests;
@@ -0,0 +1,150 @@
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::Arc;
+    use crate::colors::Color;
+    use crate::core::math::equals;
+    use crate::core::ray::ray;
+    use crate::core::tuple::{point, vector};
+    use crate::material::Material;
+    use crate::mtl_reader::MtlReader;
+    use crate::mesh::error::MeshErrorReason;
+use crate::obj_reader::ObjReader;
+    use crate::object::Object;
+    use crate::patterns::image::{ImagePattern, Texture};
+    use crate::patterns::pattern::Patterns;
+
+    #[test]
+    fn reading_material_statements_test() {
+        let str = "
+# two materials
+newmtl red
+Ka 1 1 1
+Kd 1 0 0
+Ks 0.5 0.5 0.5
+Ns 50
+illum 2
+
+newmtl glass
+Kd 0.1 0.1 0.1
+Ks 0.9 0.9 0.9
+d 0.2
+Ni 1.5
+illum 3
+";
+        let mut mtl_reader = MtlReader::new(str.as_bytes());
+        mtl_reader.read().unwrap();
+
+        let red = &mtl_reader.materials["red"];
+        assert_eq!(red.color, Color::red());
+        assert!(equals(red.ambient, Material::new().ambient));
+        assert!(equals(red.diffuse, 1.0));
+        assert!(equals(red.specular, 0.5));
+        assert!(equals(red.shininess, 50.0));
+        assert!(equals(red.reflective, 0.0));
+
+        let glass = &mtl_reader.materials["glass"];
+        assert!(equals(glass.transparency, 0.8));
+        assert!(equals(glass.refractive_index, 1.5));
+        assert!(equals(glass.reflective, 0.9));
+    }
+
+    #[test]
+    fn illum_switches_the_highlights_off_test() {
+        let str = "
+newmtl flat
+Kd 0 1 0
+Ks 1 1 1
+illum 0
+newmtl matte
+Ks 1 1 1
+illum 1
+";
+        let mut mtl_reader = MtlReader::new(str.as_bytes());
+        mtl_reader.read().unwrap();
+        let flat = &mtl_reader.materials["flat"];
+        assert!(equals(flat.ambient, 1.0));
+        assert!(equals(flat.diffuse, 0.0));
+        assert!(equals(flat.specular, 0.0));
+        assert!(equals(mtl_reader.materials["matte"].specular, 0.0));
+    }
+
+    #[test]
+    fn a_malformed_material_is_an_error_test() {
+        let mut mtl_reader = MtlReader::new("newmtl red\nKd 1 zero 0\n".as_bytes());
+        let error = mtl_reader.read().unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(matches!(error.reason, MeshErrorReason::InvalidNumber(_)));
+    }
+
+    #[test]
+    fn an_image_pattern_uses_the_texture_coordinates_test() {
+        let texture = Texture::new(2, 2, vec![Color::red(), Color::green(), Color::blue(), Color::white()]).unwrap();
+        let pattern = ImagePattern::new(texture);
+        assert_eq!(pattern.uv_pattern_at(0.25, 0.75), Color::red());
+        assert_eq!(pattern.uv_pattern_at(0.75, 0.75), Color::green());
+        assert_eq!(pattern.uv_pattern_at(0.25, 0.25), Color::blue());
+        assert_eq!(pattern.uv_pattern_at(1.75, -0.75), Color::white());
+        // the copies share the texture
+        assert!(Arc::ptr_eq(&pattern.clone().texture, &pattern.texture));
+    }
+
+    #[test]
+    fn a_texture_needs_a_pixel_per_position_test() {
+        assert!(Texture::new(2, 2, vec![Color::red(); 3]).is_err());
+        assert!(Texture::new(0, 0, vec![]).is_err());
+    }
+
+    #[test]
+    fn faces_use_the_material_of_their_group_test() {
+        let str = "
+v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+usemtl red
+f 1 2 3
+usemtl unknown
+f 1 3 4
+";
+        let mut red = Material::new();
+        red.color = Color::red();
+        let mut obj_reader = ObjReader::new(str.as_bytes());
+        obj_reader.materials.insert(String::from("red"), red);
+        obj_reader.read().unwrap();
+
+        let mut green = Material::new();
+        green.color = Color::green();
+        let mut model = Object::new_triangle_group(obj_reader.models["Default"].clone());
+        model.set_material(green);
+
+        let xs = model.intersect(&ray(point(-0.5, 0.25, -2.0), vector(0.0, 0.0, 1.0)));
+        assert_eq!(xs[0].object.material().color, Color::red());
+        let xs = model.intersect(&ray(point(0.5, 0.75, -2.0), vector(0.0, 0.0, 1.0)));
+        assert_eq!(xs[0].object.material().color, Material::new().color);
+    }
+
+    #[test]
+    fn reading_the_material_libraries_next_to_the_obj_file_test() {
+        let directory = std::env::temp_dir().join("ray_tracer_mtl_reader_test");
+        fs::create_dir_all(&directory).unwrap();
+        let image = image::RgbImage::from_pixel(2, 2, image::Rgb([0, 0, 255]));
+        image.save(directory.join("blue.png")).unwrap();
+        fs::write(directory.join("scene.mtl"), "newmtl textured\nKd 1 1 1\nmap_Kd -s 1 1 1 blue.png\n").unwrap();
+        fs::write(directory.join("scene.obj"), "mtllib scene.mtl\nv 0 1 0\nv -1 0 0\nv 1 0 0\nvt 0 0\nusemtl textured\nf 1/1 2/1 3/1\n").unwrap();
+
+        let mut obj_reader = ObjReader::open(directory.join("scene.obj")).unwrap();
+        obj_reader.read().unwrap();
+        let material = &obj_reader.models["Default"].materials[0];
+        match &material.pattern.pattern {
+            Patterns::Image(image) => assert_eq!(image.uv_pattern_at(0.5, 0.5), Color::blue()),
+            _ => panic!("an image pattern is expected"),
+        }
+
+        fs::write(directory.join("broken.obj"), "mtllib missing.mtl\n").unwrap();
+        let mut obj_reader = ObjReader::open(directory.join("broken.obj")).unwrap();
+        let error = obj_reader.read().unwrap_err();
+        assert_eq!(error.line, 1);
+        assert!(matches!(error.reason, MeshErrorReason::Io(_)));
+    }
+}
@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod tests {
+    use crate::colors::Color;
+    use crate::core::comps::prepare_computations;
+    use crate::core::intersection::Intersection;
+    use crate::core::intersections::intersections;
+    use crate::core::math::{EPSILON, equals, SQRT2};
+    use crate::core::ray::ray;
+    use crate::core::tuple::{point, Tuple, vector};
+    use crate::material::Material;
+    use crate::mesh::tangents::generate_tangents;
+    use crate::object::{build_plane, Object};
+    use crate::patterns::image::Texture;
+    use crate::patterns::normal_map::NormalMap;
+    use crate::patterns::pattern::Pattern;
+    use crate::shapes::smooth_triangle::SmoothTriangle;
+    use crate::shapes::triangle::Triangle;
+    use crate::shapes::uv_mapping::{cubic_frame, planar_frame, spherical_frame};
+
+    fn plane_with(normal_map: NormalMap) -> Object {
+        let mut plane = build_plane();
+        let mut material = Material::new();
+        material.normal_map = normal_map;
+        plane.set_material(material);
+        plane
+    }
+
+    fn shading_normal(object: Object, t: f64) -> (Tuple, Tuple) {
+        let r = ray(point(0.5, 1.0, 0.25), vector(0.0, -1.0, 0.0));
+        let i = Intersection::new(t, object);
+        let comps = prepare_computations(&i, &r, &intersections(vec![i.clone()]));
+        (comps.normalv, comps.over_point)
+    }
+
+    #[test]
+    fn spherical_texture_coordinates_test() {
+        for (p, (u, v)) in [(point(0.0, 0.0, -1.0), (0.0, 0.5)), (point(1.0, 0.0, 0.0), (0.25, 0.5)),
+                            (point(0.0, 0.0, 1.0), (0.5, 0.5)), (point(SQRT2 / 2.0, SQRT2 / 2.0, 0.0), (0.25, 0.75))] {
+            let frame = spherical_frame(&p).unwrap();
+            assert!(equals(frame.uv.0, u) && equals(frame.uv.1, v));
+        }
+        let frame = spherical_frame(&point(1.0, 0.0, 0.0)).unwrap();
+        assert_eq!(frame.tangent.normalize(), vector(0.0, 0.0, 1.0));
+        assert_eq!(frame.bitangent.normalize(), vector(0.0, 1.0, 0.0));
+        assert!(spherical_frame(&point(0.0, 1.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn planar_and_cubic_texture_coordinates_test() {
+        assert_eq!(planar_frame(&point(1.25, 0.0, -0.25)).unwrap().uv, (0.25, 0.75));
+        let front = cubic_frame(&point(-0.5, 0.5, 1.0)).unwrap();
+        assert_eq!(front.uv, (0.25, 0.75));
+        assert_eq!(front.tangent, vector(1.0, 0.0, 0.0));
+        let right = cubic_frame(&point(1.0, 0.5, -0.5)).unwrap();
+        assert_eq!(right.uv, (0.75, 0.75));
+        assert_eq!(right.tangent, vector(0.0, 0.0, -1.0));
+    }
+
+    #[test]
+    fn the_texture_frame_of_a_triangle_test() {
+        let mut triangle = Triangle::new(point(0.0, 0.0, 0.0), point(2.0, 0.0, 0.0), point(0.0, 2.0, 0.0));
+        assert!(triangle.uv_frame(0.25, 0.25, &triangle.normal).is_none());
+        triangle.uvs = Some([(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]);
+        let frame = triangle.uv_frame(0.25, 0.5, &triangle.normal).unwrap();
+        assert_eq!(frame.uv, (0.25, 0.5));
+        assert_eq!(frame.tangent, vector(2.0, 0.0, 0.0));
+        assert_eq!(frame.bitangent, vector(0.0, 2.0, 0.0));
+    }
+
+    #[test]
+    fn generated_tangents_give_the_frame_of_the_faces_test() {
+        let n = vector(0.0, 0.0, -1.0);
+        let mut t1 = SmoothTriangle::new(point(0.0, 0.0, 0.0), point(1.0, 0.0, 0.0), point(1.0, 1.0, 0.0), n, n, n);
+        t1.triangle.uvs = Some([(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)]);
+        let mut t2 = SmoothTriangle::new(point(0.0, 0.0, 0.0), point(1.0, 1.0, 0.0), point(0.0, 1.0, 0.0), n, n, n);
+        t2.triangle.uvs = Some([(0.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+        let mut smooth_triangles = vec![t1, t2];
+        generate_tangents(&mut smooth_triangles);
+
+        for smooth_triangle in smooth_triangles.iter() {
+            let tangents = smooth_triangle.triangle.tangents.unwrap();
+            assert!(tangents.iter().all(|tangent| vector(tangent.x, tangent.y, tangent.z) == vector(1.0, 0.0, 0.0)));
+            let frame = smooth_triangle.triangle.uv_frame(0.2, 0.3, &n).unwrap();
+            assert_eq!(frame.tangent, vector(1.0, 0.0, 0.0));
+            assert_eq!(frame.bitangent, vector(0.0, 1.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn a_bump_map_tilts_the_normal_with_the_slope_of_the_height_test() {
+        // the height goes from 0 to 1 along x
+        let pattern = Pattern::gradient(Color::new(0.0, 0.0, 0.0), Color::new(1.0, 1.0, 1.0));
+        let (normalv, over_point) = shading_normal(plane_with(NormalMap::bump(pattern, 0.5)), 1.0);
+        assert_eq!(normalv, vector(-0.5, 1.0, 0.0).normalize());
+        // the secondary rays start along the geometric normal
+        assert_eq!(over_point, point(0.5, EPSILON, 0.25));
+    }
+
+    #[test]
+    fn a_flat_height_keeps_the_normal_test() {
+        let pattern = Pattern::stripe(Color::new(1.0, 1.0, 1.0), Color::new(0.0, 0.0, 0.0));
+        let (normalv, _) = shading_normal(plane_with(NormalMap::bump(pattern, 1.0)), 1.0);
+        assert_eq!(normalv, vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn an_image_normal_map_is_in_the_texture_frame_test() {
+        // a normal halfway between u and the surface normal
+        let half = (SQRT2 / 2.0 + 1.0) / 2.0;
+        let texture = Texture::new(1, 1, vec![Color::new(half, 0.5, half)]).unwrap();
+        let (normalv, _) = shading_normal(plane_with(NormalMap::image(texture, 1.0)), 1.0);
+        assert_eq!(normalv, vector(1.0, 1.0, 0.0).normalize());
+    }
+
+    #[test]
+    fn a_flat_normal_map_keeps_the_normal_test() {
+        let texture = Texture::new(1, 1, vec![Color::new(0.5, 0.5, 1.0)]).unwrap();
+        let (normalv, _) = shading_normal(plane_with(NormalMap::image(texture, 1.0)), 1.0);
+        assert_eq!(normalv, vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn the_normal_map_of_an_inside_hit_is_turned_back_test() {
+        let half = (SQRT2 / 2.0 + 1.0) / 2.0;
+        let texture = Texture::new(1, 1, vec![Color::new(half, 0.5, half)]).unwrap();
+        let plane = plane_with(NormalMap::image(texture, 1.0));
+        let r = ray(point(0.5, -1.0, 0.25), vector(0.0, 1.0, 0.0));
+        let i = Intersection::new(1.0, plane);
+        let comps = prepare_computations(&i, &r, &intersections(vec![i.clone()]));
+        assert!(comps.inside);
+        assert_eq!(comps.normalv, vector(-1.0, -1.0, 0.0).normalize());
+    }
+}
@@ -1,7 +1,11 @@
 #[cfg(test)]
 mod tests {
+    use crate::core::math::equals;
+    use crate::core::ray::ray;
     use crate::core::tuple::{point, vector};
-    use crate::obj_reader::ObjReader;
+    use crate::object::Object;
+    use crate::mesh::error::MeshErrorReason;
+use crate::obj_reader::ObjReader;
 
     #[test]
     fn ignoring_unrecognized_lines_test() {
@@ -13,7 +17,7 @@ in a relative way,\n
 and came back the previous night.\n
 ";
         let mut obj_reader = ObjReader::new(str.as_bytes());
-        obj_reader.read();
+        obj_reader.read().unwrap();
         assert!(obj_reader.triangles.is_empty());
     }
 
@@ -27,7 +31,7 @@ v 1 0 0\n
 v 1 1 0\n
 ";
         let mut obj_reader = ObjReader::new(str.as_bytes());
-        obj_reader.read();
+        obj_reader.read().unwrap();
 
         let v0 = obj_reader.vertices[0];
 
@@ -51,7 +55,7 @@ f 1 3 4\n
 ";
 
         let mut obj_reader = ObjReader::new(str.as_bytes());
-        obj_reader.read();
+        obj_reader.read().unwrap();
         let model = &obj_reader.models["Default"];
         assert_eq!(model.triangles[0].p1, obj_reader.vertices[0]);
         assert_eq!(model.triangles[0].p2, obj_reader.vertices[1]);
@@ -73,7 +77,7 @@ v 0 2 0
 f 1 2 3 4 5
 ";
         let mut obj_reader = ObjReader::new(str.as_bytes());
-        obj_reader.read();
+        obj_reader.read().unwrap();
         let model = &obj_reader.models["Default"];
         assert_eq!(model.triangles[0].p1, obj_reader.vertices[0]);
         assert_eq!(model.triangles[0].p2, obj_reader.vertices[1]);
@@ -100,7 +104,7 @@ g SecondGroup
 f 1 3 4
 ";
         let mut obj_reader = ObjReader::new(str.as_bytes());
-        obj_reader.read();
+        obj_reader.read().unwrap();
 
         assert!(obj_reader.models.contains_key("FirstGroup"));
         assert!(obj_reader.models.contains_key("SecondGroup"));
@@ -122,7 +126,7 @@ vn 0.707 0 -0.707
 vn 1 2 3
 ";
         let mut obj_reader = ObjReader::new(str.as_bytes());
-        obj_reader.read();
+        obj_reader.read().unwrap();
         assert_eq!(obj_reader.normals[0], vector(0.0, 0.0, 1.0));
         assert_eq!(obj_reader.normals[1], vector(0.707, 0.0, -0.707));
         assert_eq!(obj_reader.normals[2], vector(1.0, 2.0, 3.0));
@@ -142,7 +146,7 @@ f 1/0/3 2/102/1 3/14/2
 ";
 
         let mut obj_reader = ObjReader::new(str.as_bytes());
-        obj_reader.read();
+        obj_reader.read().unwrap();
         let smooth_model = &obj_reader.smooth_models["Default"];
 
         let t1 = &smooth_model.smooth_triangles[0];
@@ -160,4 +164,193 @@ f 1/0/3 2/102/1 3/14/2
         assert_eq!(t2.n2, vector(-1.0, 0.0, 0.0));
         assert_eq!(t2.n3, vector(1.0, 0.0, 0.0));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn a_malformed_line_is_an_error_test() {
+        let str = "v 0 1 0
+v -1 0 0
+v 1 zero 0
+";
+        let mut obj_reader = ObjReader::new(str.as_bytes());
+        let error = obj_reader.read().unwrap_err();
+        assert_eq!(error.line, 3);
+        assert!(matches!(error.reason, MeshErrorReason::InvalidNumber(ref value) if value == "zero"));
+    }
+
+    #[test]
+    fn a_face_with_an_unknown_vertex_is_an_error_test() {
+        let str = "v 0 1 0
+v -1 0 0
+v 1 0 0
+f 1 2 4
+";
+        let mut obj_reader = ObjReader::new(str.as_bytes());
+        let error = obj_reader.read().unwrap_err();
+        assert_eq!(error.line, 4);
+        assert!(matches!(error.reason, MeshErrorReason::VertexOutOfRange(4)));
+        assert_eq!(error.to_string(), "line 4: no vertex 4");
+    }
+
+    #[test]
+    fn the_lenient_mode_skips_and_reports_malformed_lines_test() {
+        let str = "v 0 1 0
+v -1 0 0
+v 1 0
+v 1 0 0
+f 1 2
+f 1 2 3
+f 1 2 x
+vn 0 0 1
+f 1//1 2//1 3//2
+";
+        let mut obj_reader = ObjReader::new(str.as_bytes());
+        obj_reader.lenient = true;
+        obj_reader.read().unwrap();
+
+        assert_eq!(obj_reader.vertices.len(), 3);
+        assert_eq!(obj_reader.models["Default"].triangles.len(), 1);
+        let lines: Vec<usize> = obj_reader.errors.iter().map(|error| error.line).collect();
+        assert_eq!(lines, vec![3, 5, 7, 9]);
+        assert!(matches!(obj_reader.errors[0].reason, MeshErrorReason::MissingValue));
+        assert!(matches!(obj_reader.errors[1].reason, MeshErrorReason::NotEnoughVertices));
+        assert!(matches!(obj_reader.errors[2].reason, MeshErrorReason::InvalidIndex(_)));
+        assert!(matches!(obj_reader.errors[3].reason, MeshErrorReason::NormalOutOfRange(2)));
+    }
+
+    #[test]
+    fn texture_coordinate_records_test() {
+        let str = "
+vt 0 0
+vt 0.5 1
+vt 1
+";
+        let mut obj_reader = ObjReader::new(str.as_bytes());
+        obj_reader.read().unwrap();
+        assert_eq!(obj_reader.texture_coordinates, vec![(0.0, 0.0), (0.5, 1.0), (1.0, 0.0)]);
+    }
+
+    #[test]
+    fn all_the_face_syntaxes_test() {
+        let str = "
+v 0 1 0
+v -1 0 0
+v 1 0 0
+vt 0.5 1
+vt 0 0
+vt 1 0
+vn 0 0 -1
+f 1 2 3
+f 1/1 2/2 3/3
+f 1//1 2//1 3//1
+f 1/1/1 2/2/1 3/3/1
+";
+        let mut obj_reader = ObjReader::new(str.as_bytes());
+        obj_reader.read().unwrap();
+        let triangles = &obj_reader.models["Default"].triangles;
+        let smooth_triangles = &obj_reader.smooth_models["Default"].smooth_triangles;
+        assert_eq!(triangles.len(), 2);
+        assert_eq!(smooth_triangles.len(), 2);
+        assert_eq!(triangles[0].uvs, None);
+        assert_eq!(triangles[1].uvs, Some([(0.5, 1.0), (0.0, 0.0), (1.0, 0.0)]));
+        assert_eq!(smooth_triangles[0].triangle.uvs, None);
+        assert_eq!(smooth_triangles[0].n1, vector(0.0, 0.0, -1.0));
+        assert_eq!(smooth_triangles[1].triangle.uvs, Some([(0.5, 1.0), (0.0, 0.0), (1.0, 0.0)]));
+    }
+
+    #[test]
+    fn negative_indices_are_relative_to_the_last_record_test() {
+        let str = "
+v 5 5 5
+v 0 1 0
+v -1 0 0
+v 1 0 0
+vn 0 0 1
+f -3//-1 -2//-1 -1//-1
+";
+        let mut obj_reader = ObjReader::new(str.as_bytes());
+        obj_reader.read().unwrap();
+        let t = &obj_reader.smooth_models["Default"].smooth_triangles[0];
+        assert_eq!(t.triangle.p1, point(0.0, 1.0, 0.0));
+        assert_eq!(t.triangle.p2, point(-1.0, 0.0, 0.0));
+        assert_eq!(t.triangle.p3, point(1.0, 0.0, 0.0));
+        assert_eq!(t.n3, vector(0.0, 0.0, 1.0));
+
+        let mut obj_reader = ObjReader::new("v 0 0 0\nf -1 -2 -3\n".as_bytes());
+        let error = obj_reader.read().unwrap_err();
+        assert!(matches!(error.reason, MeshErrorReason::VertexOutOfRange(-2)));
+    }
+
+    #[test]
+    fn objects_and_material_groups_test() {
+        let str = "
+mtllib scene.mtl
+v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+o First
+usemtl red
+s 1
+f 1 2 3
+o Second
+usemtl blue
+f 1 3 4
+usemtl red
+s off
+f 1 2 4
+";
+        let mut obj_reader = ObjReader::new(str.as_bytes());
+        obj_reader.read().unwrap();
+
+        assert_eq!(obj_reader.material_names, vec![String::from("red"), String::from("blue")]);
+        let first = &obj_reader.models["First"].triangles;
+        let second = &obj_reader.models["Second"].triangles;
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].material_index, Some(0));
+        assert_eq!(second.len(), 2);
+        assert_eq!(second[0].material_index, Some(1));
+        assert_eq!(second[1].material_index, Some(0));
+    }
+
+    #[test]
+    fn a_reopened_group_adds_to_its_model_test() {
+        let str = "
+v -1 1 0
+v -1 0 0
+v 1 0 0
+g A
+f 1 2 3
+g B
+f 1 2 3
+g A
+f 1 2 3
+";
+        let mut obj_reader = ObjReader::new(str.as_bytes());
+        obj_reader.read().unwrap();
+        assert_eq!(obj_reader.models["A"].triangles.len(), 2);
+        assert_eq!(obj_reader.models["B"].triangles.len(), 1);
+    }
+
+    #[test]
+    fn the_texture_coordinates_are_carried_into_the_intersection_test() {
+        let str = "
+v 0 1 0
+v -1 0 0
+v 1 0 0
+vt 0.5 1
+vt 0 0
+vt 1 0
+f 1/1 2/2 3/3
+";
+        let mut obj_reader = ObjReader::new(str.as_bytes());
+        obj_reader.read().unwrap();
+        let model = Object::new_triangle_group(obj_reader.models["Default"].clone());
+
+        let r = ray(point(-0.2, 0.3, -2.0), vector(0.0, 0.0, 1.0));
+        let xs = model.intersect(&r);
+        assert_eq!(xs.count(), 1);
+        let (u, v) = xs[0].texture_uv.unwrap();
+        assert!(equals(u, 0.4));
+        assert!(equals(v, 0.3));
+    }
+}
@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::core::transform::{scaling, translation};
+    use crate::core::tuple::{point, vector};
+    use crate::obj_reader::ObjReader;
+    use crate::obj_writer::ObjWriter;
+    use crate::object::Object;
+    use crate::shapes::group::Group;
+    use crate::shapes::smooth_triangle::SmoothTriangle;
+    use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
+    use crate::shapes::triangle::Triangle;
+    use crate::shapes::triangle_model::TriangleModel;
+
+    fn square() -> TriangleModel {
+        TriangleModel::new(vec![
+            Triangle::new(point(0.0, 0.0, 0.0), point(1.0, 0.0, 0.0), point(1.0, 1.0, 0.0)),
+            Triangle::new(point(0.0, 0.0, 0.0), point(1.0, 1.0, 0.0), point(0.0, 1.0, 0.0)),
+        ])
+    }
+
+    fn write(object: &Object) -> String {
+        let mut obj_writer = ObjWriter::new(vec![]);
+        obj_writer.write_object(object).unwrap();
+        String::from_utf8(obj_writer.target).unwrap()
+    }
+
+    #[test]
+    fn shared_vertices_are_written_once_test() {
+        let mut obj_writer = ObjWriter::new(vec![]);
+        obj_writer.write_model(&square()).unwrap();
+        let expected = "v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+f 1 2 3
+f 1 3 4
+";
+        assert_eq!(String::from_utf8(obj_writer.target).unwrap(), expected);
+    }
+
+    #[test]
+    fn a_smooth_model_is_read_back_test() {
+        let mut triangle = Triangle::new(point(0.0, 1.0, 0.0), point(-1.0, 0.0, 0.0), point(1.0, 0.0, 0.0));
+        triangle.uvs = Some([(0.5, 1.0), (0.0, 0.0), (1.0, 0.0)]);
+        let smooth_triangle = SmoothTriangle { triangle, n1: vector(0.0, 1.0, 0.0), n2: vector(-1.0, 0.0, 0.0), n3: vector(1.0, 0.0, 0.0) };
+        let model = SmoothTriangleModel::new(vec![smooth_triangle.clone()]);
+
+        let mut obj_writer = ObjWriter::new(vec![]);
+        obj_writer.write_smooth_model(&model).unwrap();
+        let text = String::from_utf8(obj_writer.target).unwrap();
+        assert!(text.ends_with("f 1/1/1 2/2/2 3/3/3\n"));
+
+        let mut obj_reader = ObjReader::new(text.as_bytes());
+        obj_reader.read().unwrap();
+        let read = &obj_reader.smooth_models["Default"].smooth_triangles[0];
+        assert_eq!(read.triangle, smooth_triangle.triangle);
+        assert_eq!(read.triangle.uvs, smooth_triangle.triangle.uvs);
+        assert_eq!(read.n1, smooth_triangle.n1);
+        assert_eq!(read.n2, smooth_triangle.n2);
+        assert_eq!(read.n3, smooth_triangle.n3);
+    }
+
+    #[test]
+    fn the_triangles_of_a_group_are_in_world_space_test() {
+        let mut model = Object::new_triangle_group(square());
+        model.set_transformation(translation(1.0, 0.0, 0.0));
+        let inner = Group::from(vec![model], scaling(2.0, 2.0, 2.0));
+        let outer = Group::from(vec![inner], translation(0.0, 0.0, 5.0));
+
+        let text = write(&outer);
+        let mut obj_reader = ObjReader::new(text.as_bytes());
+        obj_reader.read().unwrap();
+        let triangles = &obj_reader.models["Default"].triangles;
+        assert_eq!(triangles.len(), 2);
+        assert_eq!(triangles[0].p1, point(2.0, 0.0, 5.0));
+        assert_eq!(triangles[0].p3, point(4.0, 2.0, 5.0));
+    }
+
+    #[test]
+    fn instances_are_written_with_their_placement_test() {
+        let shared = Arc::new(Object::new_triangle_group(square()));
+        let mut left = Object::new_instance(shared.clone());
+        left.set_transformation(translation(-2.0, 0.0, 0.0));
+        let right = Object::new_instance(shared);
+        let group = Group::from(vec![left, right], translation(0.0, 1.0, 0.0));
+
+        let text = write(&group);
+        let mut obj_reader = ObjReader::new(text.as_bytes());
+        obj_reader.read().unwrap();
+        let triangles = &obj_reader.models["Default"].triangles;
+        assert_eq!(triangles.len(), 4);
+        assert_eq!(triangles[0].p2, point(-1.0, 1.0, 0.0));
+        assert_eq!(triangles[2].p2, point(1.0, 1.0, 0.0));
+        assert_eq!(obj_reader.vertices.len(), 8);
+    }
+
+    #[test]
+    fn a_child_is_written_with_its_parent_transformation_test() {
+        let group = Group::from(vec![Object::new_triangle_group(square())], translation(0.0, 0.0, 3.0));
+        let child = group.group().unwrap().child(0);
+        let text = write(child);
+        assert!(text.starts_with("v 0 0 3\n"));
+    }
+}
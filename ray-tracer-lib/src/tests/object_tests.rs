@@ -62,7 +62,7 @@ mod tests {
         let mut s = build_sphere();
         let mut m = Material::new();
         m.ambient = 1.0;
-        s.set_material(m);
+        s.set_material(m.clone());
         assert_eq!(s.material(), &m);
     }
 
@@ -95,7 +95,7 @@ mod tests {
         let mut s = Object::new(Shape::Sphere(sphere));
         let mut m = Material::new();
         m.ambient = 1.0;
-        s.set_material(m);
+        s.set_material(m.clone());
         assert_eq!(s.material(), &m)
     }
 
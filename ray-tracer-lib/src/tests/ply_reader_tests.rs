@@ -0,0 +1,192 @@
+#[cfg(test)]
+mod tests {
+    use crate::colors::Color;
+    use crate::core::tuple::{point, vector};
+    use crate::mesh::error::MeshErrorReason;
+    use crate::ply_reader::PlyReader;
+
+    #[test]
+    fn reading_an_ascii_ply_test() {
+        let str = "ply
+format ascii 1.0
+comment a square
+element vertex 4
+property float x
+property float y
+property float z
+element face 1
+property list uchar int vertex_indices
+end_header
+0 0 0
+1 0 0
+1 1 0
+0 1 0
+4 0 1 2 3
+";
+        let mut ply_reader = PlyReader::new(str.as_bytes());
+        ply_reader.read().unwrap();
+        let model = &ply_reader.models["Default"];
+        assert_eq!(model.triangles.len(), 2);
+        assert_eq!(model.triangles[1].p1, point(0.0, 0.0, 0.0));
+        assert_eq!(model.triangles[1].p2, point(1.0, 1.0, 0.0));
+        assert_eq!(model.triangles[1].p3, point(0.0, 1.0, 0.0));
+        assert!(ply_reader.smooth_models.is_empty());
+    }
+
+    #[test]
+    fn vertex_normals_and_colors_give_a_smooth_colored_model_test() {
+        let str = "ply
+format ascii 1.0
+element vertex 3
+property float x
+property float y
+property float z
+property float nx
+property float ny
+property float nz
+property uchar red
+property uchar green
+property uchar blue
+element edge 1
+property int vertex1
+property int vertex2
+element face 1
+property list uchar int vertex_index
+end_header
+0 1 0 0 1 0 255 0 0
+-1 0 0 -1 0 0 0 255 0
+1 0 0 1 0 0 0 0 255
+0 1
+3 0 1 2
+";
+        let mut ply_reader = PlyReader::new(str.as_bytes());
+        ply_reader.read().unwrap();
+        let smooth_triangle = &ply_reader.smooth_models["Default"].smooth_triangles[0];
+        assert_eq!(smooth_triangle.n1, vector(0.0, 1.0, 0.0));
+        assert_eq!(smooth_triangle.n2, vector(-1.0, 0.0, 0.0));
+        assert_eq!(smooth_triangle.n3, vector(1.0, 0.0, 0.0));
+        assert_eq!(smooth_triangle.triangle.colors, Some([Color::new(1.0, 0.0, 0.0), Color::new(0.0, 1.0, 0.0), Color::new(0.0, 0.0, 1.0)]));
+    }
+
+    fn binary_ply(format: &str, to_bytes: fn(f32) -> [u8; 4], index_bytes: fn(i32) -> [u8; 4]) -> Vec<u8> {
+        let header = format!("ply
+format {} 1.0
+element vertex 3
+property float x
+property float y
+property float z
+element face 1
+property list uchar int vertex_indices
+end_header
+", format);
+        let mut data = header.into_bytes();
+        for value in [0.0, 1.0, 0.0, -1.0, 0.0, 0.0, 1.0, 0.0, 0.0] {
+            data.extend_from_slice(&to_bytes(value));
+        }
+        data.push(3);
+        for index in [0, 1, 2] {
+            data.extend_from_slice(&index_bytes(index));
+        }
+        data
+    }
+
+    #[test]
+    fn reading_a_binary_ply_test() {
+        let little_endian = binary_ply("binary_little_endian", f32::to_le_bytes, i32::to_le_bytes);
+        let big_endian = binary_ply("binary_big_endian", f32::to_be_bytes, i32::to_be_bytes);
+        for data in [little_endian, big_endian] {
+            let mut ply_reader = PlyReader::new(data.as_slice());
+            ply_reader.read().unwrap();
+            let triangle = &ply_reader.models["Default"].triangles[0];
+            assert_eq!(triangle.p1, point(0.0, 1.0, 0.0));
+            assert_eq!(triangle.p2, point(-1.0, 0.0, 0.0));
+            assert_eq!(triangle.p3, point(1.0, 0.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn a_truncated_binary_ply_is_an_error_test() {
+        let mut data = binary_ply("binary_little_endian", f32::to_le_bytes, i32::to_le_bytes);
+        data.truncate(data.len() - 2);
+        let mut ply_reader = PlyReader::new(data.as_slice());
+        let error = ply_reader.read().unwrap_err();
+        assert!(matches!(error.reason, MeshErrorReason::UnexpectedEnd));
+    }
+
+    #[test]
+    fn an_invalid_header_is_an_error_test() {
+        let str = "ply
+format ascii 1.0
+element vertex 3
+property quad x
+end_header
+";
+        let mut ply_reader = PlyReader::new(str.as_bytes());
+        let error = ply_reader.read().unwrap_err();
+        assert_eq!(error.line, 4);
+        assert_eq!(error.to_string(), "line 4: invalid header, unknown property type");
+    }
+
+    #[test]
+    fn a_face_with_an_unknown_vertex_is_an_error_test() {
+        let str = "ply
+format ascii 1.0
+element vertex 1
+property float x
+property float y
+property float z
+element face 1
+property list uchar int vertex_indices
+end_header
+0 0 0
+x 0 1 2
+";
+        let mut ply_reader = PlyReader::new(str.as_bytes());
+        let error = ply_reader.read().unwrap_err();
+        assert_eq!(error.line, 11);
+        assert!(matches!(error.reason, MeshErrorReason::InvalidNumber(ref value) if value == "x"));
+    }
+
+    fn triangle_ply(face: &str) -> String {
+        format!("ply
+format ascii 1.0
+element vertex 3
+property float x
+property float y
+property float z
+element face 1
+property list uchar int vertex_indices
+end_header
+0 0 0
+1 0 0
+0 1 0
+{}
+", face)
+    }
+
+    #[test]
+    fn a_negative_index_is_an_error_test() {
+        let str = triangle_ply("3 0 -1 2");
+        let mut ply_reader = PlyReader::new(str.as_bytes());
+        let error = ply_reader.read().unwrap_err();
+        assert_eq!(error.line, 13);
+        assert!(matches!(error.reason, MeshErrorReason::VertexOutOfRange(-1)));
+
+        let str = triangle_ply("3 0 1.5 2");
+        let mut ply_reader = PlyReader::new(str.as_bytes());
+        assert!(matches!(ply_reader.read().unwrap_err().reason, MeshErrorReason::InvalidNumber(ref value) if value == "1.5"));
+    }
+
+    #[test]
+    fn an_oversized_count_is_an_error_test() {
+        let str = triangle_ply("4000000000 0 1 2");
+        let mut ply_reader = PlyReader::new(str.as_bytes());
+        assert!(matches!(ply_reader.read().unwrap_err().reason, MeshErrorReason::MissingValue));
+
+        for count in ["-3", "nan", "2.5"] {
+            let str = triangle_ply(&format!("{} 0 1 2", count));
+            let mut ply_reader = PlyReader::new(str.as_bytes());
+            assert!(matches!(ply_reader.read().unwrap_err().reason, MeshErrorReason::InvalidNumber(_)));
+        }
+    }
+}
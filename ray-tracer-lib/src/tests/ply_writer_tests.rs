@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use crate::colors::Color;
+    use crate::core::tuple::{point, vector};
+    use crate::ply_reader::PlyReader;
+    use crate::ply_writer::PlyWriter;
+    use crate::shapes::smooth_triangle::SmoothTriangle;
+    use crate::shapes::smooth_triangle_model::SmoothTriangleModel;
+    use crate::shapes::triangle::Triangle;
+    use crate::shapes::triangle_model::TriangleModel;
+
+    fn colored_square() -> TriangleModel {
+        let red = Color::new(1.0, 0.0, 0.0);
+        let blue = Color::new(0.0, 0.0, 1.0);
+        let mut t1 = Triangle::new(point(0.0, 0.0, 0.0), point(1.0, 0.0, 0.0), point(1.0, 1.0, 0.0));
+        t1.colors = Some([red, red, blue]);
+        let mut t2 = Triangle::new(point(0.0, 0.0, 0.0), point(1.0, 1.0, 0.0), point(0.0, 1.0, 0.0));
+        t2.colors = Some([red, blue, blue]);
+        TriangleModel::new(vec![t1, t2])
+    }
+
+    #[test]
+    fn writing_an_ascii_ply_test() {
+        let mut ply_writer = PlyWriter::new(vec![]);
+        ply_writer.write_model(&colored_square()).unwrap();
+        let expected = "ply
+format ascii 1.0
+element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+element face 2
+property list uchar int vertex_indices
+end_header
+0 0 0 255 0 0
+1 0 0 255 0 0
+1 1 0 0 0 255
+0 1 0 0 0 255
+3 0 1 2
+3 0 2 3
+";
+        assert_eq!(String::from_utf8(ply_writer.target).unwrap(), expected);
+    }
+
+    #[test]
+    fn a_colored_model_is_read_back_test() {
+        let model = colored_square();
+        for binary in [false, true] {
+            let mut ply_writer = PlyWriter::new(vec![]);
+            ply_writer.binary = binary;
+            ply_writer.write_model(&model).unwrap();
+            let mut ply_reader = PlyReader::new(ply_writer.target.as_slice());
+            ply_reader.read().unwrap();
+            let triangles = &ply_reader.models["Default"].triangles;
+            assert_eq!(triangles.len(), 2);
+            for (read, written) in triangles.iter().zip(model.triangles.iter()) {
+                assert_eq!(read, written);
+                assert_eq!(read.colors, written.colors);
+            }
+        }
+    }
+
+    #[test]
+    fn a_smooth_model_is_read_back_test() {
+        let smooth_triangle = SmoothTriangle {
+            triangle: Triangle::new(point(0.0, 1.0, 0.0), point(-1.0, 0.0, 0.0), point(1.0, 0.0, 0.0)),
+            n1: vector(0.0, 1.0, 0.0), n2: vector(-1.0, 0.0, 0.0), n3: vector(1.0, 0.0, 0.0),
+        };
+        let mut ply_writer = PlyWriter::new(vec![]);
+        ply_writer.binary = true;
+        ply_writer.write_smooth_model(&SmoothTriangleModel::new(vec![smooth_triangle.clone()])).unwrap();
+        let mut ply_reader = PlyReader::new(ply_writer.target.as_slice());
+        ply_reader.read().unwrap();
+        let read = &ply_reader.smooth_models["Default"].smooth_triangles[0];
+        assert_eq!(read.triangle, smooth_triangle.triangle);
+        assert_eq!(read.n1, smooth_triangle.n1);
+        assert_eq!(read.n2, smooth_triangle.n2);
+        assert_eq!(read.n3, smooth_triangle.n3);
+    }
+}
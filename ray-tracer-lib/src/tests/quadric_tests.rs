@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use crate::core::math::{equals, Float};
+    use crate::core::ray::ray;
+    use crate::core::tuple::{point, Tuple, vector};
+    use crate::shapes::quadric::Quadric;
+
+    const SPHERE: [Float; 10] = [1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0];
+
+    fn intersecting_a_quadric(quadric: &Quadric, origin: Tuple, direction: Tuple, expected: Vec<Float>) {
+        let r = ray(origin, direction.normalize());
+        let mut xs = quadric.intersect(&r);
+        xs.sort_by(|a, b| a.total_cmp(b));
+        assert_eq!(xs.len(), expected.len());
+        for (t, expected_t) in xs.iter().zip(expected.iter()) {
+            assert!(equals(*t, *expected_t));
+        }
+    }
+
+    #[test]
+    fn intersecting_a_quadric_test() {
+        let sphere = Quadric::new(SPHERE);
+        intersecting_a_quadric(&sphere, point(0.0, 0.0, -5.0), vector(0.0, 0.0, 1.0), vec![4.0, 6.0]);
+        intersecting_a_quadric(&sphere, point(0.0, 2.0, -5.0), vector(0.0, 0.0, 1.0), vec![]);
+
+        let paraboloid = Quadric::new(Quadric::paraboloid());
+        intersecting_a_quadric(&paraboloid, point(0.0, 5.0, 0.0), vector(0.0, -1.0, 0.0), vec![5.0]);
+        intersecting_a_quadric(&paraboloid, point(-5.0, 1.0, 0.0), vector(1.0, 0.0, 0.0), vec![4.0, 6.0]);
+
+        let hyperboloid = Quadric::new(Quadric::hyperboloid_one_sheet());
+        intersecting_a_quadric(&hyperboloid, point(-5.0, 0.0, 0.0), vector(1.0, 0.0, 0.0), vec![4.0, 6.0]);
+        intersecting_a_quadric(&hyperboloid, point(0.0, -5.0, 0.0), vector(0.0, 1.0, 0.0), vec![]);
+
+        let hyperboloid = Quadric::new(Quadric::hyperboloid_two_sheets());
+        intersecting_a_quadric(&hyperboloid, point(0.0, -5.0, 0.0), vector(0.0, 1.0, 0.0), vec![4.0, 6.0]);
+    }
+
+    #[test]
+    fn intersecting_a_clipped_quadric_test() {
+        let paraboloid = Quadric::from(Quadric::paraboloid(), 0.0, 4.0, false);
+        intersecting_a_quadric(&paraboloid, point(-5.0, 1.0, 0.0), vector(1.0, 0.0, 0.0), vec![4.0, 6.0]);
+        intersecting_a_quadric(&paraboloid, point(-5.0, 5.0, 0.0), vector(1.0, 0.0, 0.0), vec![]);
+    }
+
+    #[test]
+    fn intersecting_the_caps_of_a_closed_quadric_test() {
+        // same cases as the closed cylinder
+        let cylinder = Quadric::from(Quadric::elliptic_cylinder(1.0, 1.0), 1.0, 2.0, true);
+        let count = |origin: Tuple, direction: Tuple| cylinder.intersect(&ray(origin, direction.normalize())).len();
+        assert_eq!(count(point(0.0, 3.0, 0.0), vector(0.0, -1.0, 0.0)), 2);
+        assert_eq!(count(point(0.0, 3.0, -2.0), vector(0.0, -1.0, 2.0)), 2);
+        assert_eq!(count(point(0.0, 4.0, -2.0), vector(0.0, -1.0, 1.0)), 2);
+        assert_eq!(count(point(0.0, 0.0, -2.0), vector(0.0, 1.0, 2.0)), 2);
+        assert_eq!(count(point(0.0, -1.0, -2.0), vector(0.0, 1.0, 1.0)), 2);
+    }
+
+    #[test]
+    fn the_caps_of_a_hyperboloid_of_two_sheets_are_discs_test() {
+        let hyperboloid = Quadric::from(Quadric::hyperboloid_two_sheets(), -3.0, 3.0, true);
+        // inside the sheets at the caps when x² + z² <= 3² - 1
+        intersecting_a_quadric(&hyperboloid, point(0.0, 5.0, 0.0), vector(0.0, -1.0, 0.0), vec![2.0, 4.0, 6.0, 8.0]);
+        let sheet = 5.0 - (1.0 + 2.5 * 2.5 as Float).sqrt();
+        intersecting_a_quadric(&hyperboloid, point(2.5, 5.0, 0.0), vector(0.0, -1.0, 0.0), vec![2.0, sheet, 10.0 - sheet, 8.0]);
+        intersecting_a_quadric(&hyperboloid, point(3.0, 5.0, 0.0), vector(0.0, -1.0, 0.0), vec![]);
+        assert_eq!(hyperboloid.normal_at(&point(0.0, 1.0, 0.0)).normalize(), vector(0.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn the_normal_on_a_quadric_test() {
+        let sphere = Quadric::new(SPHERE);
+        assert_eq!(sphere.normal_at(&point(1.0, 0.0, 0.0)).normalize(), vector(1.0, 0.0, 0.0));
+        assert_eq!(sphere.normal_at(&point(0.0, 0.0, -1.0)).normalize(), vector(0.0, 0.0, -1.0));
+
+        let paraboloid = Quadric::new(Quadric::paraboloid());
+        assert_eq!(paraboloid.normal_at(&point(0.0, 0.0, 0.0)).normalize(), vector(0.0, -1.0, 0.0));
+        assert_eq!(paraboloid.normal_at(&point(1.0, 1.0, 0.0)).normalize(), vector(2.0, -1.0, 0.0).normalize());
+    }
+
+    #[test]
+    fn the_normal_on_the_caps_of_a_closed_quadric_test() {
+        let cylinder = Quadric::from(Quadric::elliptic_cylinder(1.0, 1.0), 1.0, 2.0, true);
+        assert_eq!(cylinder.normal_at(&point(0.0, 1.0, 0.0)), vector(0.0, -1.0, 0.0));
+        assert_eq!(cylinder.normal_at(&point(0.5, 1.0, 0.0)), vector(0.0, -1.0, 0.0));
+        assert_eq!(cylinder.normal_at(&point(0.0, 2.0, 0.0)), vector(0.0, 1.0, 0.0));
+        assert_eq!(cylinder.normal_at(&point(0.0, 2.0, 0.5)), vector(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn a_bounded_quadric_has_finite_bounds_test() {
+        let bounds = Quadric::new(SPHERE).bounds();
+        assert_eq!(bounds.min, point(-1.0, -1.0, -1.0));
+        assert_eq!(bounds.max, point(1.0, 1.0, 1.0));
+
+        // (x - 1)²/4 + y² + (z + 2)²/9 - 1 = 0, written out, with the sign of every coefficient flipped
+        let ellipsoid = [0.25, 1.0, 1.0 / 9.0, 0.0, 0.0, 0.0, -0.5, 0.0, 4.0 / 9.0, 0.25 + 4.0 / 9.0 - 1.0];
+        for coefficients in [ellipsoid, ellipsoid.map(|coefficient| -coefficient)] {
+            let bounds = Quadric::new(coefficients).bounds();
+            assert_eq!(bounds.min, point(-1.0, -1.0, -5.0));
+            assert_eq!(bounds.max, point(3.0, 1.0, 1.0));
+        }
+
+        // a rotated ellipsoid: x² + y² + z² + xy - 1 = 0
+        let bounds = Quadric::new([1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0]).bounds();
+        let extent = (4.0 as Float / 3.0).sqrt();
+        assert_eq!(bounds.max, point(extent, extent, 1.0));
+
+        let clipped = Quadric::from(SPHERE, -0.5, 2.0, true).bounds();
+        assert_eq!(clipped.min, point(-1.0, -0.5, -1.0));
+        assert_eq!(clipped.max, point(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn an_unbounded_quadric_is_clipped_along_y_test() {
+        for coefficients in [Quadric::paraboloid(), Quadric::hyperboloid_one_sheet(), Quadric::elliptic_cylinder(1.0, 2.0)] {
+            let bounds = Quadric::from(coefficients, 0.0, 4.0, false).bounds();
+            assert_eq!((bounds.min.x, bounds.min.y, bounds.min.z), (-Float::INFINITY, 0.0, -Float::INFINITY));
+            assert_eq!((bounds.max.x, bounds.max.y, bounds.max.z), (Float::INFINITY, 4.0, Float::INFINITY));
+        }
+    }
+}
@@ -0,0 +1,105 @@
+#[cfg(test)]
+mod tests {
+    use crate::colors::Color;
+    use crate::core::tuple::point;
+    use crate::mesh::error::MeshErrorReason;
+    use crate::stl_reader::StlReader;
+
+    fn binary_stl(triangles: &[[[f32; 3]; 3]], attribute: u16) -> Vec<u8> {
+        let mut data = vec![0u8; 80];
+        data.extend_from_slice(&(triangles.len() as u32).to_le_bytes());
+        for triangle in triangles.iter() {
+            data.extend_from_slice(&[0u8; 12]);
+            for p in triangle.iter() {
+                for value in p.iter() {
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            data.extend_from_slice(&attribute.to_le_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn reading_an_ascii_stl_test() {
+        let str = "solid cube
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 1 1 0
+    endloop
+  endfacet
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 1 1 0
+      vertex 0 1 0
+    endloop
+  endfacet
+endsolid cube
+";
+        let mut stl_reader = StlReader::new(str.as_bytes());
+        stl_reader.read().unwrap();
+        let model = &stl_reader.models["cube"];
+        assert_eq!(model.triangles.len(), 2);
+        assert_eq!(model.triangles[1].p1, point(0.0, 0.0, 0.0));
+        assert_eq!(model.triangles[1].p2, point(1.0, 1.0, 0.0));
+        assert_eq!(model.triangles[1].p3, point(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn a_malformed_ascii_vertex_is_an_error_test() {
+        let str = "solid
+facet normal 0 0 1
+outer loop
+vertex 0 0 0
+vertex 1 x 0
+";
+        let mut stl_reader = StlReader::new(str.as_bytes());
+        let error = stl_reader.read().unwrap_err();
+        assert_eq!(error.line, 5);
+        assert!(matches!(error.reason, MeshErrorReason::InvalidNumber(ref value) if value == "x"));
+    }
+
+    #[test]
+    fn reading_a_binary_stl_test() {
+        let data = binary_stl(&[[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]], 0);
+        let mut stl_reader = StlReader::new(data.as_slice());
+        stl_reader.read().unwrap();
+        let model = &stl_reader.models["Default"];
+        assert_eq!(model.triangles.len(), 1);
+        assert_eq!(model.triangles[0].p2, point(1.0, 0.0, 0.0));
+        assert_eq!(model.triangles[0].p3, point(0.0, 1.0, 0.0));
+        assert_eq!(model.triangles[0].colors, None);
+    }
+
+    #[test]
+    fn a_binary_stl_may_start_with_solid_test() {
+        let mut data = binary_stl(&[[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]], 0);
+        data[..5].copy_from_slice(b"solid");
+        let mut stl_reader = StlReader::new(data.as_slice());
+        stl_reader.read().unwrap();
+        assert_eq!(stl_reader.models["Default"].triangles.len(), 1);
+    }
+
+    #[test]
+    fn the_face_colors_of_a_binary_stl_test() {
+        // red 31, green 0, blue 31
+        let attribute = 0x8000 | 31 << 10 | 31;
+        let data = binary_stl(&[[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]], attribute);
+        let mut stl_reader = StlReader::new(data.as_slice());
+        stl_reader.read().unwrap();
+        let magenta = Color::new(1.0, 0.0, 1.0);
+        assert_eq!(stl_reader.models["Default"].triangles[0].colors, Some([magenta; 3]));
+    }
+
+    #[test]
+    fn a_truncated_binary_stl_is_an_error_test() {
+        let mut data = binary_stl(&[[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]], 0);
+        data.truncate(100);
+        let mut stl_reader = StlReader::new(data.as_slice());
+        let error = stl_reader.read().unwrap_err();
+        assert!(matches!(error.reason, MeshErrorReason::UnexpectedEnd));
+    }
+}
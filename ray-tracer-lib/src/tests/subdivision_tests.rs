@@ -0,0 +1,228 @@
+#[cfg(test)]
+mod tests {
+    use crate::core::math::{equals, Float};
+    use crate::core::tuple::point;
+    use crate::mesh::error::MeshErrorReason;
+    use crate::mesh::half_edge::HalfEdgeMesh;
+    use crate::mesh::subdivision::Subdivision;
+    use crate::obj_reader::ObjReader;
+
+    const CUBE: &str = "
+v -1 -1 -1
+v 1 -1 -1
+v 1 1 -1
+v -1 1 -1
+v -1 -1 1
+v 1 -1 1
+v 1 1 1
+v -1 1 1
+f 1 4 3 2
+f 5 6 7 8
+f 1 2 6 5
+f 2 3 7 6
+f 3 4 8 7
+f 4 1 5 8
+";
+
+    fn cube() -> HalfEdgeMesh {
+        let mut obj_reader = ObjReader::new(CUBE.as_bytes());
+        obj_reader.read().unwrap();
+        HalfEdgeMesh::from_obj(&obj_reader).unwrap()
+    }
+
+    fn octahedron() -> HalfEdgeMesh {
+        let vertices = vec![point(1.0, 0.0, 0.0), point(-1.0, 0.0, 0.0), point(0.0, 1.0, 0.0),
+                            point(0.0, -1.0, 0.0), point(0.0, 0.0, 1.0), point(0.0, 0.0, -1.0)];
+        let faces = vec![vec![0, 2, 4], vec![2, 1, 4], vec![1, 3, 4], vec![3, 0, 4],
+                         vec![2, 0, 5], vec![1, 2, 5], vec![3, 1, 5], vec![0, 3, 5]];
+        HalfEdgeMesh::new(vertices, &faces).unwrap()
+    }
+
+    #[test]
+    fn the_half_edges_of_a_closed_mesh_all_have_twins_test() {
+        let mesh = cube();
+        assert_eq!(mesh.faces.len(), 6);
+        assert_eq!(mesh.half_edges.len(), 24);
+        assert_eq!(mesh.edges().count(), 12);
+        for (h, half_edge) in mesh.half_edges.iter().enumerate() {
+            let twin = half_edge.twin.unwrap();
+            assert_eq!(mesh.half_edges[twin].twin, Some(h));
+            assert_eq!(mesh.half_edges[twin].origin, mesh.destination(h));
+        }
+        assert_eq!(mesh.face_vertices(0), vec![0, 3, 2, 1]);
+    }
+
+    #[test]
+    fn a_mesh_needs_the_faces_of_the_reader_test() {
+        let obj_reader = ObjReader::new(CUBE.as_bytes());
+        assert!(matches!(HalfEdgeMesh::from_obj(&obj_reader), Err(MeshErrorReason::MissingValue)));
+    }
+
+    #[test]
+    fn a_cached_source_gives_the_same_mesh_test() {
+        let directory = std::env::temp_dir().join(format!("ray_tracer_subdivision_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let source = directory.join("cube.obj");
+        std::fs::write(&source, CUBE).unwrap();
+        let meshes: Vec<HalfEdgeMesh> = (0..2).map(|_| {
+            let mut obj_reader = ObjReader::open(&source).unwrap();
+            obj_reader.read().unwrap();
+            HalfEdgeMesh::from_obj(&obj_reader).unwrap()
+        }).collect();
+        assert_eq!(meshes[1].vertices, meshes[0].vertices);
+        assert_eq!(meshes[1].faces.len(), 6);
+        assert_eq!((0..6).map(|face| meshes[1].face_vertices(face)).collect::<Vec<_>>(),
+                   (0..6).map(|face| meshes[0].face_vertices(face)).collect::<Vec<_>>());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn the_boundary_edges_are_hard_creases_test() {
+        let mesh = HalfEdgeMesh::new(vec![point(0.0, 0.0, 0.0), point(1.0, 0.0, 0.0), point(0.0, 1.0, 0.0)], &[vec![0, 1, 2]]).unwrap();
+        assert!(mesh.half_edges.iter().all(|half_edge| half_edge.twin.is_none()));
+        assert_eq!(mesh.sharpness(0), Float::INFINITY);
+    }
+
+    #[test]
+    fn an_edge_in_three_faces_is_an_error_test() {
+        let vertices = vec![point(0.0, 0.0, 0.0), point(1.0, 0.0, 0.0), point(0.0, 1.0, 0.0), point(0.0, -1.0, 0.0), point(0.0, 0.0, 1.0)];
+        let faces = vec![vec![0, 1, 2], vec![1, 0, 3], vec![0, 1, 4]];
+        let error = HalfEdgeMesh::new(vertices, &faces).unwrap_err();
+        assert!(matches!(error, MeshErrorReason::NonManifoldEdge(0, 1)));
+    }
+
+    #[test]
+    fn welding_the_triangles_of_a_model_test() {
+        let mut obj_reader = ObjReader::new(CUBE.as_bytes());
+        obj_reader.read().unwrap();
+        let mesh = HalfEdgeMesh::from_triangles(&obj_reader.models["Default"].triangles).unwrap();
+        assert_eq!(mesh.vertices.len(), 8);
+        assert_eq!(mesh.faces.len(), 12);
+        assert!(mesh.half_edges.iter().all(|half_edge| half_edge.twin.is_some()));
+    }
+
+    #[test]
+    fn loop_subdivision_of_an_octahedron_test() {
+        let mesh = octahedron().subdivide(Subdivision::Loop, 1).unwrap();
+        assert_eq!(mesh.faces.len(), 32);
+        assert_eq!(mesh.vertices.len(), 6 + 12);
+        // beta is 31/256 for the vertices of valence 4
+        assert_eq!(mesh.vertices[0], point(0.515625, 0.0, 0.0));
+        assert!(mesh.vertices.contains(&point(0.375, 0.375, 0.0)));
+    }
+
+    #[test]
+    fn loop_subdivision_of_a_single_triangle_follows_its_boundary_test() {
+        let mesh = HalfEdgeMesh::new(vec![point(0.0, 0.0, 0.0), point(8.0, 0.0, 0.0), point(0.0, 8.0, 0.0)], &[vec![0, 1, 2]]).unwrap();
+        let mesh = mesh.subdivide(Subdivision::Loop, 1).unwrap();
+        assert_eq!(mesh.faces.len(), 4);
+        assert_eq!(mesh.vertices.len(), 6);
+        assert_eq!(mesh.vertices[0], point(1.0, 1.0, 0.0));
+        assert!(mesh.vertices.contains(&point(4.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn the_fan_of_a_polygon_avoids_the_existing_edges_test() {
+        // the diagonal from 0 to 2 is an edge of the triangle
+        let vertices = vec![point(0.0, 0.0, 0.0), point(1.0, 0.0, 0.0), point(1.0, 1.0, 0.0), point(0.0, 1.0, 0.0), point(1.0, -1.0, 1.0)];
+        let mesh = HalfEdgeMesh::new(vertices, &[vec![0, 1, 2, 3], vec![0, 2, 4]]).unwrap();
+        let triangulated = mesh.triangulated().unwrap();
+        assert_eq!(triangulated.face_vertices(0), vec![1, 2, 3]);
+        assert_eq!(triangulated.face_vertices(1), vec![1, 3, 0]);
+        assert_eq!(mesh.subdivide(Subdivision::Loop, 1).unwrap().faces.len(), 12);
+
+        // every diagonal of the quad is taken
+        let vertices = vec![point(0.0, 0.0, 0.0), point(1.0, 0.0, 0.0), point(1.0, 1.0, 0.0), point(0.0, 1.0, 0.0), point(1.0, -1.0, 1.0), point(2.0, 2.0, 1.0)];
+        let mesh = HalfEdgeMesh::new(vertices, &[vec![0, 1, 2, 3], vec![0, 2, 4], vec![1, 5, 3]]).unwrap();
+        assert!(matches!(mesh.subdivide(Subdivision::Loop, 1), Err(MeshErrorReason::NonManifoldEdge(_, _))));
+    }
+
+    #[test]
+    fn loop_subdivision_triangulates_the_polygons_test() {
+        let mesh = cube().subdivide(Subdivision::Loop, 2).unwrap();
+        assert_eq!(mesh.faces.len(), 12 * 16);
+        assert!(mesh.is_triangle_mesh());
+    }
+
+    #[test]
+    fn catmull_clark_subdivision_of_a_cube_test() {
+        let mesh = cube().subdivide(Subdivision::CatmullClark, 1).unwrap();
+        assert_eq!(mesh.faces.len(), 24);
+        assert_eq!(mesh.vertices.len(), 8 + 12 + 6);
+        let third = 5.0 / 9.0;
+        assert_eq!(mesh.vertices[6], point(third, third, third));
+        assert!(mesh.vertices.contains(&point(0.75, 0.75, 0.0)));
+        assert!(mesh.vertices.contains(&point(0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn several_catmull_clark_levels_test() {
+        let mesh = cube().subdivide(Subdivision::CatmullClark, 3).unwrap();
+        assert_eq!(mesh.faces.len(), 6 * 64);
+        assert!((0..mesh.faces.len()).all(|face| mesh.face_vertices(face).len() == 4));
+        // the limit surface is inside the cube
+        assert!(mesh.vertices.iter().all(|p| p.x.abs() < 1.0 && p.y.abs() < 1.0 && p.z.abs() < 1.0));
+    }
+
+    #[test]
+    fn hard_creases_keep_the_cube_test() {
+        let mut mesh = cube();
+        for h in mesh.edges().collect::<Vec<usize>>() {
+            let (a, b) = (mesh.half_edges[h].origin, mesh.destination(h));
+            mesh.set_crease(a, b, Float::INFINITY);
+        }
+        let mesh = mesh.subdivide(Subdivision::CatmullClark, 2).unwrap();
+        assert_eq!(mesh.vertices[6], point(1.0, 1.0, 1.0));
+        assert!(mesh.vertices.iter().all(|p| equals(p.x.abs().max(p.y.abs()).max(p.z.abs()), 1.0)));
+    }
+
+    #[test]
+    fn a_crease_edge_point_is_the_middle_of_the_edge_test() {
+        let mut mesh = octahedron();
+        mesh.set_crease(0, 2, Float::INFINITY);
+        let sharp = mesh.subdivide(Subdivision::Loop, 1).unwrap();
+        assert!(sharp.vertices.contains(&point(0.5, 0.5, 0.0)));
+
+        // halfway between the smooth and the sharp rules
+        mesh.set_crease(0, 2, 0.5);
+        let semi_sharp = mesh.subdivide(Subdivision::Loop, 1).unwrap();
+        assert!(semi_sharp.vertices.contains(&point(0.4375, 0.4375, 0.0)));
+        assert!(semi_sharp.creases.is_empty());
+    }
+
+    #[test]
+    fn the_sharpness_of_the_creases_decreases_with_the_levels_test() {
+        let mut mesh = octahedron();
+        mesh.set_crease(0, 2, 2.5);
+        let mesh = mesh.subdivide(Subdivision::Loop, 1).unwrap();
+        assert_eq!(mesh.creases.len(), 2);
+        assert!(mesh.creases.values().all(|sharpness| equals(*sharpness, 1.5)));
+    }
+
+    #[test]
+    fn the_smooth_model_of_a_subdivided_mesh_test() {
+        let model = octahedron().subdivide(Subdivision::Loop, 2).unwrap().smooth_model();
+        assert_eq!(model.smooth_triangles.len(), 8 * 16);
+        for smooth_triangle in model.smooth_triangles.iter() {
+            for n in [smooth_triangle.n1, smooth_triangle.n2, smooth_triangle.n3] {
+                assert!(n.dot(&smooth_triangle.triangle.normal) > 0.5);
+            }
+        }
+    }
+
+    #[test]
+    fn the_normals_are_not_smoothed_over_hard_creases_test() {
+        let mut mesh = cube();
+        for h in mesh.edges().collect::<Vec<usize>>() {
+            let (a, b) = (mesh.half_edges[h].origin, mesh.destination(h));
+            mesh.set_crease(a, b, Float::INFINITY);
+        }
+        let model = mesh.subdivide(Subdivision::CatmullClark, 1).unwrap().smooth_model();
+        assert_eq!(model.smooth_triangles.len(), 48);
+        for smooth_triangle in model.smooth_triangles.iter() {
+            assert_eq!(smooth_triangle.n1, smooth_triangle.triangle.normal);
+            assert_eq!(smooth_triangle.n2, smooth_triangle.triangle.normal);
+            assert_eq!(smooth_triangle.n3, smooth_triangle.triangle.normal);
+        }
+    }
+}
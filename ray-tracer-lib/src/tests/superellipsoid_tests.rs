@@ -0,0 +1,85 @@
+#[cfg(test)]
+mod tests {
+    use crate::core::math::{equals, Float};
+    use crate::core::ray::ray;
+    use crate::core::tuple::{point, Tuple, vector};
+    use crate::shapes::superellipsoid::Superellipsoid;
+
+    fn intersecting_a_superellipsoid(shape: &Superellipsoid, origin: Tuple, t0: Float, t1: Float) {
+        let r = ray(origin, vector(0.0, 0.0, 1.0));
+        let xs = shape.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert!(equals(xs[0], t0));
+        assert!(equals(xs[1], t1));
+    }
+
+    #[test]
+    fn intersecting_a_superellipsoid_test() {
+        let sphere = Superellipsoid::new(1.0, 1.0);
+        intersecting_a_superellipsoid(&sphere, point(0.0, 0.0, -5.0), 4.0, 6.0);
+        intersecting_a_superellipsoid(&sphere, point(0.6, 0.0, -5.0), 4.2, 5.8);
+
+        let octahedron = Superellipsoid::new(2.0, 2.0);
+        intersecting_a_superellipsoid(&octahedron, point(0.25, 0.25, -5.0), 4.5, 5.5);
+    }
+
+    #[test]
+    fn a_rounded_cube_is_almost_a_cube_test() {
+        let rounded_cube = Superellipsoid::new(0.1, 0.1);
+        let r = ray(point(0.5, 0.5, -5.0), vector(0.0, 0.0, 1.0));
+        let xs = rounded_cube.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert!((xs[0] - 4.0).abs() < 0.01);
+        assert!((xs[1] - 6.0).abs() < 0.01);
+        assert!(equals(rounded_cube.inside_outside(&r.position(xs[0])), 0.0));
+    }
+
+    #[test]
+    fn a_ray_grazing_an_edge_hits_a_rounded_cube_test() {
+        // a ray along the tangent of the rounded vertical edge, just inside it:
+        // it is inside for a tiny part of its path in the box
+        let rounded_cube = Superellipsoid::new(0.1, 0.1);
+        let x: Float = 0.99;
+        let z = (1.0 - x.powi(20)).powf(0.05);
+        let normal = vector(x.powi(19), 0.0, z.powi(19)).normalize();
+        let direction = vector(normal.z, 0.0, -normal.x);
+        let r = ray(point(x, 0.0, z) - normal * 1e-7 - direction * 5.0, direction);
+        let xs = rounded_cube.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert!(xs[0] < 5.0 && 5.0 < xs[1] && xs[1] - xs[0] < 0.01);
+        for t in xs {
+            assert!(equals(rounded_cube.inside_outside(&r.position(t)), 0.0));
+        }
+    }
+
+    #[test]
+    fn a_star_is_sampled_along_the_whole_path_test() {
+        let star = Superellipsoid::new(3.0, 3.0);
+        let r = ray(point(0.0, 0.0, -5.0), vector(0.0, 0.0, 1.0));
+        let xs = star.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert!(equals(xs[0], 4.0) && equals(xs[1], 6.0));
+    }
+
+    #[test]
+    fn a_ray_misses_a_superellipsoid_test() {
+        let sphere = Superellipsoid::new(1.0, 1.0);
+        let r = ray(point(0.8, 0.8, -5.0), vector(0.0, 0.0, 1.0));
+        assert!(sphere.intersect(&r).is_empty());
+
+        let octahedron = Superellipsoid::new(2.0, 2.0);
+        let r = ray(point(0.6, 0.6, -5.0), vector(0.0, 0.0, 1.0));
+        assert!(octahedron.intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn the_normal_on_a_superellipsoid_test() {
+        let sphere = Superellipsoid::new(1.0, 1.0);
+        assert_eq!(sphere.normal_at(point(0.0, 1.0, 0.0)), vector(0.0, 1.0, 0.0));
+        assert_eq!(sphere.normal_at(point(0.6, 0.0, -0.8)), vector(0.6, 0.0, -0.8));
+
+        let octahedron = Superellipsoid::new(2.0, 2.0);
+        let n = octahedron.normal_at(point(0.25, 0.25, 0.5));
+        assert_eq!(n, vector(1.0, 1.0, 1.0).normalize());
+    }
+}
@@ -0,0 +1,83 @@
+#[cfg(test)]
+mod tests {
+    use crate::colors::Color;
+    use crate::core::math::{equals, Float, INFINITY};
+    use crate::tone_mapping::{luminance, srgb_decode, srgb_encode, ToneMapper, ToneMapping};
+
+    fn mapper(operator: ToneMapping) -> ToneMapper {
+        ToneMapper { exposure: 0.0, operator, srgb: false }
+    }
+
+    #[test]
+    fn the_default_tone_mapping_clamps_each_channel_test() {
+        let tone_mapper = ToneMapper::new();
+        assert_eq!(tone_mapper.map(Color::new(1.5, 0.5, -0.5)), Color::new(1.0, 0.5, 0.0));
+        assert_eq!(tone_mapper.to_rgb8(Color::new(1.5, 0.5, -0.5)), [255, 128, 0]);
+    }
+
+    #[test]
+    fn the_exposure_is_in_stops_test() {
+        let mut tone_mapper = ToneMapper::new();
+        tone_mapper.exposure = 1.0;
+        assert_eq!(tone_mapper.map(Color::new(0.25, 0.1, 0.0)), Color::new(0.5, 0.2, 0.0));
+        tone_mapper.exposure = -2.0;
+        assert_eq!(tone_mapper.map(Color::new(2.0, 4.0, 0.0)), Color::new(0.5, 1.0, 0.0));
+    }
+
+    #[test]
+    fn reinhard_keeps_the_hue_test() {
+        let tone_mapper = mapper(ToneMapping::Reinhard { white: INFINITY });
+        assert_eq!(tone_mapper.map(Color::white()), Color::new(0.5, 0.5, 0.5));
+        let c = tone_mapper.map(Color::new(20.0, 10.0, 0.0));
+        assert!(equals(c.r, 2.0 * c.g) && c.b == 0.0 && c.r <= 1.0);
+        assert_eq!(tone_mapper.map(Color::black()), Color::black());
+    }
+
+    #[test]
+    fn reinhard_maps_the_white_to_one_test() {
+        let tone_mapper = mapper(ToneMapping::Reinhard { white: 4.0 });
+        assert_eq!(tone_mapper.map(Color::new(4.0, 4.0, 4.0)), Color::white());
+        assert!(luminance(tone_mapper.map(Color::new(2.0, 2.0, 2.0))) < 1.0);
+    }
+
+    #[test]
+    fn filmic_and_aces_curves_are_increasing_to_white_test() {
+        for operator in [ToneMapping::Filmic, ToneMapping::Aces] {
+            let tone_mapper = mapper(operator);
+            assert_eq!(tone_mapper.map(Color::black()), Color::black());
+            let mut previous = 0.0;
+            for i in 1..50 {
+                let value = tone_mapper.map(Color::new(i as Float * 0.1, 0.0, 0.0)).r;
+                assert!(value > previous && value <= 1.0);
+                previous = value;
+            }
+        }
+        assert_eq!(mapper(ToneMapping::Filmic).map(Color::new(5.6, 5.6, 5.6)), Color::white());
+        assert_eq!(mapper(ToneMapping::Aces).map(Color::new(1000.0, 1000.0, 1000.0)), Color::white());
+    }
+
+    #[test]
+    fn the_srgb_curve_test() {
+        assert!(equals(srgb_encode(0.5), 0.7354));
+        assert!(equals(srgb_encode(1.0), 1.0));
+        assert!(equals(srgb_encode(0.002), 0.02584));
+        for i in 0..=20 {
+            let value = i as Float / 20.0;
+            assert!(equals(srgb_decode(srgb_encode(value)), value));
+        }
+        let mut tone_mapper = ToneMapper::new();
+        tone_mapper.srgb = true;
+        assert_eq!(tone_mapper.to_rgb8(Color::new(0.5, 0.0, 2.0)), [188, 0, 255]);
+    }
+
+    #[test]
+    fn parsing_the_tone_mapping_test() {
+        assert_eq!("clamp".parse::<ToneMapping>(), Ok(ToneMapping::Clamp));
+        assert_eq!("reinhard".parse::<ToneMapping>(), Ok(ToneMapping::Reinhard { white: INFINITY }));
+        assert_eq!("reinhard=2.5".parse::<ToneMapping>(), Ok(ToneMapping::Reinhard { white: 2.5 }));
+        assert_eq!("filmic".parse::<ToneMapping>(), Ok(ToneMapping::Filmic));
+        assert_eq!("aces".parse::<ToneMapping>(), Ok(ToneMapping::Aces));
+        assert!("reinhard=-1".parse::<ToneMapping>().is_err());
+        assert!("gamma".parse::<ToneMapping>().is_err());
+    }
+}
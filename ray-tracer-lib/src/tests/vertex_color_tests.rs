@@ -0,0 +1,103 @@
+#[cfg(test)]
+mod tests {
+    use crate::colors::Color;
+    use crate::core::ray::ray;
+    use crate::core::tuple::{point, vector};
+    use crate::lights::point_light::PointLight;
+    use crate::material::Material;
+    use crate::obj_reader::ObjReader;
+    use crate::object::{build_sphere, Object};
+    use crate::patterns::pattern::Pattern;
+    use crate::shapes::triangle::Triangle;
+    use crate::world::World;
+
+    const COLORED_TRIANGLE: &str = "
+v 0 1 0 1 0 0
+v -1 0 0 0 1 0
+v 1 0 0 0 0 1
+f 1 2 3
+";
+
+    fn colored_triangle() -> Triangle {
+        let mut triangle = Triangle::new(point(0.0, 1.0, 0.0), point(-1.0, 0.0, 0.0), point(1.0, 0.0, 0.0));
+        triangle.colors = Some([Color::new(1.0, 0.0, 0.0), Color::new(0.0, 1.0, 0.0), Color::new(0.0, 0.0, 1.0)]);
+        triangle
+    }
+
+    #[test]
+    fn the_vertex_colors_are_interpolated_with_the_barycentric_coordinates_test() {
+        let triangle = colored_triangle();
+        assert_eq!(triangle.vertex_color(0.0, 0.0), Some(Color::new(1.0, 0.0, 0.0)));
+        assert_eq!(triangle.vertex_color(1.0, 0.0), Some(Color::new(0.0, 1.0, 0.0)));
+        assert_eq!(triangle.vertex_color(0.25, 0.5), Some(Color::new(0.25, 0.25, 0.5)));
+    }
+
+    #[test]
+    fn a_triangle_without_colors_has_no_vertex_color_test() {
+        let triangle = Triangle::new(point(0.0, 1.0, 0.0), point(-1.0, 0.0, 0.0), point(1.0, 0.0, 0.0));
+        assert_eq!(triangle.vertex_color(0.25, 0.5), None);
+    }
+
+    #[test]
+    fn reading_obj_vertex_colors_test() {
+        let mut obj_reader = ObjReader::new(COLORED_TRIANGLE.as_bytes());
+        obj_reader.read().unwrap();
+        assert_eq!(obj_reader.vertex_colors[1], Some(Color::new(0.0, 1.0, 0.0)));
+        assert_eq!(obj_reader.models["Default"].triangles[0].colors, colored_triangle().colors);
+    }
+
+    #[test]
+    fn a_face_with_an_uncolored_vertex_has_no_colors_test() {
+        let str = "
+v 0 1 0 1 0 0
+v -1 0 0
+v 1 0 0 0 0 1
+f 1 2 3
+";
+        let mut obj_reader = ObjReader::new(str.as_bytes());
+        obj_reader.read().unwrap();
+        assert_eq!(obj_reader.vertex_colors[1], None);
+        assert_eq!(obj_reader.models["Default"].triangles[0].colors, None);
+    }
+
+    #[test]
+    fn the_vertex_color_is_carried_into_the_intersection_test() {
+        let mut obj_reader = ObjReader::new(COLORED_TRIANGLE.as_bytes());
+        obj_reader.read().unwrap();
+        let model = Object::new_triangle_group(obj_reader.models["Default"].clone());
+
+        let r = ray(point(-0.2, 0.3, -2.0), vector(0.0, 0.0, 1.0));
+        let xs = model.intersect(&r);
+        assert_eq!(xs.count(), 1);
+        assert_eq!(xs[0].vertex_color, Some(Color::new(0.3, 0.45, 0.25)));
+    }
+
+    #[test]
+    fn a_vertex_color_pattern_shades_with_the_vertex_colors_test() {
+        let mut obj_reader = ObjReader::new(COLORED_TRIANGLE.as_bytes());
+        obj_reader.read().unwrap();
+        let mut model = Object::new_triangle_group(obj_reader.models["Default"].clone());
+        let mut material = Material::new();
+        material.pattern = Pattern::vertex_color();
+        material.ambient = 1.0;
+        material.diffuse = 0.0;
+        material.specular = 0.0;
+        model.set_material(material);
+        let mut world = World::new();
+        world.set_objects(vec![model]);
+        world.set_lights(vec![PointLight::new(point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0))]);
+
+        let r = ray(point(-0.2, 0.3, -2.0), vector(0.0, 0.0, 1.0));
+        assert_eq!(world.color_at(&r, 0), Color::new(0.3, 0.45, 0.25));
+    }
+
+    #[test]
+    fn a_vertex_color_pattern_falls_back_to_the_material_color_test() {
+        let mut sphere = build_sphere();
+        let mut material = Material::new();
+        material.pattern = Pattern::vertex_color();
+        material.color = Color::new(0.5, 0.25, 1.0);
+        sphere.set_material(material.clone());
+        assert_eq!(material.pattern.pattern_at_object(&sphere, point(0.0, 0.0, 1.0)), Color::new(0.5, 0.25, 1.0));
+    }
+}
@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use crate::colors::Color;
+    use crate::core::math::equals;
+    use crate::core::ray::ray;
+    use crate::core::tuple::{point, vector};
+    use crate::material::Material;
+    use crate::object::Object;
+    use crate::shapes::shape::Shape;
+    use crate::shapes::voxels::Voxels;
+
+    #[test]
+    fn setting_and_getting_voxels_test() {
+        let mut dense = Voxels::dense(3, 3, 3);
+        let mut sparse = Voxels::sparse(3, 3, 3);
+        dense.set(1, 2, 0, Some(4));
+        sparse.set(1, 2, 0, Some(4));
+        assert_eq!(dense.get(1, 2, 0), Some(4));
+        assert_eq!(sparse.get(1, 2, 0), Some(4));
+        assert_eq!(dense.get(0, 0, 0), None);
+        assert_eq!(sparse.get(0, 0, 0), None);
+        assert_eq!(dense.get(5, 0, 0), None);
+
+        sparse.set(1, 2, 0, None);
+        assert_eq!(sparse.get(1, 2, 0), None);
+    }
+
+    #[test]
+    fn a_ray_misses_an_empty_grid_test() {
+        let voxels = Voxels::dense(4, 4, 4);
+        let r = ray(point(0.5, 0.5, -5.0), vector(0.0, 0.0, 1.0));
+        assert!(voxels.intersect(&r).is_empty());
+    }
+
+    #[test]
+    fn a_ray_intersects_a_run_of_voxels_test() {
+        let mut voxels = Voxels::dense(4, 4, 4);
+        voxels.set(0, 0, 1, Some(0));
+        voxels.set(0, 0, 2, Some(1));
+        let r = ray(point(0.5, 0.5, -5.0), vector(0.0, 0.0, 1.0));
+        let xs = voxels.intersect_with_index(&r);
+        assert_eq!(xs.len(), 2);
+        assert!(equals(xs[0].0, 6.0));
+        assert_eq!(xs[0].1, 0);
+        assert!(equals(xs[1].0, 8.0));
+        assert_eq!(xs[1].1, 1);
+    }
+
+    #[test]
+    fn a_ray_intersects_separated_voxels_test() {
+        let mut voxels = Voxels::sparse(4, 4, 4);
+        voxels.set(3, 0, 0, Some(0));
+        voxels.set(1, 0, 0, Some(0));
+        let r = ray(point(10.0, 0.5, 0.5), vector(-1.0, 0.0, 0.0));
+        let xs = voxels.intersect(&r);
+        assert_eq!(xs.len(), 4);
+        assert!(equals(xs[0], 6.0));
+        assert!(equals(xs[1], 7.0));
+        assert!(equals(xs[2], 8.0));
+        assert!(equals(xs[3], 9.0));
+    }
+
+    #[test]
+    fn a_diagonal_ray_walks_through_the_grid_test() {
+        let mut voxels = Voxels::dense(3, 3, 3);
+        voxels.set(2, 2, 0, Some(0));
+        let r = ray(point(-0.5, -0.5, 0.5), vector(1.0, 1.0, 0.0).normalize());
+        let xs = voxels.intersect(&r);
+        assert_eq!(xs.len(), 2);
+        assert!(equals(xs[0], 2.5 * 2.0_f64.sqrt()));
+        assert!(equals(xs[1], 3.5 * 2.0_f64.sqrt()));
+    }
+
+    #[test]
+    fn the_normal_is_the_face_of_the_entered_voxel_test() {
+        let mut voxels = Voxels::dense(3, 3, 3);
+        voxels.set(1, 1, 1, Some(0));
+        assert_eq!(voxels.normal_at(point(1.5, 2.0, 1.5)), vector(0.0, 1.0, 0.0));
+        assert_eq!(voxels.normal_at(point(1.5, 1.0, 1.5)), vector(0.0, -1.0, 0.0));
+        assert_eq!(voxels.normal_at(point(1.0, 1.5, 1.5)), vector(-1.0, 0.0, 0.0));
+        assert_eq!(voxels.normal_at(point(2.0, 1.5, 1.5)), vector(1.0, 0.0, 0.0));
+        assert_eq!(voxels.normal_at(point(1.5, 1.5, 1.0)), vector(0.0, 0.0, -1.0));
+        assert_eq!(voxels.normal_at(point(1.5, 1.5, 2.0)), vector(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn intersections_use_the_voxel_material_test() {
+        let mut voxels = Voxels::dense(2, 1, 1);
+        let mut red = Material::new();
+        red.color = Color::red();
+        let index = voxels.add_material(red);
+        voxels.set(1, 0, 0, Some(index));
+        let object = Object::new(Shape::Voxels(voxels));
+        let r = ray(point(5.0, 0.5, 0.5), vector(-1.0, 0.0, 0.0));
+        let xs = object.intersect(&r);
+        assert_eq!(xs.count(), 2);
+        assert_eq!(xs[0].object.material().color, Color::red());
+        assert!(equals(xs[0].t, 3.0));
+        assert!(equals(xs[1].t, 4.0));
+    }
+}
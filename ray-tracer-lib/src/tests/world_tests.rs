@@ -8,6 +8,7 @@ mod tests {
     use crate::material::Material;
     use crate::core::math::{SQRT2};
     use crate::object::{build_plane, build_sphere};
+    use crate::patterns::normal_map::NormalMap;
     use crate::patterns::pattern::{Pattern};
     use crate::core::ray::ray;
     use crate::core::transform::{scaling, translation};
@@ -28,6 +29,7 @@ mod tests {
             transparency: 0.0,
             refractive_index: 1.0,
             pattern: Pattern::new(),
+            normal_map: NormalMap::None,
         });
 
         let mut sphere_2 = build_sphere();
@@ -101,7 +103,7 @@ mod tests {
         let inner = &mut w.objects[1];
         let mut inner_material = inner.material().clone();
         inner_material.ambient = 1.0;
-        inner.set_material(inner_material);
+        inner.set_material(inner_material.clone());
 
         let r = ray(point(0.0, 0.0, 0.75), vector(0.0, 0.0, -1.0));
 
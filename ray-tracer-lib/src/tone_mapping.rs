@@ -0,0 +1,137 @@
+use std::str::FromStr;
+use crate::colors::Color;
+use crate::core::math::{Float, INFINITY};
+
+// How the colors of a render, unbounded and linear, are brought into [0, 1] for 8-bit images.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ToneMapping {
+    // each channel is clipped, the bright colors lose their hue
+    Clamp,
+    // on the luminance, the colors as bright as white are mapped to 1 (none with an infinite white)
+    Reinhard { white: Float },
+    // the filmic curve of Uncharted 2 (John Hable) on each channel
+    Filmic,
+    // the fit of the ACES filmic curve by Krzysztof Narkowicz on each channel
+    Aces,
+}
+
+impl FromStr for ToneMapping {
+    type Err = String;
+
+    // clamp, reinhard, reinhard=<white>, filmic or aces
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.split_once('=') {
+            Some(("reinhard", white)) => white.parse::<Float>().ok().filter(|white| *white > 0.0)
+                .map(|white| ToneMapping::Reinhard { white })
+                .ok_or_else(|| format!("invalid white '{}'", white)),
+            None if value == "clamp" => Ok(ToneMapping::Clamp),
+            None if value == "reinhard" => Ok(ToneMapping::Reinhard { white: INFINITY }),
+            None if value == "filmic" => Ok(ToneMapping::Filmic),
+            None if value == "aces" => Ok(ToneMapping::Aces),
+            _ => Err(format!("unknown tone mapping '{}'", value)),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ToneMapper {
+    // in stops, each one doubles the light
+    pub exposure: Float,
+    pub operator: ToneMapping,
+    // the mapped colors are encoded with the sRGB curve, otherwise they are written as they are
+    pub srgb: bool,
+}
+
+impl Default for ToneMapper {
+    fn default() -> Self {
+        ToneMapper::new()
+    }
+}
+
+impl ToneMapper {
+    // the colors are clamped and written linearly, as before tone mapping
+    pub fn new() -> Self {
+        ToneMapper { exposure: 0.0, operator: ToneMapping::Clamp, srgb: false }
+    }
+
+    // the displayed color, in [0, 1]
+    pub fn map(&self, color: Color) -> Color {
+        let color = color * (2.0 as Float).powf(self.exposure);
+        let color = Color::new(color.r.max(0.0), color.g.max(0.0), color.b.max(0.0));
+        let mapped = match self.operator {
+            ToneMapping::Clamp => color,
+            ToneMapping::Reinhard { white } => {
+                let l = luminance(color);
+                if l <= 0.0 {
+                    color
+                } else {
+                    color * (reinhard(l, white) / l)
+                }
+            }
+            ToneMapping::Filmic => {
+                let scale = 1.0 / hable(FILMIC_WHITE);
+                Color::new(hable(color.r * 2.0) * scale, hable(color.g * 2.0) * scale, hable(color.b * 2.0) * scale)
+            }
+            ToneMapping::Aces => Color::new(aces(color.r), aces(color.g), aces(color.b)),
+        };
+        let mapped = match self.operator {
+            ToneMapping::Clamp => Color::new(mapped.r.min(1.0), mapped.g.min(1.0), mapped.b.min(1.0)),
+            // the saturated colors still above 1 are darkened as a whole to keep their hue
+            _ => {
+                let max = mapped.r.max(mapped.g).max(mapped.b);
+                if max > 1.0 { mapped * (1.0 / max) } else { mapped }
+            }
+        };
+        if self.srgb {
+            Color::new(srgb_encode(mapped.r), srgb_encode(mapped.g), srgb_encode(mapped.b))
+        } else {
+            mapped
+        }
+    }
+
+    pub fn to_rgb8(&self, color: Color) -> [u8; 3] {
+        let c = self.map(color);
+        [to_byte(c.r), to_byte(c.g), to_byte(c.b)]
+    }
+}
+
+fn to_byte(value: Float) -> u8 {
+    (255.0 * value).round().clamp(0.0, 255.0) as u8
+}
+
+pub fn luminance(color: Color) -> Float {
+    0.2126 * color.r + 0.7152 * color.g + 0.0722 * color.b
+}
+
+fn reinhard(l: Float, white: Float) -> Float {
+    l * (1.0 + l / (white * white)) / (1.0 + l)
+}
+
+// the linear input mapped to the white of the filmic curve
+const FILMIC_WHITE: Float = 11.2;
+
+fn hable(x: Float) -> Float {
+    let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+    (x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f) - e / f
+}
+
+fn aces(x: Float) -> Float {
+    let x = x * 0.6;
+    ((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14)).clamp(0.0, 1.0)
+}
+
+pub fn srgb_encode(value: Float) -> Float {
+    if value <= 0.0031308 {
+        12.92 * value
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+pub fn srgb_decode(value: Float) -> Float {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
@@ -1,4 +1,7 @@
+use crate::aov::SurfaceSample;
+use crate::background::Background;
 use crate::colors::Color;
+use crate::core::math::Float;
 use crate::core::comps::{Comps, prepare_computations};
 use crate::core::intersections::{Intersections, intersections};
 use crate::lights::light::Light;
@@ -8,13 +11,14 @@ use crate::core::tuple::Tuple;
 
 pub struct World {
     pub objects : Vec<Object>,
-    pub lights : Vec<Light>
+    pub lights : Vec<Light>,
+    pub background : Background,
 }
 
 impl<'a> World {
 
     pub fn new() -> World {
-        World {objects: vec![], lights: vec!()}
+        World {objects: vec![], lights: vec!(), background: Background::default()}
     }
 
     pub fn intersect_world(&self, ray: &Ray) -> Intersections
@@ -36,7 +40,7 @@ impl<'a> World {
 
         for light in self.lights.iter() {
             let in_shadow = self.is_shadowed(light, comps.over_point);
-            let surface = material.lighting(&comps.object, &light, comps.over_point, comps.eyev, comps.normalv, in_shadow);
+            let surface = material.lighting_uv(&comps.object, &light, comps.over_point, comps.eyev, comps.normalv, in_shadow, comps.texture_uv, comps.vertex_color);
             let reflected= self.reflected_color(comps, remaining);
             let refracted= self.refracted_color(comps, remaining);
 
@@ -59,18 +63,56 @@ impl<'a> World {
         self.objects = objects;
     }
 
+    pub fn set_background(&mut self, background : Background) {
+        self.background = background;
+    }
+
     pub fn color_at(&self, ray: &Ray, remaining : i32) -> Color {
+        self.color_and_alpha_at(ray, remaining).0
+    }
+
+    // the color and the coverage of a ray, covered by the objects and by the background unless it is transparent
+    pub fn color_and_alpha_at(&self, ray: &Ray, remaining : i32) -> (Color, Float) {
+        let (color, alpha, _) = self.sample_at(ray, remaining, false);
+        (color, alpha)
+    }
+
+    // the color and the coverage of a ray and, when asked for, its first surface for the output
+    // variables, from the same intersections
+    pub fn sample_at(&self, ray: &Ray, remaining : i32, with_surface: bool) -> (Color, Float, Option<SurfaceSample>) {
         let intersections = self.intersect_world(ray);
         let hit = intersections.hit();
         match hit {
-            None => Color::new(0.0, 0.0, 0.0),
+            None => (self.background.color(ray), self.background.alpha(), None),
             Some(intersection) => {
                 let comps = prepare_computations(intersection, ray, &intersections);
-                self.shade_hit(&comps, remaining)
+                let surface = if with_surface { Some(self.surface_sample(&comps, ray)) } else { None };
+                (self.shade_hit(&comps, remaining), 1.0, surface)
             }
         }
     }
 
+    // the first surface hit by a ray, for the output variables
+    pub fn surface_at(&self, ray: &Ray) -> Option<SurfaceSample> {
+        let intersections = self.intersect_world(ray);
+        let intersection = intersections.hit()?;
+        let comps = prepare_computations(intersection, ray, &intersections);
+        Some(self.surface_sample(&comps, ray))
+    }
+
+    fn surface_sample(&self, comps: &Comps, ray: &Ray) -> SurfaceSample {
+        let albedo = comps.object.material().pattern.pattern_at_object_uv(&comps.object, comps.over_point, comps.texture_uv, comps.vertex_color);
+        let shadowed = self.lights.iter().filter(|light| self.is_shadowed(light, comps.over_point)).count();
+        let shadow = if self.lights.is_empty() { 0.0 } else { shadowed as Float / self.lights.len() as Float };
+        SurfaceSample {
+            depth: comps.t * ray.direction.magnitude(),
+            normal: comps.normalv,
+            object_id: comps.object.object_id,
+            albedo,
+            shadow,
+        }
+    }
+
     pub fn is_shadowed(&self, light: &Light, point: Tuple) -> bool {
         let v = light.position() - point;
         let distance = v.magnitude();